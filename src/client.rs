@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use reqwest::{Client, StatusCode};
 use serde::de::DeserializeOwned;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use urlencoding::encode;
 
@@ -13,7 +19,7 @@ use crate::types::*;
 #[allow(clippy::enum_variant_names)]
 pub enum ApiError {
     #[error("Authentication failed. Check your public and secret keys.")]
-    AuthenticationError,
+    AuthenticationError(u16),
 
     #[error("Resource not found: {0}")]
     NotFoundError(String),
@@ -24,11 +30,359 @@ pub enum ApiError {
     #[error("Request timeout")]
     TimeoutError,
 
-    #[error("API error: {status} - {message}")]
+    #[error("API error {status}: {message}")]
     ApiError { status: u16, message: String },
 
     #[error("Network error: {0}")]
     NetworkError(String),
+
+    #[error("Dry run: request not sent")]
+    DryRun,
+
+    #[error("Missing credentials. Run 'lf config setup' or set environment variables.")]
+    MissingCredentials,
+}
+
+impl ApiError {
+    /// Short machine-readable name for the error, as reported by `--error-format json`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ApiError::AuthenticationError(_) => "authentication",
+            ApiError::NotFoundError(_) => "not_found",
+            ApiError::RateLimitError => "rate_limit",
+            ApiError::TimeoutError => "timeout",
+            ApiError::ApiError { .. } => "api",
+            ApiError::NetworkError(_) => "network",
+            ApiError::DryRun => "dry_run",
+            ApiError::MissingCredentials => "missing_credentials",
+        }
+    }
+
+    /// HTTP status of the response behind the error, if there was one
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::AuthenticationError(status) | ApiError::ApiError { status, .. } => {
+                Some(*status)
+            }
+            ApiError::NotFoundError(_) => Some(404),
+            ApiError::RateLimitError => Some(429),
+            ApiError::TimeoutError
+            | ApiError::NetworkError(_)
+            | ApiError::DryRun
+            | ApiError::MissingCredentials => None,
+        }
+    }
+}
+
+/// Whether `e` is a request held back by `--dry-run`
+pub fn is_dry_run(e: &anyhow::Error) -> bool {
+    matches!(e.downcast_ref::<ApiError>(), Some(ApiError::DryRun))
+}
+
+/// Readable message from an error response body. JSON bodies like
+/// `{"message": "...", "error": [...]}` are summarised as the message plus
+/// each field error; anything else is returned as-is.
+fn error_message(body: &str) -> String {
+    let Ok(Value::Object(json)) = serde_json::from_str::<Value>(body) else {
+        return body.to_string();
+    };
+
+    let message = json.get("message").and_then(Value::as_str);
+    let details: Vec<String> = match json.get("error") {
+        Some(Value::Array(errors)) => errors.iter().map(describe_field_error).collect(),
+        Some(Value::String(error)) => vec![error.clone()],
+        _ => vec![],
+    };
+
+    match (message, details.is_empty()) {
+        (Some(message), true) => message.to_string(),
+        (Some(message), false) => format!("{message} — {}", details.join("; ")),
+        (None, false) => details.join("; "),
+        (None, true) => body.to_string(),
+    }
+}
+
+/// `field `path`: message` for one validation error
+fn describe_field_error(error: &Value) -> String {
+    let message = error
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| error.to_string());
+
+    let path: Vec<String> = error
+        .get("path")
+        .and_then(Value::as_array)
+        .map(|path| {
+            path.iter()
+                .map(|p| p.as_str().map_or_else(|| p.to_string(), str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if path.is_empty() {
+        message
+    } else {
+        format!("field `{}`: {message}", path.join("."))
+    }
+}
+
+/// Sentinel limit for list methods meaning "fetch every page"
+pub const UNLIMITED: u32 = 0;
+
+/// Largest page size the API accepts, and the default page size
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Page size to request for a given overall limit: the configured page size
+/// (capped at the API maximum), or less when the limit is smaller
+fn page_size_for(limit: u32, page_size: u32) -> u32 {
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    if limit == UNLIMITED {
+        page_size
+    } else {
+        std::cmp::min(limit, page_size)
+    }
+}
+
+/// Number of pages fetched at once when the page count is known
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Overall timeout for each HTTP request, in seconds
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Time allowed to establish each connection, in seconds
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// User-Agent sent with every request unless --user-agent overrides it
+pub const DEFAULT_USER_AGENT: &str = concat!("lf/", env!("CARGO_PKG_VERSION"));
+
+/// Header carrying the key that lets the server recognise a repeated create
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// A fresh idempotency key, for creates where the caller didn't supply one
+pub fn new_idempotency_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The connect timeout for a configured number of seconds. The flag rejects
+/// 0, so an unset (zero) value falls back to the default.
+fn connect_timeout(connect_timeout_secs: u64) -> Duration {
+    match connect_timeout_secs {
+        0 => Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS),
+        secs => Duration::from_secs(secs),
+    }
+}
+
+/// The request timeout for a configured number of seconds; 0 means none
+fn request_timeout(timeout_secs: u64) -> Option<Duration> {
+    (timeout_secs > 0).then(|| Duration::from_secs(timeout_secs))
+}
+
+/// Filter params plus the limit/page params for one page request
+fn with_page<'a>(
+    params: &[(&'a str, String)],
+    page: u32,
+    page_size: u32,
+) -> Vec<(&'a str, String)> {
+    let mut params = params.to_vec();
+    params.push(("limit", page_size.to_string()));
+    params.push(("page", page.to_string()));
+    params
+}
+
+/// The oldest timestamp among `traces`, if any have one
+fn oldest_timestamp(traces: &[Trace]) -> Result<Option<DateTime<FixedOffset>>> {
+    let mut oldest: Option<DateTime<FixedOffset>> = None;
+    for trace in traces {
+        let Some(ts) = &trace.timestamp else {
+            continue;
+        };
+        let parsed = DateTime::parse_from_rfc3339(ts)
+            .with_context(|| format!("Trace {} has an invalid timestamp '{ts}'", trace.id))?;
+        oldest = Some(oldest.map_or(parsed, |o| o.min(parsed)));
+    }
+    Ok(oldest)
+}
+
+/// A timestamp as sent in query params, in UTC with milliseconds
+fn timestamp_param(ts: DateTime<FixedOffset>) -> String {
+    ts.with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Query params for the trace list filters
+#[allow(clippy::too_many_arguments)]
+fn trace_params(
+    name: Option<&str>,
+    user_id: Option<&str>,
+    session_id: Option<&str>,
+    tags: Option<&[String]>,
+    from_timestamp: Option<&str>,
+    to_timestamp: Option<&str>,
+    environment: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut params: Vec<(&'static str, String)> = vec![];
+
+    if let Some(n) = name {
+        params.push(("name", n.to_string()));
+    }
+    if let Some(u) = user_id {
+        params.push(("userId", u.to_string()));
+    }
+    if let Some(s) = session_id {
+        params.push(("sessionId", s.to_string()));
+    }
+    if let Some(from) = from_timestamp {
+        params.push(("fromTimestamp", from.to_string()));
+    }
+    if let Some(to) = to_timestamp {
+        params.push(("toTimestamp", to.to_string()));
+    }
+    if let Some(t) = tags {
+        for tag in t {
+            params.push(("tags", tag.clone()));
+        }
+    }
+    if let Some(env) = environment {
+        params.push(("environment", env.to_string()));
+    }
+    params
+}
+
+/// Query params for the observation list filters
+#[allow(clippy::too_many_arguments)]
+fn observation_params(
+    trace_id: Option<&str>,
+    name: Option<&str>,
+    observation_type: Option<&str>,
+    level: Option<&str>,
+    parent_observation_id: Option<&str>,
+    user_id: Option<&str>,
+    from_start_time: Option<&str>,
+    to_start_time: Option<&str>,
+    environment: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut params: Vec<(&'static str, String)> = vec![];
+
+    if let Some(t) = trace_id {
+        params.push(("traceId", t.to_string()));
+    }
+    if let Some(n) = name {
+        params.push(("name", n.to_string()));
+    }
+    if let Some(ot) = observation_type {
+        params.push(("type", ot.to_string()));
+    }
+    if let Some(l) = level {
+        params.push(("level", l.to_string()));
+    }
+    if let Some(p) = parent_observation_id {
+        params.push(("parentObservationId", p.to_string()));
+    }
+    if let Some(u) = user_id {
+        params.push(("userId", u.to_string()));
+    }
+    if let Some(from) = from_start_time {
+        params.push(("fromStartTime", from.to_string()));
+    }
+    if let Some(to) = to_start_time {
+        params.push(("toStartTime", to.to_string()));
+    }
+    if let Some(env) = environment {
+        params.push(("environment", env.to_string()));
+    }
+    params
+}
+
+/// Query params for the score list filters
+fn score_params(
+    name: Option<&str>,
+    trace_id: Option<&str>,
+    observation_id: Option<&str>,
+    from_timestamp: Option<&str>,
+    to_timestamp: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let mut params: Vec<(&'static str, String)> = vec![];
+
+    if let Some(n) = name {
+        params.push(("name", n.to_string()));
+    }
+    if let Some(t) = trace_id {
+        params.push(("traceId", t.to_string()));
+    }
+    if let Some(o) = observation_id {
+        params.push(("observationId", o.to_string()));
+    }
+    if let Some(from) = from_timestamp {
+        params.push(("fromTimestamp", from.to_string()));
+    }
+    if let Some(to) = to_timestamp {
+        params.push(("toTimestamp", to.to_string()));
+    }
+    params
+}
+
+/// How many times a page that exceeds the per-page timeout is retried
+const PAGE_TIMEOUT_RETRIES: u32 = 2;
+
+/// Percent-encode a name for use as a single URL path segment, so that
+/// `/`, spaces and other reserved characters don't change the path
+fn path_segment(name: &str) -> std::borrow::Cow<'_, str> {
+    encode(name)
+}
+
+/// `url` with `params` appended as its query string
+fn with_query(url: &str, params: &[(&str, &str)]) -> String {
+    if params.is_empty() {
+        return url.to_string();
+    }
+    reqwest::Url::parse_with_params(url, params)
+        .map(String::from)
+        .unwrap_or_else(|_| url.to_string())
+}
+
+/// Log an outgoing request for `RUST_LOG=lf=debug`: the method and full URL,
+/// plus the JSON body at trace level. Credentials travel in the
+/// Authorization header, which is never logged.
+fn log_request<B: serde::Serialize>(
+    method: &str,
+    url: &str,
+    params: &[(&str, &str)],
+    body: Option<&B>,
+) {
+    tracing::debug!(method, url = %with_query(url, params), "sending request");
+    if let Some(body) = body {
+        if tracing::enabled!(tracing::Level::TRACE) {
+            let body = serde_json::to_string(body).unwrap_or_default();
+            tracing::trace!(method, url, %body, "request body");
+        }
+    }
+}
+
+/// Log the status of a response at trace level
+fn log_response(method: &str, url: &str, status: StatusCode) {
+    tracing::trace!(method, url, status = status.as_u16(), "received response");
+}
+
+/// Receives lines the client reports as it goes: page progress (stderr) and
+/// dry-run request descriptions (stdout)
+#[derive(Clone)]
+struct LineSink(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl LineSink {
+    fn stderr() -> Self {
+        Self(Arc::new(|line| eprintln!("{line}")))
+    }
+
+    fn stdout() -> Self {
+        Self(Arc::new(|line| println!("{line}")))
+    }
+}
+
+impl std::fmt::Debug for LineSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LineSink")
+    }
 }
 
 /// Langfuse API client
@@ -38,6 +392,13 @@ pub struct LangfuseClient {
     host: String,
     public_key: String,
     secret_key: String,
+    page_timeout: Option<Duration>,
+    concurrency: usize,
+    page_size: u32,
+    progress: bool,
+    progress_sink: LineSink,
+    dry_run: bool,
+    dry_run_sink: LineSink,
 }
 
 impl LangfuseClient {
@@ -52,22 +413,241 @@ impl LangfuseClient {
             .clone()
             .ok_or_else(|| anyhow::anyhow!("Secret key is required"))?;
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .connect_timeout(std::time::Duration::from_secs(10))
-            .build()
-            .context("Failed to create HTTP client")?;
+        let mut builder = Client::builder()
+            .connect_timeout(connect_timeout(config.connect_timeout_secs))
+            .user_agent(config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+        if let Some(timeout) = request_timeout(config.timeout_secs) {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(url) = &config.proxy {
+            let proxy = reqwest::Proxy::all(url)
+                .with_context(|| format!("Invalid proxy URL '{url}'"))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build().context("Failed to create HTTP client")?;
 
         Ok(Self {
             client,
             host: config.host.clone(),
             public_key,
             secret_key,
+            page_timeout: config.page_timeout,
+            concurrency: config.concurrency,
+            page_size: config.page_size,
+            progress: config.progress,
+            progress_sink: LineSink::stderr(),
+            dry_run: config.dry_run,
+            dry_run_sink: LineSink::stdout(),
         })
     }
 
+    /// Send progress lines somewhere other than stderr
+    #[cfg(test)]
+    fn with_progress_sink(mut self, sink: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.progress_sink = LineSink(Arc::new(sink));
+        self
+    }
+
+    /// Send dry-run request descriptions somewhere other than stdout
+    #[cfg(test)]
+    fn with_dry_run_sink(mut self, sink: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.dry_run_sink = LineSink(Arc::new(sink));
+        self
+    }
+
+    /// With `--dry-run`, describe a mutating request instead of sending it:
+    /// the method and URL, then the JSON body. Returns `ApiError::DryRun` so
+    /// the caller stops short of the request.
+    fn hold_for_dry_run<B: serde::Serialize>(
+        &self,
+        method: &str,
+        url: &str,
+        params: &[(&str, &str)],
+        body: Option<&B>,
+    ) -> Result<()> {
+        if !self.dry_run {
+            return Ok(());
+        }
+
+        let mut description = format!("{method} {}", with_query(url, params));
+        if let Some(body) = body {
+            description.push('\n');
+            description.push_str(&serde_json::to_string_pretty(body)?);
+        }
+        (self.dry_run_sink.0)(&description);
+
+        Err(ApiError::DryRun.into())
+    }
+
+    /// Report a fetched page when progress is enabled
+    fn report_page(&self, page: u32, items_so_far: usize) {
+        if self.progress {
+            (self.progress_sink.0)(&format!(
+                "Fetched page {page} ({items_so_far} items so far)"
+            ));
+        }
+    }
+
+    /// Collect up to `limit` items (every item for `UNLIMITED`) starting at `page`.
+    /// The first response reports the page count, so the remaining pages are then
+    /// fetched concurrently, `concurrency` at a time, and kept in page order.
+    async fn paginate<T, F, Fut>(&self, limit: u32, page: u32, fetch: F) -> Result<Vec<T>>
+    where
+        F: Fn(u32, u32) -> Fut,
+        Fut: Future<Output = Result<(Vec<T>, Option<PaginationMeta>)>>,
+    {
+        let page_size = page_size_for(limit, self.page_size);
+        let wanted = (limit != UNLIMITED).then_some(limit as usize);
+        let is_done = |items: &Vec<T>| wanted.is_some_and(|w| items.len() >= w);
+
+        let (mut items, meta) = fetch(page, page_size).await?;
+        self.report_page(page, items.len());
+
+        if !items.is_empty() && !is_done(&items) {
+            match meta.and_then(|m| m.total_pages) {
+                Some(total_pages) => {
+                    let mut last_page = total_pages.max(0) as u32;
+                    if let Some(w) = wanted {
+                        let remaining = (w - items.len()).div_ceil(page_size as usize) as u32;
+                        last_page = last_page.min(page.saturating_add(remaining));
+                    }
+
+                    let mut pages = std::pin::pin!(stream::iter(page + 1..=last_page)
+                        .map(|p| fetch(p, page_size))
+                        .buffered(self.concurrency.max(1)));
+                    let mut current_page = page;
+                    while let Some((data, _)) = pages.try_next().await? {
+                        current_page += 1;
+                        items.extend(data);
+                        self.report_page(current_page, items.len());
+                    }
+                }
+                None => {
+                    // Without a page count, walk pages one at a time until one comes back empty
+                    let mut current_page = page;
+                    while !is_done(&items) {
+                        current_page += 1;
+                        let (data, _) = fetch(current_page, page_size).await?;
+                        if data.is_empty() {
+                            break;
+                        }
+                        items.extend(data);
+                        self.report_page(current_page, items.len());
+                    }
+                }
+            }
+        }
+
+        if let Some(w) = wanted {
+            items.truncate(w);
+        }
+        Ok(items)
+    }
+
+    /// Walk every page from the first, handing each item to `on_item` as its
+    /// page arrives rather than collecting them, so only one page is held in
+    /// memory at a time. Returns the number of items seen.
+    async fn for_each_item<T, F, Fut>(
+        &self,
+        fetch: F,
+        mut on_item: impl FnMut(T) -> Result<()>,
+    ) -> Result<usize>
+    where
+        F: Fn(u32, u32) -> Fut,
+        Fut: Future<Output = Result<(Vec<T>, Option<PaginationMeta>)>>,
+    {
+        let page_size = page_size_for(UNLIMITED, self.page_size);
+        let mut page = 1;
+        let mut seen = 0;
+
+        loop {
+            let (items, meta) = fetch(page, page_size).await?;
+            if items.is_empty() {
+                break;
+            }
+            for item in items {
+                on_item(item)?;
+                seen += 1;
+            }
+            self.report_page(page, seen);
+
+            let total_pages = meta.and_then(|m| m.total_pages);
+            if total_pages.is_some_and(|total| page as i64 >= i64::from(total)) {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(seen)
+    }
+
+    /// Count the items matching `params` from the pagination metadata of a
+    /// single-item page, without parsing the records. Returns None if the API
+    /// doesn't report a total.
+    async fn count(&self, path: &str, params: &[(&str, String)]) -> Result<Option<i32>> {
+        let params = with_page(params, 1, 1);
+        let params_refs: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let response: CountResponse = self.get(path, &params_refs).await?;
+        Ok(response.meta.and_then(|m| m.total_items))
+    }
+
+    /// Fetch one page of a paginated listing. With a per-page timeout set,
+    /// a page that times out is retried up to `PAGE_TIMEOUT_RETRIES` times.
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        self.with_page_retries(|timeout| self.get_with_timeout(path, params, timeout))
+            .await
+    }
+
+    /// Fetch one page of a paginated v2 listing (see `get_page`)
+    async fn get_page_v2<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        self.with_page_retries(|timeout| self.get_v2_with_timeout(path, params, timeout))
+            .await
+    }
+
+    async fn with_page_retries<T, F, Fut>(&self, fetch: F) -> Result<T>
+    where
+        F: Fn(Option<Duration>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(timeout) = self.page_timeout else {
+            return fetch(None).await;
+        };
+
+        let mut attempt = 0;
+        loop {
+            match fetch(Some(timeout)).await {
+                Err(e)
+                    if attempt < PAGE_TIMEOUT_RETRIES
+                        && matches!(e.downcast_ref(), Some(ApiError::TimeoutError)) =>
+                {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Make an authenticated GET request
     async fn get<T: DeserializeOwned>(&self, path: &str, params: &[(&str, &str)]) -> Result<T> {
+        self.get_with_timeout(path, params, None).await
+    }
+
+    async fn get_with_timeout<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        timeout: Option<Duration>,
+    ) -> Result<T> {
         let url = format!("{}/api/public{}", self.host, path);
 
         let mut request = self
@@ -78,7 +658,11 @@ impl LangfuseClient {
         if !params.is_empty() {
             request = request.query(params);
         }
+        if let Some(t) = timeout {
+            request = request.timeout(t);
+        }
 
+        log_request::<Value>("GET", &url, params, None);
         let response = request.send().await.map_err(|e| {
             if e.is_timeout() {
                 ApiError::TimeoutError
@@ -88,6 +672,7 @@ impl LangfuseClient {
         })?;
 
         let status = response.status();
+        log_response("GET", &url, status);
 
         match status {
             StatusCode::OK => {
@@ -98,7 +683,7 @@ impl LangfuseClient {
                 Ok(body)
             }
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -106,7 +691,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -118,6 +703,15 @@ impl LangfuseClient {
 
     /// Make an authenticated GET request to v2 API
     async fn get_v2<T: DeserializeOwned>(&self, path: &str, params: &[(&str, &str)]) -> Result<T> {
+        self.get_v2_with_timeout(path, params, None).await
+    }
+
+    async fn get_v2_with_timeout<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, &str)],
+        timeout: Option<Duration>,
+    ) -> Result<T> {
         let url = format!("{}/api/public/v2{}", self.host, path);
 
         let mut request = self
@@ -128,7 +722,11 @@ impl LangfuseClient {
         if !params.is_empty() {
             request = request.query(params);
         }
+        if let Some(t) = timeout {
+            request = request.timeout(t);
+        }
 
+        log_request::<Value>("GET", &url, params, None);
         let response = request.send().await.map_err(|e| {
             if e.is_timeout() {
                 ApiError::TimeoutError
@@ -138,6 +736,7 @@ impl LangfuseClient {
         })?;
 
         let status = response.status();
+        log_response("GET", &url, status);
 
         match status {
             StatusCode::OK => {
@@ -148,7 +747,7 @@ impl LangfuseClient {
                 Ok(body)
             }
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -156,7 +755,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -171,25 +770,30 @@ impl LangfuseClient {
         &self,
         path: &str,
         body: &B,
+        idempotency_key: Option<&str>,
     ) -> Result<T> {
         let url = format!("{}/api/public/v2{}", self.host, path);
+        self.hold_for_dry_run("POST", &url, &[], Some(body))?;
 
-        let response = self
+        log_request("POST", &url, &[], Some(body));
+        let mut request = self
             .client
             .post(&url)
             .basic_auth(&self.public_key, Some(&self.secret_key))
-            .json(body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ApiError::TimeoutError
-                } else {
-                    ApiError::NetworkError(e.to_string())
-                }
-            })?;
+            .json(body);
+        if let Some(key) = idempotency_key {
+            request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                ApiError::TimeoutError
+            } else {
+                ApiError::NetworkError(e.to_string())
+            }
+        })?;
 
         let status = response.status();
+        log_response("POST", &url, status);
 
         match status {
             StatusCode::OK | StatusCode::CREATED => {
@@ -200,7 +804,7 @@ impl LangfuseClient {
                 Ok(body)
             }
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -208,7 +812,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -225,10 +829,30 @@ impl LangfuseClient {
         body: &B,
     ) -> Result<T> {
         let url = format!("{}/api/public/v2{}", self.host, path);
+        self.patch_url(&url, body).await
+    }
+
+    /// Make an authenticated PATCH request to the v1 API
+    async fn patch<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        let url = format!("{}/api/public{}", self.host, path);
+        self.patch_url(&url, body).await
+    }
+
+    async fn patch_url<T: DeserializeOwned, B: serde::Serialize>(
+        &self,
+        url: &str,
+        body: &B,
+    ) -> Result<T> {
+        self.hold_for_dry_run("PATCH", url, &[], Some(body))?;
 
+        log_request("PATCH", url, &[], Some(body));
         let response = self
             .client
-            .patch(&url)
+            .patch(url)
             .basic_auth(&self.public_key, Some(&self.secret_key))
             .json(body)
             .send()
@@ -242,6 +866,7 @@ impl LangfuseClient {
             })?;
 
         let status = response.status();
+        log_response("PATCH", url, status);
 
         match status {
             StatusCode::OK => {
@@ -252,7 +877,7 @@ impl LangfuseClient {
                 Ok(body)
             }
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -260,7 +885,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -273,16 +898,28 @@ impl LangfuseClient {
     /// Make an authenticated DELETE request to v2 API
     async fn delete_v2(&self, path: &str, params: &[(&str, &str)]) -> Result<()> {
         let url = format!("{}/api/public/v2{}", self.host, path);
+        self.delete_url(&url, params).await
+    }
+
+    /// Make an authenticated DELETE request to the v1 API
+    async fn delete(&self, path: &str, params: &[(&str, &str)]) -> Result<()> {
+        let url = format!("{}/api/public{}", self.host, path);
+        self.delete_url(&url, params).await
+    }
+
+    async fn delete_url(&self, url: &str, params: &[(&str, &str)]) -> Result<()> {
+        self.hold_for_dry_run::<Value>("DELETE", url, params, None)?;
 
         let mut request = self
             .client
-            .delete(&url)
+            .delete(url)
             .basic_auth(&self.public_key, Some(&self.secret_key));
 
         if !params.is_empty() {
             request = request.query(params);
         }
 
+        log_request::<Value>("DELETE", url, params, None);
         let response = request.send().await.map_err(|e| {
             if e.is_timeout() {
                 ApiError::TimeoutError
@@ -292,11 +929,12 @@ impl LangfuseClient {
         })?;
 
         let status = response.status();
+        log_response("DELETE", url, status);
 
         match status {
             StatusCode::NO_CONTENT | StatusCode::OK => Ok(()),
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -304,7 +942,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -319,25 +957,30 @@ impl LangfuseClient {
         &self,
         path: &str,
         body: &B,
+        idempotency_key: Option<&str>,
     ) -> Result<T> {
         let url = format!("{}/api/public{}", self.host, path);
+        self.hold_for_dry_run("POST", &url, &[], Some(body))?;
 
-        let response = self
+        log_request("POST", &url, &[], Some(body));
+        let mut request = self
             .client
             .post(&url)
             .basic_auth(&self.public_key, Some(&self.secret_key))
-            .json(body)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ApiError::TimeoutError
-                } else {
-                    ApiError::NetworkError(e.to_string())
-                }
-            })?;
+            .json(body);
+        if let Some(key) = idempotency_key {
+            request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                ApiError::TimeoutError
+            } else {
+                ApiError::NetworkError(e.to_string())
+            }
+        })?;
 
         let status = response.status();
+        log_response("POST", &url, status);
 
         match status {
             StatusCode::OK | StatusCode::CREATED => {
@@ -348,7 +991,7 @@ impl LangfuseClient {
                 Ok(body)
             }
             StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Err(ApiError::AuthenticationError.into())
+                Err(ApiError::AuthenticationError(status.as_u16()).into())
             }
             StatusCode::NOT_FOUND => {
                 let message = response.text().await.unwrap_or_default();
@@ -356,7 +999,7 @@ impl LangfuseClient {
             }
             StatusCode::TOO_MANY_REQUESTS => Err(ApiError::RateLimitError.into()),
             _ => {
-                let message = response.text().await.unwrap_or_default();
+                let message = error_message(&response.text().await.unwrap_or_default());
                 Err(ApiError::ApiError {
                     status: status.as_u16(),
                     message,
@@ -378,64 +1021,175 @@ impl LangfuseClient {
         tags: Option<&[String]>,
         from_timestamp: Option<&str>,
         to_timestamp: Option<&str>,
+        environment: Option<&str>,
+        order_by: Option<&str>,
         limit: u32,
         page: u32,
     ) -> Result<Vec<Trace>> {
-        let mut all_traces = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
+        let mut params = trace_params(
+            name,
+            user_id,
+            session_id,
+            tags,
+            from_timestamp,
+            to_timestamp,
+            environment,
+        );
+        if let Some(order_by) = order_by {
+            params.push(("orderBy", order_by.to_string()));
+        }
 
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if let Some(n) = name {
-                params.push(("name", n.to_string()));
-            }
-            if let Some(u) = user_id {
-                params.push(("userId", u.to_string()));
+                let response: TracesResponse = self.get_page("/traces", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
-            if let Some(s) = session_id {
-                params.push(("sessionId", s.to_string()));
-            }
-            if let Some(from) = from_timestamp {
-                params.push(("fromTimestamp", from.to_string()));
+        })
+        .await
+    }
+
+    /// List traces newest first without page numbers. Each request asks for
+    /// the first page below a `toTimestamp` boundary just past the oldest trace
+    /// seen so far, so traces arriving mid-listing can't shift later pages and
+    /// cause duplicates or skips. Traces repeated at the boundary are dropped.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn list_traces_stable(
+        &self,
+        name: Option<&str>,
+        user_id: Option<&str>,
+        session_id: Option<&str>,
+        tags: Option<&[String]>,
+        from_timestamp: Option<&str>,
+        to_timestamp: Option<&str>,
+        environment: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<Trace>> {
+        let mut params = trace_params(
+            name,
+            user_id,
+            session_id,
+            tags,
+            from_timestamp,
+            None,
+            environment,
+        );
+        params.push(("orderBy", "timestamp.desc".to_string()));
+
+        let page_size = page_size_for(limit, self.page_size);
+        let wanted = (limit != UNLIMITED).then_some(limit as usize);
+        let mut boundary = to_timestamp.map(str::to_string);
+        let mut seen = HashSet::new();
+        let mut traces: Vec<Trace> = vec![];
+        let mut batch_number = 0;
+
+        while wanted.is_none_or(|w| traces.len() < w) {
+            let mut batch_params = with_page(&params, 1, page_size);
+            if let Some(to) = &boundary {
+                batch_params.push(("toTimestamp", to.clone()));
             }
-            if let Some(to) = to_timestamp {
-                params.push(("toTimestamp", to.to_string()));
+            let params_refs: Vec<(&str, &str)> =
+                batch_params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+            let response: TracesResponse = self.get_page("/traces", &params_refs).await?;
+
+            let batch = response.data;
+            let full_batch = batch.len() >= page_size as usize;
+            let oldest = oldest_timestamp(&batch)?;
+            let before = traces.len();
+            traces.extend(batch.into_iter().filter(|t| seen.insert(t.id.clone())));
+            batch_number += 1;
+            self.report_page(batch_number, traces.len());
+
+            if !full_batch {
+                break;
             }
-            if let Some(t) = tags {
-                for tag in t {
-                    params.push(("tags", tag.clone()));
-                }
+            let Some(oldest) = oldest else {
+                anyhow::bail!("Traces have no timestamps to page by; list them without --stable");
+            };
+            // A full page of traces already seen means at least a page's worth
+            // share the oldest timestamp, so the boundary can't move past them
+            if traces.len() == before {
+                anyhow::bail!(
+                    "At least {page_size} traces share the timestamp {}; raise --page-size to list them with --stable",
+                    timestamp_param(oldest)
+                );
             }
+            // A millisecond past the oldest timestamp, so traces sharing it
+            // are fetched again rather than skipped
+            boundary = Some(timestamp_param(oldest + chrono::Duration::milliseconds(1)));
+        }
 
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: TracesResponse = self.get("/traces", &params_refs).await?;
-
-            all_traces.extend(response.data);
-
-            if all_traces.len() >= limit as usize {
-                all_traces.truncate(limit as usize);
-                break;
-            }
+        if let Some(w) = wanted {
+            traces.truncate(w);
+        }
+        Ok(traces)
+    }
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
+    /// Stream every trace matching the filters to `on_trace`, a page at a time
+    /// (see `for_each_item`). Returns the number of traces.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn export_traces(
+        &self,
+        name: Option<&str>,
+        user_id: Option<&str>,
+        session_id: Option<&str>,
+        tags: Option<&[String]>,
+        from_timestamp: Option<&str>,
+        to_timestamp: Option<&str>,
+        environment: Option<&str>,
+        on_trace: impl FnMut(Trace) -> Result<()>,
+    ) -> Result<usize> {
+        let params = trace_params(
+            name,
+            user_id,
+            session_id,
+            tags,
+            from_timestamp,
+            to_timestamp,
+            environment,
+        );
+
+        self.for_each_item(
+            |page, page_size| {
+                let params = with_page(&params, page, page_size);
+                async move {
+                    let params_refs: Vec<(&str, &str)> =
+                        params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+                    let response: TracesResponse = self.get_page("/traces", &params_refs).await?;
+                    Ok((response.data, response.meta))
                 }
-            }
-
-            current_page += 1;
-        }
+            },
+            on_trace,
+        )
+        .await
+    }
 
-        Ok(all_traces)
+    /// Count traces matching the filters (see `count`)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn count_traces(
+        &self,
+        name: Option<&str>,
+        user_id: Option<&str>,
+        session_id: Option<&str>,
+        tags: Option<&[String]>,
+        from_timestamp: Option<&str>,
+        to_timestamp: Option<&str>,
+        environment: Option<&str>,
+    ) -> Result<Option<i32>> {
+        let params = trace_params(
+            name,
+            user_id,
+            session_id,
+            tags,
+            from_timestamp,
+            to_timestamp,
+            environment,
+        );
+        self.count("/traces", &params).await
     }
 
     /// Get a single trace by ID
@@ -443,6 +1197,12 @@ impl LangfuseClient {
         self.get(&format!("/traces/{id}"), &[]).await
     }
 
+    /// Delete a trace by ID
+    pub async fn delete_trace(&self, id: &str) -> Result<()> {
+        self.delete(&format!("/traces/{}", path_segment(id)), &[])
+            .await
+    }
+
     // ========== Sessions API ==========
 
     /// List sessions with optional filters
@@ -453,47 +1213,26 @@ impl LangfuseClient {
         limit: u32,
         page: u32,
     ) -> Result<Vec<Session>> {
-        let mut all_sessions = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            if let Some(from) = from_timestamp {
-                params.push(("fromTimestamp", from.to_string()));
-            }
-            if let Some(to) = to_timestamp {
-                params.push(("toTimestamp", to.to_string()));
-            }
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: SessionsResponse = self.get("/sessions", &params_refs).await?;
+        let mut params: Vec<(&str, String)> = vec![];
 
-            all_sessions.extend(response.data);
+        if let Some(from) = from_timestamp {
+            params.push(("fromTimestamp", from.to_string()));
+        }
+        if let Some(to) = to_timestamp {
+            params.push(("toTimestamp", to.to_string()));
+        }
 
-            if all_sessions.len() >= limit as usize {
-                all_sessions.truncate(limit as usize);
-                break;
-            }
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
+                let response: SessionsResponse = self.get_page("/sessions", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
-
-            current_page += 1;
-        }
-
-        Ok(all_sessions)
+        })
+        .await
     }
 
     /// Get a single session by ID
@@ -510,65 +1249,67 @@ impl LangfuseClient {
         trace_id: Option<&str>,
         name: Option<&str>,
         observation_type: Option<&str>,
+        level: Option<&str>,
+        parent_observation_id: Option<&str>,
         user_id: Option<&str>,
         from_start_time: Option<&str>,
         to_start_time: Option<&str>,
+        environment: Option<&str>,
         limit: u32,
         page: u32,
     ) -> Result<Vec<Observation>> {
-        let mut all_observations = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            if let Some(t) = trace_id {
-                params.push(("traceId", t.to_string()));
-            }
-            if let Some(n) = name {
-                params.push(("name", n.to_string()));
-            }
-            if let Some(ot) = observation_type {
-                params.push(("type", ot.to_string()));
-            }
-            if let Some(u) = user_id {
-                params.push(("userId", u.to_string()));
-            }
-            if let Some(from) = from_start_time {
-                params.push(("fromStartTime", from.to_string()));
+        let params = observation_params(
+            trace_id,
+            name,
+            observation_type,
+            level,
+            parent_observation_id,
+            user_id,
+            from_start_time,
+            to_start_time,
+            environment,
+        );
+
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+                let response: ObservationsResponse =
+                    self.get_page("/observations", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
-            if let Some(to) = to_start_time {
-                params.push(("toStartTime", to.to_string()));
-            }
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: ObservationsResponse = self.get("/observations", &params_refs).await?;
-
-            all_observations.extend(response.data);
-
-            if all_observations.len() >= limit as usize {
-                all_observations.truncate(limit as usize);
-                break;
-            }
-
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
-            }
-
-            current_page += 1;
-        }
+        })
+        .await
+    }
 
-        Ok(all_observations)
+    /// Count observations matching the filters (see `count`)
+    #[allow(clippy::too_many_arguments)]
+    pub async fn count_observations(
+        &self,
+        trace_id: Option<&str>,
+        name: Option<&str>,
+        observation_type: Option<&str>,
+        level: Option<&str>,
+        parent_observation_id: Option<&str>,
+        user_id: Option<&str>,
+        from_start_time: Option<&str>,
+        to_start_time: Option<&str>,
+        environment: Option<&str>,
+    ) -> Result<Option<i32>> {
+        let params = observation_params(
+            trace_id,
+            name,
+            observation_type,
+            level,
+            parent_observation_id,
+            user_id,
+            from_start_time,
+            to_start_time,
+            environment,
+        );
+        self.count("/observations", &params).await
     }
 
     /// Get a single observation by ID
@@ -579,58 +1320,43 @@ impl LangfuseClient {
     // ========== Scores API ==========
 
     /// List scores with optional filters
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_scores(
         &self,
         name: Option<&str>,
+        trace_id: Option<&str>,
+        observation_id: Option<&str>,
         from_timestamp: Option<&str>,
         to_timestamp: Option<&str>,
         limit: u32,
         page: u32,
     ) -> Result<Vec<Score>> {
-        let mut all_scores = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            if let Some(n) = name {
-                params.push(("name", n.to_string()));
-            }
-            if let Some(from) = from_timestamp {
-                params.push(("fromTimestamp", from.to_string()));
-            }
-            if let Some(to) = to_timestamp {
-                params.push(("toTimestamp", to.to_string()));
-            }
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let params = score_params(name, trace_id, observation_id, from_timestamp, to_timestamp);
 
-            let response: ScoresResponse = self.get("/scores", &params_refs).await?;
-
-            all_scores.extend(response.data);
-
-            if all_scores.len() >= limit as usize {
-                all_scores.truncate(limit as usize);
-                break;
-            }
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
+                let response: ScoresResponse = self.get_page("/scores", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
+        })
+        .await
+    }
 
-            current_page += 1;
-        }
-
-        Ok(all_scores)
+    /// Count scores matching the filters (see `count`)
+    pub async fn count_scores(
+        &self,
+        name: Option<&str>,
+        trace_id: Option<&str>,
+        observation_id: Option<&str>,
+        from_timestamp: Option<&str>,
+        to_timestamp: Option<&str>,
+    ) -> Result<Option<i32>> {
+        let params = score_params(name, trace_id, observation_id, from_timestamp, to_timestamp);
+        self.count("/scores", &params).await
     }
 
     /// Get a single score by ID
@@ -638,23 +1364,36 @@ impl LangfuseClient {
         self.get(&format!("/scores/{id}"), &[]).await
     }
 
-    /// Create a new score
+    /// Delete a score by ID
+    pub async fn delete_score(&self, id: &str) -> Result<()> {
+        self.delete(&format!("/scores/{}", path_segment(id)), &[])
+            .await
+    }
+
+    /// Create a new score. A given `id` is sent as the score's ID and as the
+    /// idempotency key, so repeating the call updates the same score rather
+    /// than adding another.
     #[allow(clippy::too_many_arguments)]
     pub async fn create_score(
         &self,
         name: &str,
-        value: f64,
+        value: &ScoreValue,
         trace_id: Option<&str>,
         observation_id: Option<&str>,
         session_id: Option<&str>,
         data_type: Option<&str>,
         comment: Option<&str>,
+        id: Option<&str>,
     ) -> Result<CreateScoreResponse> {
         let mut body = serde_json::json!({
             "name": name,
-            "value": value,
+            "value": value.to_json(),
         });
 
+        if let Some(id) = id {
+            body["id"] = serde_json::json!(id);
+        }
+
         if let Some(tid) = trace_id {
             body["traceId"] = serde_json::json!(tid);
         }
@@ -671,7 +1410,32 @@ impl LangfuseClient {
             body["comment"] = serde_json::json!(c);
         }
 
-        self.post("/scores", &body).await
+        let key = id.map_or_else(new_idempotency_key, str::to_string);
+        self.post("/scores", &body, Some(&key)).await
+    }
+
+    /// Update a score, sending only the fields that are given
+    pub async fn update_score(
+        &self,
+        id: &str,
+        value: Option<&ScoreValue>,
+        comment: Option<&str>,
+        data_type: Option<&str>,
+    ) -> Result<Score> {
+        let mut body = serde_json::json!({});
+
+        if let Some(v) = value {
+            body["value"] = v.to_json();
+        }
+        if let Some(c) = comment {
+            body["comment"] = serde_json::json!(c);
+        }
+        if let Some(dt) = data_type {
+            body["dataType"] = serde_json::json!(dt);
+        }
+
+        self.patch(&format!("/scores/{}", path_segment(id)), &body)
+            .await
     }
 
     // ========== Metrics API ==========
@@ -688,6 +1452,7 @@ impl LangfuseClient {
         to_timestamp: Option<&str>,
         granularity: Option<&str>,
         limit: Option<u32>,
+        filters: &[MetricsFilter],
     ) -> Result<MetricsResult> {
         let mut body: HashMap<String, serde_json::Value> = HashMap::new();
 
@@ -719,8 +1484,16 @@ impl LangfuseClient {
         if let Some(l) = limit {
             body.insert("limit".to_string(), serde_json::json!(l));
         }
+        if !filters.is_empty() {
+            body.insert("filters".to_string(), serde_json::json!(filters));
+        }
+
+        self.post("/metrics", &body, None).await
+    }
 
-        self.post("/metrics", &body).await
+    /// Run a metrics query from a prebuilt JSON body, sent as-is
+    pub async fn query_metrics_raw(&self, query: &serde_json::Value) -> Result<MetricsResult> {
+        self.post("/metrics", query, None).await
     }
 
     /// Test connectivity (used for config validation)
@@ -745,50 +1518,47 @@ impl LangfuseClient {
         limit: u32,
         page: u32,
     ) -> Result<Vec<PromptMeta>> {
-        let mut all_prompts = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            if let Some(n) = name {
-                params.push(("name", n.to_string()));
-            }
-            if let Some(l) = label {
-                params.push(("label", l.to_string()));
-            }
-            if let Some(t) = tag {
-                params.push(("tag", t.to_string()));
-            }
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let mut params: Vec<(&str, String)> = vec![];
 
-            let response: PromptsResponse = self.get_v2("/prompts", &params_refs).await?;
+        if let Some(n) = name {
+            params.push(("name", n.to_string()));
+        }
+        if let Some(l) = label {
+            params.push(("label", l.to_string()));
+        }
+        if let Some(t) = tag {
+            params.push(("tag", t.to_string()));
+        }
 
-            all_prompts.extend(response.data);
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if all_prompts.len() >= limit as usize {
-                all_prompts.truncate(limit as usize);
-                break;
+                let response: PromptsResponse = self.get_page_v2("/prompts", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
+        })
+        .await
+    }
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
-            }
+    /// List every version of a prompt
+    pub async fn list_prompt_versions(&self, name: &str) -> Result<Vec<Prompt>> {
+        let path = format!("/prompts/{}/versions", path_segment(name));
 
-            current_page += 1;
-        }
+        self.paginate(UNLIMITED, 1, |page, page_size| {
+            let params = with_page(&[], page, page_size);
+            let path = &path;
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-        Ok(all_prompts)
+                let response: PromptVersionsResponse = self.get_page_v2(path, &params_refs).await?;
+                Ok((response.data, response.meta))
+            }
+        })
+        .await
     }
 
     /// Get a specific prompt by name
@@ -809,11 +1579,12 @@ impl LangfuseClient {
 
         let params_refs: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-        self.get_v2(&format!("/prompts/{}", encode(name)), &params_refs)
+        self.get_v2(&format!("/prompts/{}", path_segment(name)), &params_refs)
             .await
     }
 
-    /// Create a text prompt
+    /// Create a text prompt, sent with `idempotency_key` or a fresh one
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_text_prompt(
         &self,
         name: &str,
@@ -822,6 +1593,7 @@ impl LangfuseClient {
         tags: Option<&[String]>,
         config: Option<&serde_json::Value>,
         commit_message: Option<&str>,
+        idempotency_key: Option<&str>,
     ) -> Result<Prompt> {
         let mut body = serde_json::json!({
             "name": name,
@@ -842,19 +1614,29 @@ impl LangfuseClient {
             body["commitMessage"] = serde_json::json!(m);
         }
 
-        self.post_v2("/prompts", &body).await
+        let key = idempotency_key.map_or_else(new_idempotency_key, str::to_string);
+        self.post_v2("/prompts", &body, Some(&key)).await
     }
 
-    /// Create a chat prompt
-    pub async fn create_chat_prompt(
+    /// Create a chat prompt. `messages` is usually `[ChatMessage]`, or raw
+    /// JSON for message shapes the typed struct doesn't model, in which case
+    /// the response is best read back as a `Value` too. Sent with
+    /// `idempotency_key` or a fresh one.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_chat_prompt<M, T>(
         &self,
         name: &str,
-        messages: &[ChatMessage],
+        messages: &M,
         labels: Option<&[String]>,
         tags: Option<&[String]>,
         config: Option<&serde_json::Value>,
         commit_message: Option<&str>,
-    ) -> Result<Prompt> {
+        idempotency_key: Option<&str>,
+    ) -> Result<T>
+    where
+        M: serde::Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
         let mut body = serde_json::json!({
             "name": name,
             "type": "chat",
@@ -874,7 +1656,8 @@ impl LangfuseClient {
             body["commitMessage"] = serde_json::json!(m);
         }
 
-        self.post_v2("/prompts", &body).await
+        let key = idempotency_key.map_or_else(new_idempotency_key, str::to_string);
+        self.post_v2("/prompts", &body, Some(&key)).await
     }
 
     /// Update labels on a prompt version
@@ -889,7 +1672,7 @@ impl LangfuseClient {
         });
 
         self.patch_v2(
-            &format!("/prompts/{}/versions/{}", encode(name), version),
+            &format!("/prompts/{}/versions/{}", path_segment(name), version),
             &body,
         )
         .await
@@ -913,7 +1696,7 @@ impl LangfuseClient {
 
         let params_refs: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-        self.delete_v2(&format!("/prompts/{}", encode(name)), &params_refs)
+        self.delete_v2(&format!("/prompts/{}", path_segment(name)), &params_refs)
             .await
     }
 
@@ -921,45 +1704,32 @@ impl LangfuseClient {
 
     /// List datasets with optional pagination
     pub async fn list_datasets(&self, limit: u32, page: u32) -> Result<Vec<Dataset>> {
-        let mut all_datasets = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
+        let params: Vec<(&str, String)> = vec![];
 
-        loop {
-            let params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: DatasetsResponse = self.get_v2("/datasets", &params_refs).await?;
-
-            all_datasets.extend(response.data);
-
-            if all_datasets.len() >= limit as usize {
-                all_datasets.truncate(limit as usize);
-                break;
-            }
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
+                let response: DatasetsResponse =
+                    self.get_page_v2("/datasets", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
-
-            current_page += 1;
-        }
-
-        Ok(all_datasets)
+        })
+        .await
     }
 
     /// Get a dataset by name
     pub async fn get_dataset(&self, name: &str) -> Result<Dataset> {
-        self.get_v2(&format!("/datasets/{}", name), &[]).await
+        self.get_v2(&format!("/datasets/{}", path_segment(name)), &[])
+            .await
+    }
+
+    /// Delete a dataset by name
+    pub async fn delete_dataset(&self, name: &str) -> Result<()> {
+        self.delete_v2(&format!("/datasets/{}", path_segment(name)), &[])
+            .await
     }
 
     /// Create a new dataset
@@ -980,7 +1750,7 @@ impl LangfuseClient {
             body["metadata"] = m.clone();
         }
 
-        self.post_v2("/datasets", &body).await
+        self.post_v2("/datasets", &body, None).await
     }
 
     // ========== Dataset Items API ==========
@@ -992,49 +1762,30 @@ impl LangfuseClient {
         limit: u32,
         page: u32,
     ) -> Result<Vec<DatasetItem>> {
-        let mut all_items = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let mut params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            if let Some(name) = dataset_name {
-                params.push(("datasetName", name.to_string()));
-            }
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: DatasetItemsResponse = self.get("/dataset-items", &params_refs).await?;
+        let mut params: Vec<(&str, String)> = vec![];
 
-            all_items.extend(response.data);
+        if let Some(name) = dataset_name {
+            params.push(("datasetName", name.to_string()));
+        }
 
-            if all_items.len() >= limit as usize {
-                all_items.truncate(limit as usize);
-                break;
-            }
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
+                let response: DatasetItemsResponse =
+                    self.get_page("/dataset-items", &params_refs).await?;
+                Ok((response.data, response.meta))
             }
-
-            current_page += 1;
-        }
-
-        Ok(all_items)
+        })
+        .await
     }
 
     /// Get a dataset item by ID
     pub async fn get_dataset_item(&self, id: &str) -> Result<DatasetItem> {
-        self.get(&format!("/dataset-items/{}", id), &[]).await
+        self.get(&format!("/dataset-items/{}", path_segment(id)), &[])
+            .await
     }
 
     /// Create a dataset item
@@ -1065,7 +1816,41 @@ impl LangfuseClient {
             body["sourceObservationId"] = serde_json::json!(oid);
         }
 
-        self.post("/dataset-items", &body).await
+        self.post("/dataset-items", &body, None).await
+    }
+
+    /// Update a dataset item. The API upserts on POST when an id is given,
+    /// so only the fields provided here are sent and the rest are kept.
+    pub async fn update_dataset_item(
+        &self,
+        id: &str,
+        dataset_name: &str,
+        input: Option<&serde_json::Value>,
+        expected_output: Option<&serde_json::Value>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<DatasetItem> {
+        let mut body = serde_json::json!({
+            "id": id,
+            "datasetName": dataset_name,
+        });
+
+        if let Some(i) = input {
+            body["input"] = i.clone();
+        }
+        if let Some(eo) = expected_output {
+            body["expectedOutput"] = eo.clone();
+        }
+        if let Some(m) = metadata {
+            body["metadata"] = m.clone();
+        }
+
+        self.post("/dataset-items", &body, None).await
+    }
+
+    /// Delete a dataset item by ID
+    pub async fn delete_dataset_item(&self, id: &str) -> Result<()> {
+        self.delete(&format!("/dataset-items/{}", path_segment(id)), &[])
+            .await
     }
 
     // ========== Dataset Runs API ==========
@@ -1077,73 +1862,137 @@ impl LangfuseClient {
         limit: u32,
         page: u32,
     ) -> Result<Vec<DatasetRun>> {
-        let mut all_runs = Vec::new();
-        let mut current_page = page;
-        let page_size = std::cmp::min(limit, 100);
-
-        loop {
-            let params: Vec<(&str, String)> = vec![
-                ("limit", page_size.to_string()),
-                ("page", current_page.to_string()),
-            ];
-
-            let params_refs: Vec<(&str, &str)> =
-                params.iter().map(|(k, v)| (*k, v.as_str())).collect();
-
-            let response: DatasetRunsResponse = self
-                .get(&format!("/datasets/{}/runs", dataset_name), &params_refs)
-                .await?;
-
-            all_runs.extend(response.data);
-
-            if all_runs.len() >= limit as usize {
-                all_runs.truncate(limit as usize);
-                break;
-            }
-
-            if let Some(meta) = &response.meta {
-                if let Some(total_pages) = meta.total_pages {
-                    if current_page >= total_pages as u32 {
-                        break;
-                    }
-                }
+        let params: Vec<(&str, String)> = vec![];
+
+        self.paginate(limit, page, |page, page_size| {
+            let params = with_page(&params, page, page_size);
+            async move {
+                let params_refs: Vec<(&str, &str)> =
+                    params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+                let response: DatasetRunsResponse = self
+                    .get_page(
+                        &format!("/datasets/{}/runs", path_segment(dataset_name)),
+                        &params_refs,
+                    )
+                    .await?;
+                Ok((response.data, response.meta))
             }
-
-            current_page += 1;
-        }
-
-        Ok(all_runs)
+        })
+        .await
     }
 
     /// Get a dataset run by name
     pub async fn get_dataset_run(&self, dataset_name: &str, run_name: &str) -> Result<DatasetRun> {
         self.get(
-            &format!("/datasets/{}/runs/{}", dataset_name, run_name),
+            &format!(
+                "/datasets/{}/runs/{}",
+                path_segment(dataset_name),
+                path_segment(run_name)
+            ),
             &[],
         )
         .await
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    use wiremock::matchers::{body_json, method, path, query_param};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    fn create_test_config(host: &str) -> Config {
-        Config {
-            public_key: Some("pk-test-123".to_string()),
-            secret_key: Some("sk-test-456".to_string()),
+    /// Create a dataset run explicitly, so it can carry a description and
+    /// metadata before any run items are linked to it
+    pub async fn create_dataset_run(
+        &self,
+        dataset_name: &str,
+        run_name: &str,
+        description: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<DatasetRun> {
+        let mut body = serde_json::json!({
+            "runName": run_name,
+        });
+
+        if let Some(d) = description {
+            body["description"] = serde_json::json!(d);
+        }
+        if let Some(m) = metadata {
+            body["metadata"] = m.clone();
+        }
+
+        self.post(
+            &format!("/datasets/{}/runs", path_segment(dataset_name)),
+            &body,
+            None,
+        )
+        .await
+    }
+
+    /// Create a dataset run item, adding the run if it doesn't exist yet
+    pub async fn create_dataset_run_item(
+        &self,
+        run_name: &str,
+        dataset_item_id: &str,
+        trace_id: Option<&str>,
+        observation_id: Option<&str>,
+        metadata: Option<&serde_json::Value>,
+    ) -> Result<DatasetRunItem> {
+        let mut body = serde_json::json!({
+            "runName": run_name,
+            "datasetItemId": dataset_item_id,
+        });
+
+        if let Some(tid) = trace_id {
+            body["traceId"] = serde_json::json!(tid);
+        }
+        if let Some(oid) = observation_id {
+            body["observationId"] = serde_json::json!(oid);
+        }
+        if let Some(m) = metadata {
+            body["metadata"] = m.clone();
+        }
+
+        self.post("/dataset-run-items", &body, None).await
+    }
+
+    // ========== Projects API ==========
+
+    /// The project(s) the configured keys can access
+    pub async fn get_projects(&self) -> Result<Vec<Project>> {
+        let response: ProjectsResponse = self.get("/projects", &[]).await?;
+        Ok(response.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use wiremock::matchers::{
+        any, body_json, body_partial_json, header, method, path, query_param,
+        query_param_is_missing,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn create_test_config(host: &str) -> Config {
+        Config {
+            public_key: Some("pk-test-123".to_string()),
+            secret_key: Some("sk-test-456".to_string()),
             host: host.to_string(),
             profile: "test".to_string(),
             format: crate::types::OutputFormat::Table,
+            format_set: false,
             limit: 50,
             page: 1,
             output: None,
             verbose: false,
             no_color: false,
+            page_timeout: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            page_size: MAX_PAGE_SIZE,
+            quiet: false,
+            progress: false,
+            dry_run: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            proxy: None,
+            user_agent: None,
         }
     }
 
@@ -1162,6 +2011,75 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_client_new_with_custom_timeout() {
+        for timeout_secs in [120, 0] {
+            let config = Config {
+                public_key: Some("pk-test".to_string()),
+                secret_key: Some("sk-test".to_string()),
+                timeout_secs,
+                ..Default::default()
+            };
+
+            assert!(LangfuseClient::new(&config).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_client_new_with_custom_connect_timeout() {
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            connect_timeout_secs: 60,
+            ..Default::default()
+        };
+
+        assert!(LangfuseClient::new(&config).is_ok());
+    }
+
+    #[test]
+    fn test_client_new_with_proxy() {
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            proxy: Some("http://proxy.internal:3128".to_string()),
+            ..Default::default()
+        };
+
+        assert!(LangfuseClient::new(&config).is_ok());
+    }
+
+    #[test]
+    fn test_client_new_with_invalid_proxy() {
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            proxy: Some("http://[not-a-host".to_string()),
+            ..Default::default()
+        };
+
+        let err = LangfuseClient::new(&config).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid proxy URL 'http://[not-a-host'"));
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        assert_eq!(request_timeout(30), Some(Duration::from_secs(30)));
+        assert_eq!(request_timeout(600), Some(Duration::from_secs(600)));
+        assert_eq!(request_timeout(0), None);
+    }
+
+    #[test]
+    fn test_connect_timeout() {
+        assert_eq!(connect_timeout(45), Duration::from_secs(45));
+        assert_eq!(
+            connect_timeout(0),
+            Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS)
+        );
+    }
+
     #[test]
     fn test_client_new_missing_public_key() {
         let config = Config {
@@ -1190,11 +2108,49 @@ mod tests {
         assert!(client.unwrap_err().to_string().contains("Secret key"));
     }
 
+    #[tokio::test]
+    async fn test_default_user_agent() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces/trace-1"))
+            .and(header("user-agent", DEFAULT_USER_AGENT))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "trace-1"})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        client.get_trace("trace-1").await.unwrap();
+        assert!(DEFAULT_USER_AGENT.starts_with("lf/"));
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_override() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces/trace-1"))
+            .and(header("user-agent", "gateway-approved/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "trace-1"})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config(&mock_server.uri());
+        config.user_agent = Some("gateway-approved/1.0".to_string());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        client.get_trace("trace-1").await.unwrap();
+    }
+
     // ========== API Error Tests ==========
 
     #[test]
     fn test_api_error_display() {
-        let auth_err = ApiError::AuthenticationError;
+        let auth_err = ApiError::AuthenticationError(401);
         assert!(auth_err.to_string().contains("Authentication failed"));
 
         let not_found = ApiError::NotFoundError("trace-123".to_string());
@@ -1217,6 +2173,52 @@ mod tests {
         assert!(network_err.to_string().contains("Connection refused"));
     }
 
+    #[test]
+    fn test_error_message_formats_structured_body() {
+        let body = r#"{"message":"Invalid request data","error":[{"code":"invalid_type","expected":"array","received":"string","path":["newLabels"],"message":"expected array"}]}"#;
+        assert_eq!(
+            error_message(body),
+            "Invalid request data — field `newLabels`: expected array"
+        );
+
+        assert_eq!(error_message(r#"{"message":"Boom"}"#), "Boom");
+        assert_eq!(error_message(r#"{"error":"Bad filter"}"#), "Bad filter");
+    }
+
+    #[test]
+    fn test_error_message_falls_back_to_raw_text() {
+        assert_eq!(
+            error_message("Internal Server Error"),
+            "Internal Server Error"
+        );
+        assert_eq!(error_message(r#"{"detail":"x"}"#), r#"{"detail":"x"}"#);
+        assert_eq!(error_message(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_structured_400_error_is_formatted() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces/trace-1"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "message": "Invalid request data",
+                "error": [{"path": ["fields", 0], "message": "unknown field"}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let err = client.get_trace("trace-1").await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "API error 400: Invalid request data — field `fields.0`: unknown field"
+        );
+    }
+
     // ========== Traces API Tests ==========
 
     #[tokio::test]
@@ -1246,7 +2248,7 @@ mod tests {
         let client = LangfuseClient::new(&config).unwrap();
 
         let traces = client
-            .list_traces(None, None, None, None, None, None, 50, 1)
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
             .await
             .unwrap();
 
@@ -1256,250 +2258,382 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_list_traces_with_filters() {
+    async fn test_list_traces_unlimited_fetches_every_page() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .and(query_param("name", "my-trace"))
-            .and(query_param("userId", "user-123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [{"id": "trace-1"}],
-                "meta": {"totalPages": 1}
-            })))
-            .mount(&mock_server)
-            .await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("limit", "100"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": [{"id": format!("trace-{page}")}],
+                    "meta": {"page": page, "limit": 100, "totalItems": 3, "totalPages": 3}
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
         let traces = client
-            .list_traces(
-                Some("my-trace"),
-                Some("user-123"),
-                None,
-                None,
-                None,
-                None,
-                50,
-                1,
-            )
+            .list_traces(None, None, None, None, None, None, None, None, UNLIMITED, 1)
             .await
             .unwrap();
 
-        assert_eq!(traces.len(), 1);
+        let ids: Vec<_> = traces.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["trace-1", "trace-2", "trace-3"]);
     }
 
-    #[tokio::test]
-    async fn test_get_trace_success() {
+    /// Three pages of one trace each, and a client whose progress lines are captured
+    async fn paged_client(progress: bool) -> (MockServer, LangfuseClient, Arc<Mutex<Vec<String>>>) {
         let mock_server = MockServer::start().await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": [{"id": format!("trace-{page}")}],
+                    "meta": {"page": page, "limit": 100, "totalItems": 3, "totalPages": 3}
+                })))
+                .mount(&mock_server)
+                .await;
+        }
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/traces/trace-123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "trace-123",
-                "name": "My Trace",
-                "userId": "user-456"
-            })))
-            .mount(&mock_server)
-            .await;
+        let config = Config {
+            progress,
+            ..create_test_config(&mock_server.uri())
+        };
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let client = LangfuseClient::new(&config)
+            .unwrap()
+            .with_progress_sink(move |line| sink.lock().unwrap().push(line.to_string()));
+        (mock_server, client, lines)
+    }
 
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
+    #[tokio::test]
+    async fn test_list_reports_progress_per_page() {
+        let (_server, client, lines) = paged_client(true).await;
 
-        let trace = client.get_trace("trace-123").await.unwrap();
+        client
+            .list_traces(None, None, None, None, None, None, None, None, UNLIMITED, 1)
+            .await
+            .unwrap();
 
-        assert_eq!(trace.id, "trace-123");
-        assert_eq!(trace.name, Some("My Trace".to_string()));
+        assert_eq!(
+            *lines.lock().unwrap(),
+            [
+                "Fetched page 1 (1 items so far)",
+                "Fetched page 2 (2 items so far)",
+                "Fetched page 3 (3 items so far)"
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_get_trace_not_found() {
-        let mock_server = MockServer::start().await;
-
-        Mock::given(method("GET"))
-            .and(path("/api/public/traces/nonexistent"))
-            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
-            .mount(&mock_server)
-            .await;
-
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
+    async fn test_list_reports_nothing_without_progress() {
+        let (_server, client, lines) = paged_client(false).await;
 
-        let result = client.get_trace("nonexistent").await;
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, UNLIMITED, 1)
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
-        let err = result.unwrap_err();
-        assert!(err.to_string().contains("not found") || err.to_string().contains("Not found"));
+        assert_eq!(traces.len(), 3);
+        assert!(lines.lock().unwrap().is_empty());
     }
 
-    // ========== Sessions API Tests ==========
-
-    #[tokio::test]
-    async fn test_list_sessions_success() {
+    /// A dry-run client against a server that must not be called, with its
+    /// request descriptions captured
+    async fn dry_run_client() -> (MockServer, LangfuseClient, Arc<Mutex<Vec<String>>>) {
         let mock_server = MockServer::start().await;
-
-        Mock::given(method("GET"))
-            .and(path("/api/public/sessions"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "session-1", "createdAt": "2024-01-15T10:00:00Z"},
-                    {"id": "session-2", "createdAt": "2024-01-16T10:00:00Z"}
-                ],
-                "meta": {"totalPages": 1}
-            })))
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
             .mount(&mock_server)
             .await;
 
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
+        let config = Config {
+            dry_run: true,
+            ..create_test_config(&mock_server.uri())
+        };
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        let client = LangfuseClient::new(&config)
+            .unwrap()
+            .with_dry_run_sink(move |line| sink.lock().unwrap().push(line.to_string()));
+        (mock_server, client, lines)
+    }
 
-        let sessions = client.list_sessions(None, None, 50, 1).await.unwrap();
+    #[tokio::test]
+    async fn test_dry_run_prints_body_without_sending() {
+        let (mock_server, client, lines) = dry_run_client().await;
 
-        assert_eq!(sessions.len(), 2);
-        assert_eq!(sessions[0].id, "session-1");
+        let err = client
+            .create_score(
+                "accuracy",
+                &ScoreValue::Numeric(0.9),
+                Some("trace-1"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(is_dry_run(&err));
+        let lines = lines.lock().unwrap();
+        assert_eq!(lines.len(), 1);
+        let (request_line, body) = lines[0].split_once('\n').unwrap();
+        assert_eq!(
+            request_line,
+            format!("POST {}/api/public/scores", mock_server.uri())
+        );
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["name"], "accuracy");
+        assert_eq!(body["traceId"], "trace-1");
     }
 
     #[tokio::test]
-    async fn test_get_session_success() {
-        let mock_server = MockServer::start().await;
-
-        Mock::given(method("GET"))
-            .and(path("/api/public/sessions/session-123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "session-123",
-                "createdAt": "2024-01-15T10:00:00Z",
-                "projectId": "project-456"
-            })))
-            .mount(&mock_server)
-            .await;
+    async fn test_dry_run_delete_prints_url_only() {
+        let (mock_server, client, lines) = dry_run_client().await;
+
+        let err = client.delete_trace("trace-1").await.unwrap_err();
+
+        assert!(is_dry_run(&err));
+        assert_eq!(
+            *lines.lock().unwrap(),
+            [format!(
+                "DELETE {}/api/public/traces/trace-1",
+                mock_server.uri()
+            )]
+        );
+    }
 
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
+    /// Log output captured from a tracing subscriber
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
 
-        let session = client.get_session("session-123").await.unwrap();
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
 
-        assert_eq!(session.id, "session-123");
-        assert_eq!(session.project_id, Some("project-456".to_string()));
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
     }
 
-    // ========== Observations API Tests ==========
-
     #[tokio::test]
-    async fn test_list_observations_success() {
+    async fn test_request_logging_never_includes_secret_key() {
         let mock_server = MockServer::start().await;
-
-        Mock::given(method("GET"))
-            .and(path("/api/public/observations"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "obs-1", "type": "GENERATION", "name": "completion"},
-                    {"id": "obs-2", "type": "SPAN", "name": "process"}
-                ],
-                "meta": {"totalPages": 1}
-            })))
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "score-1"})))
             .mount(&mock_server)
             .await;
 
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let client = LangfuseClient::new(&create_test_config(&mock_server.uri())).unwrap();
+        client
+            .create_score(
+                "accuracy",
+                &ScoreValue::Numeric(0.9),
+                Some("trace-1"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains(&format!("{}/api/public/scores", mock_server.uri())));
+        assert!(logs.contains("\"name\":\"accuracy\""));
+        assert!(logs.contains("status=200"));
+        assert!(!logs.contains("sk-test-456"));
+        assert!(!logs.to_lowercase().contains("authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_list_traces_concurrent_pages_keep_page_order() {
+        let mock_server = MockServer::start().await;
+
+        // Earlier pages respond slowest, so they complete out of order
+        for page in 1..=5u64 {
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({
+                            "data": [
+                                {"id": format!("trace-{page}a")},
+                                {"id": format!("trace-{page}b")}
+                            ],
+                            "meta": {"page": page, "limit": 2, "totalItems": 10, "totalPages": 5}
+                        }))
+                        .set_delay(Duration::from_millis(if page == 1 {
+                            0
+                        } else {
+                            (6 - page) * 40
+                        })),
+                )
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let observations = client
-            .list_observations(None, None, None, None, None, None, 50, 1)
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, UNLIMITED, 1)
             .await
             .unwrap();
 
-        assert_eq!(observations.len(), 2);
-        assert_eq!(observations[0].r#type, Some("GENERATION".to_string()));
+        let ids: Vec<_> = traces.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "trace-1a", "trace-1b", "trace-2a", "trace-2b", "trace-3a", "trace-3b", "trace-4a",
+                "trace-4b", "trace-5a", "trace-5b"
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_list_observations_with_trace_filter() {
+    async fn test_list_traces_only_fetches_pages_needed_for_limit() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/observations"))
-            .and(query_param("traceId", "trace-123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [{"id": "obs-1", "traceId": "trace-123"}],
-                "meta": {"totalPages": 1}
-            })))
-            .mount(&mock_server)
-            .await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": (0..100).map(|i| json!({"id": format!("trace-{page}-{i}")})).collect::<Vec<_>>(),
+                    "meta": {"page": page, "limit": 100, "totalItems": 1000, "totalPages": 10}
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let observations = client
-            .list_observations(Some("trace-123"), None, None, None, None, None, 50, 1)
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, 250, 1)
             .await
             .unwrap();
 
-        assert_eq!(observations.len(), 1);
-        assert_eq!(observations[0].trace_id, Some("trace-123".to_string()));
+        assert_eq!(traces.len(), 250);
+        assert_eq!(traces[249].id, "trace-3-49");
     }
 
     #[tokio::test]
-    async fn test_get_observation_success() {
+    async fn test_list_traces_small_page_size_with_large_limit() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/observations/obs-123"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "obs-123",
-                "type": "GENERATION",
-                "model": "gpt-4"
-            })))
-            .mount(&mock_server)
-            .await;
+        for page in 1..=3 {
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page.to_string()))
+                .and(query_param("limit", "20"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": (0..20).map(|i| json!({"id": format!("trace-{page}-{i}")})).collect::<Vec<_>>(),
+                    "meta": {"page": page, "limit": 20, "totalItems": 50, "totalPages": 3}
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
 
-        let config = create_test_config(&mock_server.uri());
+        let mut config = create_test_config(&mock_server.uri());
+        config.page_size = 20;
         let client = LangfuseClient::new(&config).unwrap();
 
-        let observation = client.get_observation("obs-123").await.unwrap();
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, 5000, 1)
+            .await
+            .unwrap();
 
-        assert_eq!(observation.id, "obs-123");
-        assert_eq!(observation.model, Some("gpt-4".to_string()));
+        assert_eq!(traces.len(), 60);
+        assert_eq!(traces[59].id, "trace-3-19");
     }
 
-    // ========== Scores API Tests ==========
+    #[test]
+    fn test_page_size_for() {
+        assert_eq!(page_size_for(UNLIMITED, MAX_PAGE_SIZE), 100);
+        assert_eq!(page_size_for(5000, 25), 25);
+        assert_eq!(page_size_for(10, 25), 10);
+        assert_eq!(page_size_for(UNLIMITED, 500), MAX_PAGE_SIZE);
+    }
 
     #[tokio::test]
-    async fn test_list_scores_success() {
+    async fn test_list_traces_retries_page_after_per_page_timeout() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/scores"))
+            .and(path("/api/public/traces"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"data": [], "meta": {"totalPages": 1}}))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "score-1", "name": "accuracy", "value": 0.95},
-                    {"id": "score-2", "name": "relevance", "value": 0.88}
-                ],
+                "data": [{"id": "trace-1"}],
                 "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
 
-        let config = create_test_config(&mock_server.uri());
+        let mut config = create_test_config(&mock_server.uri());
+        config.page_timeout = Some(Duration::from_millis(200));
         let client = LangfuseClient::new(&config).unwrap();
 
-        let scores = client.list_scores(None, None, None, 50, 1).await.unwrap();
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await
+            .unwrap();
 
-        assert_eq!(scores.len(), 2);
-        assert_eq!(scores[0].name, Some("accuracy".to_string()));
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].id, "trace-1");
     }
 
     #[tokio::test]
-    async fn test_get_score_success() {
+    async fn test_list_traces_with_filters() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/scores/score-123"))
+            .and(path("/api/public/traces"))
+            .and(query_param("name", "my-trace"))
+            .and(query_param("userId", "user-123"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "score-123",
-                "name": "accuracy",
-                "value": 0.95,
-                "source": "API"
+                "data": [{"id": "trace-1"}],
+                "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
@@ -1507,184 +2641,207 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let score = client.get_score("score-123").await.unwrap();
+        let traces = client
+            .list_traces(
+                Some("my-trace"),
+                Some("user-123"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                50,
+                1,
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(score.id, "score-123");
-        assert_eq!(score.source, Some("API".to_string()));
+        assert_eq!(traces.len(), 1);
     }
 
-    // ========== Metrics API Tests ==========
-
     #[tokio::test]
-    async fn test_query_metrics_success() {
+    async fn test_list_traces_with_environment() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/metrics"))
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("environment", "staging"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"timestamp": "2024-01-15", "count": 100},
-                    {"timestamp": "2024-01-16", "count": 150}
-                ]
+                "data": [{"id": "trace-1", "environment": "staging"}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .query_metrics("traces", "count", "count", None, None, None, None, None)
+        let traces = client
+            .list_traces(
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("staging"),
+                None,
+                50,
+                1,
+            )
             .await
             .unwrap();
 
-        assert_eq!(result.data.len(), 2);
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].environment, Some("staging".to_string()));
     }
 
     #[tokio::test]
-    async fn test_query_metrics_with_dimensions() {
+    async fn test_list_traces_forwards_order_by() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/metrics"))
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("orderBy", "timestamp.asc"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"model": "gpt-4", "count": 50},
-                    {"model": "gpt-3.5", "count": 100}
-                ]
+                "data": [{"id": "oldest"}, {"id": "newer"}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let dimensions = vec!["model".to_string()];
-        let result = client
-            .query_metrics(
-                "observations",
-                "count",
-                "count",
-                Some(&dimensions),
+        let traces = client
+            .list_traces(
+                None,
+                None,
                 None,
                 None,
                 None,
                 None,
+                None,
+                Some(SortOrder::Asc.to_order_by()),
+                50,
+                1,
             )
             .await
             .unwrap();
 
-        assert_eq!(result.data.len(), 2);
+        let ids: Vec<_> = traces.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["oldest", "newer"]);
     }
 
-    // ========== Authentication Tests ==========
-
     #[tokio::test]
-    async fn test_authentication_failure() {
+    async fn test_count_traces_reads_total_items() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
             .and(path("/api/public/traces"))
-            .respond_with(ResponseTemplate::new(401))
+            .and(query_param("limit", "1"))
+            .and(query_param("sessionId", "session-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "trace-1"}],
+                "meta": {"page": 1, "limit": 1, "totalItems": 17, "totalPages": 17}
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .list_traces(None, None, None, None, None, None, 50, 1)
-            .await;
+        let count = client
+            .count_traces(None, None, Some("session-1"), None, None, None, None)
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Authentication failed"));
+        assert_eq!(count, Some(17));
     }
 
     #[tokio::test]
-    async fn test_forbidden_error() {
+    async fn test_count_traces_without_meta() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
             .and(path("/api/public/traces"))
-            .respond_with(ResponseTemplate::new(403))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": []
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .list_traces(None, None, None, None, None, None, 50, 1)
-            .await;
+        let count = client
+            .count_traces(None, None, None, None, None, None, None)
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Authentication failed"));
+        assert_eq!(count, None);
     }
 
-    // ========== Rate Limiting Tests ==========
-
     #[tokio::test]
-    async fn test_rate_limit_error() {
+    async fn test_get_trace_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .respond_with(ResponseTemplate::new(429))
+            .and(path("/api/public/traces/trace-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "trace-123",
+                "name": "My Trace",
+                "userId": "user-456"
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .list_traces(None, None, None, None, None, None, 50, 1)
-            .await;
+        let trace = client.get_trace("trace-123").await.unwrap();
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Rate limit"));
+        assert_eq!(trace.id, "trace-123");
+        assert_eq!(trace.name, Some("My Trace".to_string()));
     }
 
-    // ========== Server Error Tests ==========
-
     #[tokio::test]
-    async fn test_server_error() {
+    async fn test_get_trace_not_found() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .and(path("/api/public/traces/nonexistent"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .list_traces(None, None, None, None, None, None, 50, 1)
-            .await;
+        let result = client.get_trace("nonexistent").await;
 
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("500"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("not found") || err.to_string().contains("Not found"));
     }
 
-    // ========== Test Connection Tests ==========
+    // ========== Sessions API Tests ==========
 
     #[tokio::test]
-    async fn test_connection_success() {
+    async fn test_list_sessions_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .and(query_param("limit", "1"))
+            .and(path("/api/public/sessions"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [],
-                "meta": {}
+                "data": [
+                    {"id": "session-1", "createdAt": "2024-01-15T10:00:00Z"},
+                    {"id": "session-2", "createdAt": "2024-01-16T10:00:00Z"}
+                ],
+                "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
@@ -1692,51 +2849,49 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.test_connection().await;
+        let sessions = client.list_sessions(None, None, 50, 1).await.unwrap();
 
-        assert!(result.is_ok());
-        assert!(result.unwrap());
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].id, "session-1");
     }
 
     #[tokio::test]
-    async fn test_connection_failure() {
+    async fn test_get_session_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .respond_with(ResponseTemplate::new(401))
+            .and(path("/api/public/sessions/session-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "session-123",
+                "createdAt": "2024-01-15T10:00:00Z",
+                "projectId": "project-456"
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.test_connection().await;
+        let session = client.get_session("session-123").await.unwrap();
 
-        assert!(result.is_err());
+        assert_eq!(session.id, "session-123");
+        assert_eq!(session.project_id, Some("project-456".to_string()));
     }
 
-    // ========== Prompts API Tests ==========
+    // ========== Observations API Tests ==========
 
     #[tokio::test]
-    async fn test_list_prompts_success() {
+    async fn test_list_observations_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts"))
-            .and(query_param("limit", "50"))
-            .and(query_param("page", "1"))
+            .and(path("/api/public/observations"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
                 "data": [
-                    {"name": "prompt-1", "versions": [1, 2], "labels": ["production"], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"},
-                    {"name": "prompt-2", "versions": [1], "labels": [], "tags": ["test"], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
+                    {"id": "obs-1", "type": "GENERATION", "name": "completion"},
+                    {"id": "obs-2", "type": "SPAN", "name": "process"}
                 ],
-                "meta": {
-                    "page": 1,
-                    "limit": 50,
-                    "totalItems": 2,
-                    "totalPages": 1
-                }
+                "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
@@ -1744,23 +2899,24 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompts = client.list_prompts(None, None, None, 50, 1).await.unwrap();
+        let observations = client
+            .list_observations(None, None, None, None, None, None, None, None, None, 50, 1)
+            .await
+            .unwrap();
 
-        assert_eq!(prompts.len(), 2);
-        assert_eq!(prompts[0].name, "prompt-1");
-        assert_eq!(prompts[0].versions, vec![1, 2]);
+        assert_eq!(observations.len(), 2);
+        assert_eq!(observations[0].r#type, Some("GENERATION".to_string()));
     }
 
     #[tokio::test]
-    async fn test_list_prompts_with_filters() {
+    async fn test_list_observations_with_trace_filter() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts"))
-            .and(query_param("name", "welcome"))
-            .and(query_param("label", "production"))
+            .and(path("/api/public/observations"))
+            .and(query_param("traceId", "trace-123"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [{"name": "welcome", "versions": [1], "labels": ["production"], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}],
+                "data": [{"id": "obs-1", "traceId": "trace-123"}],
                 "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
@@ -1769,108 +2925,116 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompts = client
-            .list_prompts(Some("welcome"), Some("production"), None, 50, 1)
+        let observations = client
+            .list_observations(
+                Some("trace-123"),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                50,
+                1,
+            )
             .await
             .unwrap();
 
-        assert_eq!(prompts.len(), 1);
-        assert_eq!(prompts[0].name, "welcome");
+        assert_eq!(observations.len(), 1);
+        assert_eq!(observations[0].trace_id, Some("trace-123".to_string()));
     }
 
     #[tokio::test]
-    async fn test_get_prompt_success() {
+    async fn test_list_observations_with_level_and_parent_filters() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts/welcome"))
+            .and(path("/api/public/observations"))
+            .and(query_param("level", "ERROR"))
+            .and(query_param("parentObservationId", "obs-parent"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "welcome",
-                "version": 2,
-                "type": "text",
-                "prompt": "Hello {{name}}!",
-                "labels": ["production"],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [{"id": "obs-1", "level": "ERROR", "parentObservationId": "obs-parent"}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client.get_prompt("welcome", None, None).await.unwrap();
+        let observations = client
+            .list_observations(
+                None,
+                None,
+                None,
+                Some("ERROR"),
+                Some("obs-parent"),
+                None,
+                None,
+                None,
+                None,
+                50,
+                1,
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(prompt.name, "welcome");
-        assert_eq!(prompt.version, 2);
+        assert_eq!(observations.len(), 1);
+        assert_eq!(observations[0].level, Some("ERROR".to_string()));
     }
 
     #[tokio::test]
-    async fn test_get_prompt_with_version() {
+    async fn test_list_observations_with_environment() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts/welcome"))
-            .and(query_param("version", "1"))
+            .and(path("/api/public/observations"))
+            .and(query_param("environment", "production"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "welcome",
-                "version": 1,
-                "type": "text",
-                "prompt": "Hi {{name}}!",
-                "labels": [],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [{"id": "obs-1", "environment": "production"}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client.get_prompt("welcome", Some(1), None).await.unwrap();
+        let observations = client
+            .list_observations(
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some("production"),
+                50,
+                1,
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(prompt.version, 1);
+        assert_eq!(observations.len(), 1);
+        assert_eq!(observations[0].environment, Some("production".to_string()));
     }
 
-    // ========== Pagination Tests ==========
-
     #[tokio::test]
-    async fn test_list_traces_pagination() {
+    async fn test_get_observation_success() {
         let mock_server = MockServer::start().await;
 
-        // First page - server returns only 2 items despite our limit=3
-        Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .and(query_param("page", "1"))
-            .and(query_param("limit", "3"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "trace-1"},
-                    {"id": "trace-2"}
-                ],
-                "meta": {
-                    "page": 1,
-                    "totalPages": 2
-                }
-            })))
-            .mount(&mock_server)
-            .await;
-
-        // Second page
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
-            .and(query_param("page", "2"))
-            .and(query_param("limit", "3"))
+            .and(path("/api/public/observations/obs-123"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "trace-3"}
-                ],
-                "meta": {
-                    "page": 2,
-                    "totalPages": 2
-                }
+                "id": "obs-123",
+                "type": "GENERATION",
+                "model": "gpt-4"
             })))
             .mount(&mock_server)
             .await;
@@ -1878,28 +3042,24 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        // Request 3 items, should fetch both pages
-        let traces = client
-            .list_traces(None, None, None, None, None, None, 3, 1)
-            .await
-            .unwrap();
+        let observation = client.get_observation("obs-123").await.unwrap();
 
-        assert_eq!(traces.len(), 3);
-        assert_eq!(traces[0].id, "trace-1");
-        assert_eq!(traces[2].id, "trace-3");
+        assert_eq!(observation.id, "obs-123");
+        assert_eq!(observation.model, Some("gpt-4".to_string()));
     }
 
+    // ========== Scores API Tests ==========
+
     #[tokio::test]
-    async fn test_list_traces_limit_truncation() {
+    async fn test_list_scores_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/traces"))
+            .and(path("/api/public/scores"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
                 "data": [
-                    {"id": "trace-1"},
-                    {"id": "trace-2"},
-                    {"id": "trace-3"}
+                    {"id": "score-1", "name": "accuracy", "value": 0.95},
+                    {"id": "score-2", "name": "relevance", "value": 0.88}
                 ],
                 "meta": {"totalPages": 1}
             })))
@@ -1909,106 +3069,1445 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        // Request only 2 items
-        let traces = client
-            .list_traces(None, None, None, None, None, None, 2, 1)
+        let scores = client
+            .list_scores(None, None, None, None, None, 50, 1)
             .await
             .unwrap();
 
-        assert_eq!(traces.len(), 2);
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].name, Some("accuracy".to_string()));
     }
 
-    // ========== Prompts Create/Update/Delete Tests ==========
-
     #[tokio::test]
-    async fn test_create_text_prompt_success() {
+    async fn test_list_scores_filters_by_trace_id() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/v2/prompts"))
+        Mock::given(method("GET"))
+            .and(path("/api/public/scores"))
+            .and(query_param("traceId", "trace-1"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "greeting",
-                "version": 1,
-                "type": "text",
-                "prompt": "Hello {{name}}!",
-                "labels": ["staging"],
-                "tags": ["test"],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [{"id": "score-1", "traceId": "trace-1", "name": "accuracy", "value": 0.9}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
-
-        let prompt = client
-            .create_text_prompt(
-                "greeting",
-                "Hello {{name}}!",
-                Some(&["staging".to_string()]),
-                Some(&["test".to_string()]),
-                None,
-                None,
-            )
+        let client = LangfuseClient::new(&create_test_config(&mock_server.uri())).unwrap();
+        let scores = client
+            .list_scores(None, Some("trace-1"), None, None, None, 50, 1)
             .await
             .unwrap();
 
-        assert_eq!(prompt.name, "greeting");
-        assert_eq!(prompt.version, 1);
+        assert_eq!(scores.len(), 1);
     }
 
     #[tokio::test]
-    async fn test_create_chat_prompt_success() {
+    async fn test_list_scores_filters_by_observation_id() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/v2/prompts"))
+        Mock::given(method("GET"))
+            .and(path("/api/public/scores"))
+            .and(query_param("observationId", "obs-1"))
+            .and(query_param("traceId", "trace-1"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "assistant",
-                "version": 1,
-                "type": "chat",
-                "prompt": [{"role": "system", "content": "You are helpful."}],
-                "labels": [],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [{"id": "score-1", "observationId": "obs-1", "name": "accuracy", "value": 0.9}],
+                "meta": {"totalPages": 1}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
-
-        let messages = vec![ChatMessage {
-            role: "system".to_string(),
-            content: "You are helpful.".to_string(),
-        }];
-
-        let prompt = client
-            .create_chat_prompt("assistant", &messages, None, None, None, None)
+        let client = LangfuseClient::new(&create_test_config(&mock_server.uri())).unwrap();
+        let scores = client
+            .list_scores(None, Some("trace-1"), Some("obs-1"), None, None, 50, 1)
             .await
             .unwrap();
 
-        assert_eq!(prompt.name, "assistant");
-        assert_eq!(prompt.prompt_type, "chat");
+        assert_eq!(scores.len(), 1);
     }
 
     #[tokio::test]
-    async fn test_update_prompt_labels_success() {
+    async fn test_get_score_success() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("PATCH"))
-            .and(path("/api/public/v2/prompts/greeting/versions/2"))
-            .and(body_json(json!({
-                "newLabels": ["production"]
-            })))
+        Mock::given(method("GET"))
+            .and(path("/api/public/scores/score-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "score-123",
+                "name": "accuracy",
+                "value": 0.95,
+                "source": "API"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let score = client.get_score("score-123").await.unwrap();
+
+        assert_eq!(score.id, "score-123");
+        assert_eq!(score.source, Some("API".to_string()));
+    }
+
+    // ========== Metrics API Tests ==========
+
+    #[tokio::test]
+    async fn test_query_metrics_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"timestamp": "2024-01-15", "count": 100},
+                    {"timestamp": "2024-01-16", "count": 150}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .query_metrics(
+                "traces",
+                "count",
+                "count",
+                None,
+                None,
+                None,
+                None,
+                None,
+                &[],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.data.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_metrics_raw_sends_body_verbatim() {
+        let mock_server = MockServer::start().await;
+
+        let query = json!({
+            "view": "observations",
+            "metrics": [{"measure": "totalCost", "aggregation": "sum"}],
+            "dimensions": [{"field": "providedModelName"}],
+            "filters": [{"column": "type", "operator": "=", "value": "GENERATION", "type": "string"}]
+        });
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/metrics"))
+            .and(body_json(&query))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"providedModelName": "gpt-4", "sum_totalCost": 1.5}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.query_metrics_raw(&query).await.unwrap();
+
+        assert_eq!(result.data.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_metrics_with_dimensions() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/metrics"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"model": "gpt-4", "count": 50},
+                    {"model": "gpt-3.5", "count": 100}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let dimensions = vec!["model".to_string()];
+        let result = client
+            .query_metrics(
+                "observations",
+                "count",
+                "count",
+                Some(&dimensions),
+                None,
+                None,
+                None,
+                None,
+                &[],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.data.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_metrics_with_filters() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/metrics"))
+            .and(body_partial_json(json!({
+                "filters": [
+                    {"column": "name", "operator": "=", "value": "chat", "type": "string"},
+                    {"column": "userId", "operator": "any of", "value": ["a", "b"], "type": "stringOptions"}
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"count": 3}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let filters = vec![
+            MetricsFilter::parse("name:=:chat").unwrap(),
+            MetricsFilter::parse("userId:in:a,b").unwrap(),
+        ];
+        let result = client
+            .query_metrics(
+                "traces", "count", "count", None, None, None, None, None, &filters,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.data.len(), 1);
+    }
+
+    // ========== Authentication Tests ==========
+
+    #[tokio::test]
+    async fn test_authentication_failure() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await;
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Authentication failed"));
+    }
+
+    #[tokio::test]
+    async fn test_authentication_error_kind_and_status() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let err = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await
+            .unwrap_err();
+        let api_err = err.downcast_ref::<ApiError>().unwrap();
+
+        assert_eq!(api_err.kind(), "authentication");
+        assert_eq!(api_err.status(), Some(401));
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await;
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Authentication failed"));
+    }
+
+    // ========== Rate Limiting Tests ==========
+
+    #[tokio::test]
+    async fn test_rate_limit_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Rate limit"));
+    }
+
+    // ========== Server Error Tests ==========
+
+    #[tokio::test]
+    async fn test_server_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .list_traces(None, None, None, None, None, None, None, None, 50, 1)
+            .await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("500"));
+    }
+
+    // ========== Test Connection Tests ==========
+
+    #[tokio::test]
+    async fn test_connection_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("limit", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [],
+                "meta": {}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.test_connection().await;
+
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.test_connection().await;
+
+        assert!(result.is_err());
+    }
+
+    // ========== Prompts API Tests ==========
+
+    #[tokio::test]
+    async fn test_list_prompts_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .and(query_param("limit", "50"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "prompt-1", "versions": [1, 2], "labels": ["production"], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"},
+                    {"name": "prompt-2", "versions": [1], "labels": [], "tags": ["test"], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
+                ],
+                "meta": {
+                    "page": 1,
+                    "limit": 50,
+                    "totalItems": 2,
+                    "totalPages": 1
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompts = client.list_prompts(None, None, None, 50, 1).await.unwrap();
+
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(prompts[0].name, "prompt-1");
+        assert_eq!(prompts[0].versions, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_list_prompts_with_filters() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .and(query_param("name", "welcome"))
+            .and(query_param("label", "production"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"name": "welcome", "versions": [1], "labels": ["production"], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}],
+                "meta": {"totalPages": 1}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompts = client
+            .list_prompts(Some("welcome"), Some("production"), None, 50, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "welcome");
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/welcome"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "welcome",
+                "version": 2,
+                "type": "text",
+                "prompt": "Hello {{name}}!",
+                "labels": ["production"],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client.get_prompt("welcome", None, None).await.unwrap();
+
+        assert_eq!(prompt.name, "welcome");
+        assert_eq!(prompt.version, 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_with_version() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/welcome"))
+            .and(query_param("version", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "welcome",
+                "version": 1,
+                "type": "text",
+                "prompt": "Hi {{name}}!",
+                "labels": [],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client.get_prompt("welcome", Some(1), None).await.unwrap();
+
+        assert_eq!(prompt.version, 1);
+    }
+
+    // ========== Pagination Tests ==========
+
+    #[tokio::test]
+    async fn test_list_traces_pagination() {
+        let mock_server = MockServer::start().await;
+
+        // First page - server returns only 2 items despite our limit=3
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "1"))
+            .and(query_param("limit", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-1"},
+                    {"id": "trace-2"}
+                ],
+                "meta": {
+                    "page": 1,
+                    "totalPages": 2
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        // Second page
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "2"))
+            .and(query_param("limit", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-3"}
+                ],
+                "meta": {
+                    "page": 2,
+                    "totalPages": 2
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        // Request 3 items, should fetch both pages
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, 3, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(traces.len(), 3);
+        assert_eq!(traces[0].id, "trace-1");
+        assert_eq!(traces[2].id, "trace-3");
+    }
+
+    #[tokio::test]
+    async fn test_list_traces_limit_truncation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-1"},
+                    {"id": "trace-2"},
+                    {"id": "trace-3"}
+                ],
+                "meta": {"totalPages": 1}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        // Request only 2 items
+        let traces = client
+            .list_traces(None, None, None, None, None, None, None, None, 2, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(traces.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_traces_stable_avoids_duplicates_from_new_traces() {
+        let mock_server = MockServer::start().await;
+
+        // Page 1 before a new trace arrives, page 2 after it has pushed trace-3 down
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "1"))
+            .and(query_param_is_missing("toTimestamp"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-4", "timestamp": "2024-01-15T10:04:00Z"},
+                    {"id": "trace-3", "timestamp": "2024-01-15T10:03:00Z"}
+                ],
+                "meta": {"page": 1, "totalPages": 2}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-3", "timestamp": "2024-01-15T10:03:00Z"},
+                    {"id": "trace-2", "timestamp": "2024-01-15T10:02:00Z"}
+                ],
+                "meta": {"page": 2, "totalPages": 2}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        // The same data seen through a timestamp boundary
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("toTimestamp", "2024-01-15T10:03:00.001Z"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-3", "timestamp": "2024-01-15T10:03:00Z"},
+                    {"id": "trace-2", "timestamp": "2024-01-15T10:02:00Z"}
+                ]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("toTimestamp", "2024-01-15T10:02:00.001Z"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-2", "timestamp": "2024-01-15T10:02:00Z"}
+                ]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config(&mock_server.uri());
+        config.page_size = 2;
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let paged = client
+            .list_traces(None, None, None, None, None, None, None, None, UNLIMITED, 1)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = paged.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["trace-4", "trace-3", "trace-3", "trace-2"]);
+
+        let stable = client
+            .list_traces_stable(None, None, None, None, None, None, None, UNLIMITED)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = stable.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["trace-4", "trace-3", "trace-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_traces_stable_reports_a_full_page_sharing_a_timestamp() {
+        let mock_server = MockServer::start().await;
+
+        // Exactly page_size traces share 10:03, so every batch below the
+        // boundary returns the same two traces
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "trace-2", "timestamp": "2024-01-15T10:03:00Z"},
+                    {"id": "trace-1", "timestamp": "2024-01-15T10:03:00Z"}
+                ]
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut config = create_test_config(&mock_server.uri());
+        config.page_size = 2;
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let err = client
+            .list_traces_stable(None, None, None, None, None, None, None, UNLIMITED)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "At least 2 traces share the timestamp 2024-01-15T10:03:00.000Z; raise --page-size to list them with --stable"
+        );
+    }
+
+    // ========== Prompts Create/Update/Delete Tests ==========
+
+    #[tokio::test]
+    async fn test_create_text_prompt_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting",
+                "version": 1,
+                "type": "text",
+                "prompt": "Hello {{name}}!",
+                "labels": ["staging"],
+                "tags": ["test"],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client
+            .create_text_prompt(
+                "greeting",
+                "Hello {{name}}!",
+                Some(&["staging".to_string()]),
+                Some(&["test".to_string()]),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.name, "greeting");
+        assert_eq!(prompt.version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_chat_prompt_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "assistant",
+                "version": 1,
+                "type": "chat",
+                "prompt": [{"role": "system", "content": "You are helpful."}],
+                "labels": [],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let messages = vec![ChatMessage {
+            role: "system".to_string(),
+            content: "You are helpful.".to_string(),
+            ..Default::default()
+        }];
+
+        let prompt: Prompt = client
+            .create_chat_prompt("assistant", &messages, None, None, None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.name, "assistant");
+        assert_eq!(prompt.prompt_type, "chat");
+    }
+
+    #[tokio::test]
+    async fn test_create_chat_prompt_with_placeholder_round_trips() {
+        let mock_server = MockServer::start().await;
+        let prompt_json = json!([
+            {"role": "system", "content": "You are helpful."},
+            {"type": "placeholder", "name": "history"}
+        ]);
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .and(body_partial_json(json!({"prompt": prompt_json})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "assistant",
+                "version": 1,
+                "type": "chat",
+                "prompt": prompt_json
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let messages: Vec<ChatMessage> = serde_json::from_value(prompt_json.clone()).unwrap();
+        let prompt: Prompt = client
+            .create_chat_prompt("assistant", &messages, None, None, None, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.prompt, PromptContent::Chat(messages));
+    }
+
+    #[tokio::test]
+    async fn test_update_prompt_labels_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PATCH"))
+            .and(path("/api/public/v2/prompts/greeting/versions/2"))
+            .and(body_json(json!({
+                "newLabels": ["production"]
+            })))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
                 "name": "greeting",
                 "version": 2,
                 "type": "text",
-                "prompt": "Hello!",
-                "labels": ["production"],
+                "prompt": "Hello!",
+                "labels": ["production"],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client
+            .update_prompt_labels("greeting", 2, &["production".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.labels, vec!["production"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_prompt_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.delete_prompt("greeting", None, None).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_prompt_with_version() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("version", "1"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.delete_prompt("greeting", Some(1), None).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_with_label() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/welcome"))
+            .and(query_param("label", "staging"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "welcome",
+                "version": 2,
+                "type": "text",
+                "prompt": "Staging version",
+                "labels": ["staging"],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client
+            .get_prompt("welcome", None, Some("staging"))
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.labels, vec!["staging"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_prompt_with_label() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("label", "staging"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .delete_prompt("greeting", None, Some("staging"))
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    // ========== URL Encoding Tests ==========
+
+    #[tokio::test]
+    async fn test_update_prompt_labels_url_encodes_name_with_slash() {
+        let mock_server = MockServer::start().await;
+
+        // The prompt name contains a slash which must be URL-encoded as %2F
+        Mock::given(method("PATCH"))
+            .and(path(
+                "/api/public/v2/prompts/customer%2Fgenerate-yaml/versions/1",
+            ))
+            .and(body_json(json!({
+                "newLabels": ["production"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "customer/generate-yaml",
+                "version": 1,
+                "type": "text",
+                "prompt": "Content",
+                "labels": ["production"],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client
+            .update_prompt_labels("customer/generate-yaml", 1, &["production".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.name, "customer/generate-yaml");
+        assert_eq!(prompt.labels, vec!["production"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_url_encodes_name_with_slash() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/customer%2Fgenerate-yaml"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "customer/generate-yaml",
+                "version": 1,
+                "type": "text",
+                "prompt": "Content",
+                "labels": [],
+                "tags": [],
+                "createdAt": "2024-01-15T10:00:00Z",
+                "updatedAt": "2024-01-15T10:00:00Z"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompt = client
+            .get_prompt("customer/generate-yaml", None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.name, "customer/generate-yaml");
+    }
+
+    #[tokio::test]
+    async fn test_delete_prompt_url_encodes_name_with_slash() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/v2/prompts/customer%2Fgenerate-yaml"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .delete_prompt("customer/generate-yaml", None, None)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_prompt_versions() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/team%2Fgreeting/versions"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "team/greeting", "version": 3, "type": "text", "prompt": "Hi {{name}}!",
+                     "labels": ["production", "latest"], "commitMessage": "Friendlier tone",
+                     "updatedAt": "2024-03-01T00:00:00Z"},
+                    {"name": "team/greeting", "version": 2, "type": "text", "prompt": "Hello {{name}}",
+                     "labels": ["staging"], "updatedAt": "2024-02-01T00:00:00Z"}
+                ],
+                "meta": {"page": 1, "limit": 100, "totalItems": 3, "totalPages": 2}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/team%2Fgreeting/versions"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "team/greeting", "version": 1, "type": "text", "prompt": "Hello",
+                     "labels": [], "updatedAt": "2024-01-01T00:00:00Z"}
+                ],
+                "meta": {"page": 2, "limit": 100, "totalItems": 3, "totalPages": 2}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let versions = client.list_prompt_versions("team/greeting").await.unwrap();
+
+        assert_eq!(versions.len(), 3);
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+        assert_eq!(versions[0].labels, ["production", "latest"]);
+        assert_eq!(
+            versions[0].commit_message.as_deref(),
+            Some("Friendlier tone")
+        );
+        assert_eq!(
+            versions[2].updated_at.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_prompts_pagination() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .and(query_param("page", "1"))
+            .and(query_param("limit", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "prompt-1", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"},
+                    {"name": "prompt-2", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
+                ],
+                "meta": {
+                    "page": 1,
+                    "totalPages": 2
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .and(query_param("page", "2"))
+            .and(query_param("limit", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "prompt-3", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
+                ],
+                "meta": {
+                    "page": 2,
+                    "totalPages": 2
+                }
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let prompts = client.list_prompts(None, None, None, 3, 1).await.unwrap();
+
+        assert_eq!(prompts.len(), 3);
+        assert_eq!(prompts[0].name, "prompt-1");
+        assert_eq!(prompts[2].name, "prompt-3");
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_not_found() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/nonexistent"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Prompt not found"))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.get_prompt("nonexistent", None, None).await;
+
+        assert!(result.is_err());
+        let err_msg = result.unwrap_err().to_string().to_lowercase();
+        assert!(err_msg.contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_list_prompts_auth_failure() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client.list_prompts(None, None, None, 50, 1).await;
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Authentication failed"));
+    }
+
+    #[tokio::test]
+    async fn test_create_prompt_rate_limit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .create_text_prompt("test", "content", None, None, None, None, None)
+            .await;
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Rate limit"));
+    }
+
+    // ========== Score Creation Tests ==========
+
+    #[tokio::test]
+    async fn test_create_score_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "score-abc123"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .create_score(
+                "accuracy",
+                &ScoreValue::Numeric(0.95),
+                Some("trace-123"),
+                None,
+                None,
+                Some("NUMERIC"),
+                Some("Good result"),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.id, "score-abc123");
+    }
+
+    #[tokio::test]
+    async fn test_create_score_with_observation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "score-def456"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .create_score(
+                "relevance",
+                &ScoreValue::Numeric(0.88),
+                Some("trace-123"),
+                Some("obs-456"),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.id, "score-def456");
+    }
+
+    #[tokio::test]
+    async fn test_create_score_with_id_repeats_same_request() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .and(body_json(json!({
+                "id": "eval-run-7-trace-123",
+                "name": "accuracy",
+                "value": 0.9,
+                "traceId": "trace-123"
+            })))
+            .and(header("Idempotency-Key", "eval-run-7-trace-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "eval-run-7-trace-123"
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        for _ in 0..2 {
+            let result = client
+                .create_score(
+                    "accuracy",
+                    &ScoreValue::Numeric(0.9),
+                    Some("trace-123"),
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some("eval-run-7-trace-123"),
+                )
+                .await
+                .unwrap();
+            assert_eq!(result.id, "eval-run-7-trace-123");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_score_without_id_sends_fresh_idempotency_key() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "s-1"})))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+        for _ in 0..2 {
+            client
+                .create_score(
+                    "accuracy",
+                    &ScoreValue::Numeric(0.9),
+                    Some("trace-123"),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+        }
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let keys: Vec<&str> = requests
+            .iter()
+            .map(|r| r.headers[IDEMPOTENCY_KEY_HEADER].to_str().unwrap())
+            .collect();
+        assert_eq!(keys.len(), 2);
+        assert!(!keys[0].is_empty());
+        assert_ne!(keys[0], keys[1]);
+        assert!(requests[0]
+            .body_json::<Value>()
+            .unwrap()
+            .get("id")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_score_categorical_and_boolean_bodies() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .and(body_json(json!({
+                "name": "quality",
+                "value": "good",
+                "traceId": "trace-1",
+                "dataType": "CATEGORICAL"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "score-cat"})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .and(body_json(json!({
+                "name": "correct",
+                "value": 1,
+                "traceId": "trace-1",
+                "dataType": "BOOLEAN"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"id": "score-bool"})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let categorical = client
+            .create_score(
+                "quality",
+                &ScoreValue::Categorical("good".to_string()),
+                Some("trace-1"),
+                None,
+                None,
+                Some("CATEGORICAL"),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let boolean = client
+            .create_score(
+                "correct",
+                &ScoreValue::Boolean(true),
+                Some("trace-1"),
+                None,
+                None,
+                Some("BOOLEAN"),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(categorical.id, "score-cat");
+        assert_eq!(boolean.id, "score-bool");
+    }
+
+    #[tokio::test]
+    async fn test_create_score_handles_201_created() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/scores"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "score-new"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let result = client
+            .create_score(
+                "test",
+                &ScoreValue::Numeric(1.0),
+                Some("trace-1"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().id, "score-new");
+    }
+
+    #[tokio::test]
+    async fn test_create_prompt_handles_201_created() {
+        let mock_server = MockServer::start().await;
+
+        // API returns 201 Created for successful resource creation
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "name": "test-prompt",
+                "version": 1,
+                "type": "text",
+                "prompt": "Test content",
+                "labels": [],
                 "tags": [],
                 "createdAt": "2024-01-15T10:00:00Z",
                 "updatedAt": "2024-01-15T10:00:00Z"
@@ -2019,67 +4518,75 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client
-            .update_prompt_labels("greeting", 2, &["production".to_string()])
-            .await
-            .unwrap();
+        let result = client
+            .create_text_prompt("test-prompt", "Test content", None, None, None, None, None)
+            .await;
 
-        assert_eq!(prompt.labels, vec!["production"]);
+        assert!(result.is_ok(), "201 Created should be treated as success");
+        let prompt = result.unwrap();
+        assert_eq!(prompt.name, "test-prompt");
+        assert_eq!(prompt.version, 1);
     }
 
+    // ========== Datasets API Tests ==========
+
     #[tokio::test]
-    async fn test_delete_prompt_success() {
+    async fn test_delete_dataset() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("DELETE"))
-            .and(path("/api/public/v2/prompts/greeting"))
+            .and(path("/api/public/v2/datasets/evals%2Fqa"))
             .respond_with(ResponseTemplate::new(204))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.delete_prompt("greeting", None, None).await;
-
-        assert!(result.is_ok());
+        client.delete_dataset("evals/qa").await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_delete_prompt_with_version() {
+    async fn test_delete_dataset_not_found() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("DELETE"))
-            .and(path("/api/public/v2/prompts/greeting"))
-            .and(query_param("version", "1"))
-            .respond_with(ResponseTemplate::new(204))
+            .and(path("/api/public/v2/datasets/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Dataset not found"))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.delete_prompt("greeting", Some(1), None).await;
+        let err = client.delete_dataset("missing").await.unwrap_err();
 
-        assert!(result.is_ok());
+        assert!(matches!(
+            err.downcast_ref::<ApiError>(),
+            Some(ApiError::NotFoundError(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_get_prompt_with_label() {
+    async fn test_list_datasets_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts/welcome"))
-            .and(query_param("label", "staging"))
+            .and(path("/api/public/v2/datasets"))
+            .and(query_param("limit", "50"))
+            .and(query_param("page", "1"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "welcome",
-                "version": 2,
-                "type": "text",
-                "prompt": "Staging version",
-                "labels": ["staging"],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [
+                    {"id": "ds-1", "name": "dataset-1", "description": "First dataset"},
+                    {"id": "ds-2", "name": "dataset-2"}
+                ],
+                "meta": {
+                    "page": 1,
+                    "limit": 50,
+                    "totalItems": 2,
+                    "totalPages": 1
+                }
             })))
             .mount(&mock_server)
             .await;
@@ -2087,58 +4594,75 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client
-            .get_prompt("welcome", None, Some("staging"))
-            .await
-            .unwrap();
+        let datasets = client.list_datasets(50, 1).await.unwrap();
 
-        assert_eq!(prompt.labels, vec!["staging"]);
+        assert_eq!(datasets.len(), 2);
+        assert_eq!(datasets[0].name, "dataset-1");
+        assert_eq!(datasets[1].name, "dataset-2");
     }
 
     #[tokio::test]
-    async fn test_delete_prompt_with_label() {
+    async fn test_get_dataset_success() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("DELETE"))
-            .and(path("/api/public/v2/prompts/greeting"))
-            .and(query_param("label", "staging"))
-            .respond_with(ResponseTemplate::new(204))
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/datasets/my-dataset"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "ds-123",
+                "name": "my-dataset",
+                "description": "Test dataset",
+                "projectId": "proj-456"
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .delete_prompt("greeting", None, Some("staging"))
-            .await;
+        let dataset = client.get_dataset("my-dataset").await.unwrap();
 
-        assert!(result.is_ok());
+        assert_eq!(dataset.id, "ds-123");
+        assert_eq!(dataset.name, "my-dataset");
     }
 
-    // ========== URL Encoding Tests ==========
-
     #[tokio::test]
-    async fn test_update_prompt_labels_url_encodes_name_with_slash() {
+    async fn test_create_dataset_success() {
         let mock_server = MockServer::start().await;
 
-        // The prompt name contains a slash which must be URL-encoded as %2F
-        Mock::given(method("PATCH"))
-            .and(path(
-                "/api/public/v2/prompts/customer%2Fgenerate-yaml/versions/1",
-            ))
-            .and(body_json(json!({
-                "newLabels": ["production"]
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/datasets"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "ds-new",
+                "name": "new-dataset",
+                "description": "A new dataset"
             })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let dataset = client
+            .create_dataset("new-dataset", Some("A new dataset"), None)
+            .await
+            .unwrap();
+
+        assert_eq!(dataset.name, "new-dataset");
+    }
+
+    #[tokio::test]
+    async fn test_list_dataset_items_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/dataset-items"))
+            .and(query_param("datasetName", "my-dataset"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "customer/generate-yaml",
-                "version": 1,
-                "type": "text",
-                "prompt": "Content",
-                "labels": ["production"],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "data": [
+                    {"id": "item-1", "datasetName": "my-dataset", "input": {"prompt": "Hello"}},
+                    {"id": "item-2", "datasetName": "my-dataset", "input": {"prompt": "World"}}
+                ],
+                "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
@@ -2146,30 +4670,26 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client
-            .update_prompt_labels("customer/generate-yaml", 1, &["production".to_string()])
+        let items = client
+            .list_dataset_items(Some("my-dataset"), 50, 1)
             .await
             .unwrap();
 
-        assert_eq!(prompt.name, "customer/generate-yaml");
-        assert_eq!(prompt.labels, vec!["production"]);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, "item-1");
     }
 
     #[tokio::test]
-    async fn test_get_prompt_url_encodes_name_with_slash() {
+    async fn test_get_dataset_item_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts/customer%2Fgenerate-yaml"))
+            .and(path("/api/public/dataset-items/item-123"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "name": "customer/generate-yaml",
-                "version": 1,
-                "type": "text",
-                "prompt": "Content",
-                "labels": [],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
+                "id": "item-123",
+                "datasetName": "my-dataset",
+                "input": {"prompt": "Test"},
+                "expectedOutput": {"response": "Expected"}
             })))
             .mount(&mock_server)
             .await;
@@ -2177,67 +4697,101 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompt = client
-            .get_prompt("customer/generate-yaml", None, None)
+        let item = client.get_dataset_item("item-123").await.unwrap();
+
+        assert_eq!(item.id, "item-123");
+    }
+
+    #[tokio::test]
+    async fn test_create_dataset_item_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/dataset-items"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "item-new",
+                "datasetName": "my-dataset",
+                "input": {"prompt": "New item"}
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        let input = json!({"prompt": "New item"});
+        let item = client
+            .create_dataset_item("my-dataset", &input, None, None, None, None)
             .await
             .unwrap();
 
-        assert_eq!(prompt.name, "customer/generate-yaml");
+        assert_eq!(item.id, "item-new");
     }
 
     #[tokio::test]
-    async fn test_delete_prompt_url_encodes_name_with_slash() {
+    async fn test_update_dataset_item_sends_only_given_fields() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("DELETE"))
-            .and(path("/api/public/v2/prompts/customer%2Fgenerate-yaml"))
-            .respond_with(ResponseTemplate::new(204))
+        Mock::given(method("POST"))
+            .and(path("/api/public/dataset-items"))
+            .and(body_json(json!({
+                "id": "item-123",
+                "datasetName": "my-dataset",
+                "expectedOutput": {"response": "Better"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "item-123",
+                "datasetName": "my-dataset",
+                "input": {"prompt": "Test"},
+                "expectedOutput": {"response": "Better"}
+            })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .delete_prompt("customer/generate-yaml", None, None)
-            .await;
+        let expected = json!({"response": "Better"});
+        let item = client
+            .update_dataset_item("item-123", "my-dataset", None, Some(&expected), None)
+            .await
+            .unwrap();
 
-        assert!(result.is_ok());
+        assert_eq!(item.expected_output, Some(expected));
     }
 
     #[tokio::test]
-    async fn test_list_prompts_pagination() {
+    async fn test_delete_dataset_item_encodes_id() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts"))
-            .and(query_param("page", "1"))
-            .and(query_param("limit", "3"))
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/dataset-items/item%2F123"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"name": "prompt-1", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"},
-                    {"name": "prompt-2", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
-                ],
-                "meta": {
-                    "page": 1,
-                    "totalPages": 2
-                }
+                "message": "Dataset item deleted"
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
+        let config = create_test_config(&mock_server.uri());
+        let client = LangfuseClient::new(&config).unwrap();
+
+        client.delete_dataset_item("item/123").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_dataset_runs_success() {
+        let mock_server = MockServer::start().await;
+
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts"))
-            .and(query_param("page", "2"))
-            .and(query_param("limit", "3"))
+            .and(path("/api/public/datasets/my-dataset/runs"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
                 "data": [
-                    {"name": "prompt-3", "versions": [1], "labels": [], "tags": [], "lastUpdatedAt": "2024-01-15T10:00:00Z"}
+                    {"id": "run-1", "name": "eval-run-1", "datasetName": "my-dataset"},
+                    {"id": "run-2", "name": "eval-run-2", "datasetName": "my-dataset"}
                 ],
-                "meta": {
-                    "page": 2,
-                    "totalPages": 2
-                }
+                "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
             .await;
@@ -2245,265 +4799,222 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let prompts = client.list_prompts(None, None, None, 3, 1).await.unwrap();
+        let runs = client.list_dataset_runs("my-dataset", 50, 1).await.unwrap();
 
-        assert_eq!(prompts.len(), 3);
-        assert_eq!(prompts[0].name, "prompt-1");
-        assert_eq!(prompts[2].name, "prompt-3");
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].name, "eval-run-1");
     }
 
     #[tokio::test]
-    async fn test_get_prompt_not_found() {
+    async fn test_get_dataset_run_success() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts/nonexistent"))
-            .respond_with(ResponseTemplate::new(404).set_body_string("Prompt not found"))
+            .and(path("/api/public/datasets/my-dataset/runs/eval-run"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "run-123",
+                "name": "eval-run",
+                "datasetName": "my-dataset",
+                "description": "Evaluation run"
+            })))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.get_prompt("nonexistent", None, None).await;
+        let run = client
+            .get_dataset_run("my-dataset", "eval-run")
+            .await
+            .unwrap();
 
-        assert!(result.is_err());
-        let err_msg = result.unwrap_err().to_string().to_lowercase();
-        assert!(err_msg.contains("not found"));
+        assert_eq!(run.id, "run-123");
+        assert_eq!(run.name, "eval-run");
     }
 
     #[tokio::test]
-    async fn test_list_prompts_auth_failure() {
+    async fn test_delete_trace_success() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/v2/prompts"))
-            .respond_with(ResponseTemplate::new(401))
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/traces/trace-123"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "message": "Trace deleted successfully"
+            })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client.list_prompts(None, None, None, 50, 1).await;
+        let result = client.delete_trace("trace-123").await;
 
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Authentication failed"));
+        assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_create_prompt_rate_limit() {
+    async fn test_delete_trace_not_found() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/v2/prompts"))
-            .respond_with(ResponseTemplate::new(429))
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/traces/missing%2Ftrace"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Trace not found"))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .create_text_prompt("test", "content", None, None, None, None)
-            .await;
+        let err = client.delete_trace("missing/trace").await.unwrap_err();
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Rate limit"));
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(ApiError::NotFoundError(_))
+        ));
     }
 
-    // ========== Score Creation Tests ==========
-
     #[tokio::test]
-    async fn test_create_score_success() {
+    async fn test_update_score_sends_only_changed_fields() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/scores"))
+        Mock::given(method("PATCH"))
+            .and(path("/api/public/scores/score%2F1"))
+            .and(body_json(json!({"comment": "Rechecked by hand"})))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "score-abc123"
+                "id": "score/1",
+                "name": "accuracy",
+                "value": 0.9,
+                "comment": "Rechecked by hand"
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .create_score(
-                "accuracy",
-                0.95,
-                Some("trace-123"),
-                None,
-                None,
-                Some("NUMERIC"),
-                Some("Good result"),
-            )
+        let score = client
+            .update_score("score/1", None, Some("Rechecked by hand"), None)
             .await
             .unwrap();
 
-        assert_eq!(result.id, "score-abc123");
+        assert_eq!(score.id, "score/1");
     }
 
     #[tokio::test]
-    async fn test_create_score_with_observation() {
+    async fn test_update_score_categorical_value() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/scores"))
+        Mock::given(method("PATCH"))
+            .and(path("/api/public/scores/score-1"))
+            .and(body_json(
+                json!({"value": "good", "dataType": "CATEGORICAL"}),
+            ))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "score-def456"
+                "id": "score-1",
+                "name": "quality",
+                "value": "good"
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .create_score(
-                "relevance",
-                0.88,
-                Some("trace-123"),
-                Some("obs-456"),
-                None,
-                None,
+        client
+            .update_score(
+                "score-1",
+                Some(&ScoreValue::Categorical("good".to_string())),
                 None,
+                Some("CATEGORICAL"),
             )
             .await
             .unwrap();
-
-        assert_eq!(result.id, "score-def456");
     }
 
     #[tokio::test]
-    async fn test_create_score_handles_201_created() {
+    async fn test_update_score_not_found() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/api/public/scores"))
-            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
-                "id": "score-new"
-            })))
+        Mock::given(method("PATCH"))
+            .and(path("/api/public/scores/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Score not found"))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .create_score("test", 1.0, Some("trace-1"), None, None, None, None)
-            .await;
+        let err = client
+            .update_score("missing", Some(&ScoreValue::Numeric(1.0)), None, None)
+            .await
+            .unwrap_err();
 
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().id, "score-new");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(ApiError::NotFoundError(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_create_prompt_handles_201_created() {
+    async fn test_delete_score_success() {
         let mock_server = MockServer::start().await;
 
-        // API returns 201 Created for successful resource creation
-        Mock::given(method("POST"))
-            .and(path("/api/public/v2/prompts"))
-            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
-                "name": "test-prompt",
-                "version": 1,
-                "type": "text",
-                "prompt": "Test content",
-                "labels": [],
-                "tags": [],
-                "createdAt": "2024-01-15T10:00:00Z",
-                "updatedAt": "2024-01-15T10:00:00Z"
-            })))
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/scores/score-123"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let result = client
-            .create_text_prompt("test-prompt", "Test content", None, None, None, None)
-            .await;
+        let result = client.delete_score("score-123").await;
 
-        assert!(result.is_ok(), "201 Created should be treated as success");
-        let prompt = result.unwrap();
-        assert_eq!(prompt.name, "test-prompt");
-        assert_eq!(prompt.version, 1);
+        assert!(result.is_ok());
     }
 
-    // ========== Datasets API Tests ==========
-
     #[tokio::test]
-    async fn test_list_datasets_success() {
+    async fn test_delete_score_not_found() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/v2/datasets"))
-            .and(query_param("limit", "50"))
-            .and(query_param("page", "1"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "ds-1", "name": "dataset-1", "description": "First dataset"},
-                    {"id": "ds-2", "name": "dataset-2"}
-                ],
-                "meta": {
-                    "page": 1,
-                    "limit": 50,
-                    "totalItems": 2,
-                    "totalPages": 1
-                }
-            })))
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/scores/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Score not found"))
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let datasets = client.list_datasets(50, 1).await.unwrap();
+        let err = client.delete_score("missing").await.unwrap_err();
 
-        assert_eq!(datasets.len(), 2);
-        assert_eq!(datasets[0].name, "dataset-1");
-        assert_eq!(datasets[1].name, "dataset-2");
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(ApiError::NotFoundError(_))
+        ));
+    }
+
+    #[test]
+    fn test_path_segment_encodes_reserved_characters() {
+        assert_eq!(path_segment("my-dataset"), "my-dataset");
+        assert_eq!(path_segment("team/eval-set"), "team%2Feval-set");
+        assert_eq!(path_segment("q1 run?"), "q1%20run%3F");
     }
 
     #[tokio::test]
-    async fn test_get_dataset_success() {
+    async fn test_get_dataset_url_encodes_name_with_slash() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/v2/datasets/my-dataset"))
+            .and(path("/api/public/v2/datasets/team%2Feval-set"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
                 "id": "ds-123",
-                "name": "my-dataset",
-                "description": "Test dataset",
-                "projectId": "proj-456"
-            })))
-            .mount(&mock_server)
-            .await;
-
-        let config = create_test_config(&mock_server.uri());
-        let client = LangfuseClient::new(&config).unwrap();
-
-        let dataset = client.get_dataset("my-dataset").await.unwrap();
-
-        assert_eq!(dataset.id, "ds-123");
-        assert_eq!(dataset.name, "my-dataset");
-    }
-
-    #[tokio::test]
-    async fn test_create_dataset_success() {
-        let mock_server = MockServer::start().await;
-
-        Mock::given(method("POST"))
-            .and(path("/api/public/v2/datasets"))
-            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
-                "id": "ds-new",
-                "name": "new-dataset",
-                "description": "A new dataset"
+                "name": "team/eval-set"
             })))
             .mount(&mock_server)
             .await;
@@ -2511,26 +5022,19 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let dataset = client
-            .create_dataset("new-dataset", Some("A new dataset"), None)
-            .await
-            .unwrap();
+        let dataset = client.get_dataset("team/eval-set").await.unwrap();
 
-        assert_eq!(dataset.name, "new-dataset");
+        assert_eq!(dataset.name, "team/eval-set");
     }
 
     #[tokio::test]
-    async fn test_list_dataset_items_success() {
+    async fn test_list_dataset_runs_url_encodes_dataset_name() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/dataset-items"))
-            .and(query_param("datasetName", "my-dataset"))
+            .and(path("/api/public/datasets/team%2Feval-set/runs"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "item-1", "datasetName": "my-dataset", "input": {"prompt": "Hello"}},
-                    {"id": "item-2", "datasetName": "my-dataset", "input": {"prompt": "World"}}
-                ],
+                "data": [{"id": "run-1", "name": "eval-run-1", "datasetName": "team/eval-set"}],
                 "meta": {"totalPages": 1}
             })))
             .mount(&mock_server)
@@ -2539,76 +5043,86 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let items = client
-            .list_dataset_items(Some("my-dataset"), 50, 1)
+        let runs = client
+            .list_dataset_runs("team/eval-set", 50, 1)
             .await
             .unwrap();
 
-        assert_eq!(items.len(), 2);
-        assert_eq!(items[0].id, "item-1");
+        assert_eq!(runs.len(), 1);
     }
 
     #[tokio::test]
-    async fn test_get_dataset_item_success() {
+    async fn test_create_dataset_run_posts_name_and_metadata() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/dataset-items/item-123"))
+        Mock::given(method("POST"))
+            .and(path("/api/public/datasets/team%2Feval-set/runs"))
+            .and(body_json(json!({
+                "runName": "baseline",
+                "description": "First pass",
+                "metadata": {"model": "gpt-4o"}
+            })))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "item-123",
-                "datasetName": "my-dataset",
-                "input": {"prompt": "Test"},
-                "expectedOutput": {"response": "Expected"}
+                "id": "run-1",
+                "name": "baseline",
+                "datasetName": "team/eval-set",
+                "metadata": {"model": "gpt-4o"}
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let item = client.get_dataset_item("item-123").await.unwrap();
+        let run = client
+            .create_dataset_run(
+                "team/eval-set",
+                "baseline",
+                Some("First pass"),
+                Some(&json!({"model": "gpt-4o"})),
+            )
+            .await
+            .unwrap();
 
-        assert_eq!(item.id, "item-123");
+        assert_eq!(run.id, "run-1");
+        assert_eq!(run.metadata, Some(json!({"model": "gpt-4o"})));
     }
 
     #[tokio::test]
-    async fn test_create_dataset_item_success() {
+    async fn test_create_dataset_run_omits_unset_fields() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("POST"))
-            .and(path("/api/public/dataset-items"))
-            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
-                "id": "item-new",
-                "datasetName": "my-dataset",
-                "input": {"prompt": "New item"}
+            .and(path("/api/public/datasets/evals/runs"))
+            .and(body_json(json!({"runName": "baseline"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "run-1",
+                "name": "baseline"
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let input = json!({"prompt": "New item"});
-        let item = client
-            .create_dataset_item("my-dataset", &input, None, None, None, None)
+        client
+            .create_dataset_run("evals", "baseline", None, None)
             .await
             .unwrap();
-
-        assert_eq!(item.id, "item-new");
     }
 
     #[tokio::test]
-    async fn test_list_dataset_runs_success() {
+    async fn test_get_dataset_run_url_encodes_names() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/api/public/datasets/my-dataset/runs"))
+            .and(path("/api/public/datasets/team%2Feval-set/runs/2024%2Fq1"))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "data": [
-                    {"id": "run-1", "name": "eval-run-1", "datasetName": "my-dataset"},
-                    {"id": "run-2", "name": "eval-run-2", "datasetName": "my-dataset"}
-                ],
-                "meta": {"totalPages": 1}
+                "id": "run-123",
+                "name": "2024/q1",
+                "datasetName": "team/eval-set"
             })))
             .mount(&mock_server)
             .await;
@@ -2616,36 +5130,53 @@ mod tests {
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let runs = client.list_dataset_runs("my-dataset", 50, 1).await.unwrap();
+        let run = client
+            .get_dataset_run("team/eval-set", "2024/q1")
+            .await
+            .unwrap();
 
-        assert_eq!(runs.len(), 2);
-        assert_eq!(runs[0].name, "eval-run-1");
+        assert_eq!(run.name, "2024/q1");
     }
 
     #[tokio::test]
-    async fn test_get_dataset_run_success() {
+    async fn test_create_dataset_run_item_body() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/api/public/datasets/my-dataset/runs/eval-run"))
+        Mock::given(method("POST"))
+            .and(path("/api/public/dataset-run-items"))
+            .and(body_json(json!({
+                "runName": "eval-2024-01",
+                "datasetItemId": "item-123",
+                "traceId": "trace-456",
+                "metadata": {"model": "gpt-4"}
+            })))
             .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-                "id": "run-123",
-                "name": "eval-run",
-                "datasetName": "my-dataset",
-                "description": "Evaluation run"
+                "id": "run-item-1",
+                "datasetRunId": "run-1",
+                "datasetRunName": "eval-2024-01",
+                "datasetItemId": "item-123",
+                "traceId": "trace-456"
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = create_test_config(&mock_server.uri());
         let client = LangfuseClient::new(&config).unwrap();
 
-        let run = client
-            .get_dataset_run("my-dataset", "eval-run")
+        let metadata = json!({"model": "gpt-4"});
+        let run_item = client
+            .create_dataset_run_item(
+                "eval-2024-01",
+                "item-123",
+                Some("trace-456"),
+                None,
+                Some(&metadata),
+            )
             .await
             .unwrap();
 
-        assert_eq!(run.id, "run-123");
-        assert_eq!(run.name, "eval-run");
+        assert_eq!(run_item.id, "run-item-1");
+        assert_eq!(run_item.trace_id.as_deref(), Some("trace-456"));
     }
 }
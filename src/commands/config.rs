@@ -4,6 +4,7 @@ use dialoguer::{Input, Password};
 use std::io::{self, Write};
 
 use crate::client::LangfuseClient;
+use crate::commands::{build_config, GlobalOptions};
 use crate::config::Config;
 
 fn read_line_with_prompt(prompt: &str) -> Result<String> {
@@ -14,6 +15,47 @@ fn read_line_with_prompt(prompt: &str) -> Result<String> {
     Ok(input.trim().to_string())
 }
 
+/// Quote a value for POSIX shells using single quotes
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Call the API with `config`'s credentials, describing the outcome with
+/// the profile and host that were tried
+async fn check_connection(config: &Config) -> Result<String> {
+    let client = LangfuseClient::new(config)?;
+    client.test_connection().await.with_context(|| {
+        format!(
+            "Connection to {} failed for profile '{}'",
+            config.host, config.profile
+        )
+    })?;
+    Ok(format!(
+        "Connection to {} succeeded for profile '{}'",
+        config.host, config.profile
+    ))
+}
+
+/// Say where the keys being tested came from: the saved profile, or the
+/// environment for any key the profile doesn't have
+fn key_source(profile_name: &str, public_saved: bool, secret_saved: bool) -> String {
+    let profile = format!("profile '{profile_name}'");
+    match (public_saved, secret_saved) {
+        (true, true) => format!("Using keys from {profile}"),
+        (false, false) => "Using keys from LANGFUSE_PUBLIC_KEY and LANGFUSE_SECRET_KEY".to_string(),
+        (true, false) => {
+            format!(
+                "Using the public key from {profile} and the secret key from LANGFUSE_SECRET_KEY"
+            )
+        }
+        (false, true) => {
+            format!(
+                "Using the public key from LANGFUSE_PUBLIC_KEY and the secret key from {profile}"
+            )
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommands {
     /// Interactive configuration wizard
@@ -40,6 +82,10 @@ pub enum ConfigCommands {
         /// Langfuse host URL
         #[arg(long, env = "LANGFUSE_HOST")]
         host: Option<String>,
+
+        /// Store the secret key in the OS keychain instead of the config file
+        #[arg(long, visible_alias = "use-keyring")]
+        use_keychain: bool,
     },
 
     /// Show configuration for a profile
@@ -51,14 +97,47 @@ pub enum ConfigCommands {
 
     /// List all configured profiles
     List,
+
+    /// Print a profile as shell export statements (unmasked, for eval)
+    Export {
+        /// Profile name
+        #[arg(short, long, default_value = "default")]
+        profile: String,
+    },
+
+    /// Delete a profile
+    Delete {
+        /// Profile name
+        profile: String,
+    },
+
+    /// Make a profile the default when --profile and LANGFUSE_PROFILE aren't set
+    Use {
+        /// Profile name
+        profile: String,
+    },
+
+    /// Check that a saved profile can still reach its host (e.g. after a key rotation)
+    Test {
+        /// Profile name (defaults to the active profile)
+        #[arg(short, long)]
+        profile: Option<String>,
+
+        /// Langfuse host URL, overriding the profile's host
+        #[arg(long)]
+        host: Option<String>,
+    },
+
+    /// Rewrite the config file in the current format, reporting what changed
+    Migrate,
 }
 
 impl ConfigCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             ConfigCommands::Setup { non_interactive } => {
                 if *non_interactive {
-                    self.setup_non_interactive().await
+                    self.setup_non_interactive(global.quiet).await
                 } else {
                     self.setup_interactive().await
                 }
@@ -68,12 +147,27 @@ impl ConfigCommands {
                 public_key,
                 secret_key,
                 host,
+                use_keychain,
             } => {
-                self.set_config(profile, public_key, secret_key, host.as_deref())
-                    .await
+                self.set_config(
+                    profile,
+                    public_key,
+                    secret_key,
+                    host.as_deref(),
+                    *use_keychain,
+                )
+                .await
             }
             ConfigCommands::Show { profile } => self.show_config(profile),
             ConfigCommands::List => self.list_profiles(),
+            ConfigCommands::Export { profile } => self.export_profile(profile),
+            ConfigCommands::Delete { profile } => self.delete_profile(profile),
+            ConfigCommands::Use { profile } => self.use_profile(profile),
+            ConfigCommands::Test { profile, host } => {
+                self.test_profile(profile.as_deref(), host.as_deref(), global)
+                    .await
+            }
+            ConfigCommands::Migrate => self.migrate(global.dry_run),
         }
     }
 
@@ -116,7 +210,7 @@ impl ConfigCommands {
                 println!("Connection successful!");
 
                 // Save configuration
-                Config::set_profile(&profile, &public_key, &secret_key, Some(&host))?;
+                Config::set_profile(&profile, &public_key, &secret_key, Some(&host), false)?;
                 println!("\nConfiguration saved to profile '{profile}'");
 
                 if let Some(path) = Config::config_path() {
@@ -138,7 +232,7 @@ impl ConfigCommands {
         }
     }
 
-    async fn setup_non_interactive(&self) -> Result<()> {
+    async fn setup_non_interactive(&self, quiet: bool) -> Result<()> {
         let profile = std::env::var("LANGFUSE_PROFILE").unwrap_or_else(|_| "default".to_string());
         let public_key =
             std::env::var("LANGFUSE_PUBLIC_KEY").context("LANGFUSE_PUBLIC_KEY not set")?;
@@ -148,7 +242,9 @@ impl ConfigCommands {
             .unwrap_or_else(|_| "https://cloud.langfuse.com".to_string());
 
         // Test connection
-        eprintln!("Testing connection...");
+        if !quiet {
+            eprintln!("Testing connection...");
+        }
         let config = Config::load(
             Some(&profile),
             Some(&public_key),
@@ -165,10 +261,15 @@ impl ConfigCommands {
         let client = LangfuseClient::new(&config)?;
         match client.test_connection().await {
             Ok(_) => {
-                eprintln!("Connection successful!");
+                if !quiet {
+                    eprintln!("Connection successful!");
+                }
 
                 // Save configuration
-                Config::set_profile(&profile, &public_key, &secret_key, Some(&host))?;
+                Config::set_profile(&profile, &public_key, &secret_key, Some(&host), false)?;
+                if quiet {
+                    return Ok(());
+                }
                 eprintln!("Configuration saved to profile '{profile}'");
 
                 if profile != "default" {
@@ -192,6 +293,7 @@ impl ConfigCommands {
         public_key: &str,
         secret_key: &str,
         host: Option<&str>,
+        use_keychain: bool,
     ) -> Result<()> {
         // Test connection before saving
         let test_config = Config::load(
@@ -210,7 +312,7 @@ impl ConfigCommands {
         let client = LangfuseClient::new(&test_config)?;
         match client.test_connection().await {
             Ok(_) => {
-                Config::set_profile(profile, public_key, secret_key, host)?;
+                Config::set_profile(profile, public_key, secret_key, host, use_keychain)?;
                 println!("Configuration saved to profile '{profile}'");
                 if profile != "default" {
                     println!("\nTo use this profile, either:");
@@ -238,7 +340,9 @@ impl ConfigCommands {
                     println!("Public Key: (not set)");
                 }
 
-                if let Some(sk) = &profile.secret_key {
+                if profile.secret_in_keychain() {
+                    println!("Secret Key: (stored in OS keychain)");
+                } else if let Some(sk) = &profile.secret_key {
                     println!("Secret Key: {}", Config::mask_key(sk));
                 } else {
                     println!("Secret Key: (not set)");
@@ -252,11 +356,123 @@ impl ConfigCommands {
 
                 Ok(())
             }
-            None => {
-                eprintln!("Profile '{profile_name}' not found");
-                std::process::exit(1);
+            None => anyhow::bail!("Profile '{profile_name}' not found"),
+        }
+    }
+
+    fn export_profile(&self, profile_name: &str) -> Result<()> {
+        let Some(profile) = Config::get_profile(profile_name)? else {
+            anyhow::bail!("Profile '{profile_name}' not found");
+        };
+
+        let secret_key = Config::profile_secret_key(profile_name, &profile)?;
+        let vars = [
+            ("LANGFUSE_PUBLIC_KEY", profile.public_key.as_deref()),
+            ("LANGFUSE_SECRET_KEY", secret_key.as_deref()),
+            ("LANGFUSE_HOST", profile.host.as_deref()),
+        ];
+
+        for (name, value) in vars {
+            if let Some(value) = value {
+                println!("export {name}={}", shell_quote(value));
             }
         }
+
+        Ok(())
+    }
+
+    fn delete_profile(&self, profile_name: &str) -> Result<()> {
+        if Config::delete_profile(profile_name)? {
+            println!("Profile '{profile_name}' deleted");
+            Ok(())
+        } else {
+            anyhow::bail!("Profile '{profile_name}' not found")
+        }
+    }
+
+    fn use_profile(&self, profile_name: &str) -> Result<()> {
+        if Config::set_default_profile(profile_name)? {
+            println!("Default profile set to '{profile_name}'");
+            Ok(())
+        } else {
+            anyhow::bail!("Profile '{profile_name}' not found")
+        }
+    }
+
+    fn migrate(&self, dry_run: bool) -> Result<()> {
+        let Some((path, changes)) = Config::migrate_config_file(dry_run)? else {
+            println!("No config file found; nothing to migrate");
+            return Ok(());
+        };
+
+        if changes.is_empty() {
+            println!("Config file is up to date: {path:?}");
+            return Ok(());
+        }
+
+        for change in &changes {
+            println!("  {change}");
+        }
+        if dry_run {
+            println!("Dry run: {path:?} was not changed");
+        } else {
+            println!("Migrated {path:?}");
+        }
+        Ok(())
+    }
+
+    async fn test_profile(
+        &self,
+        profile: Option<&str>,
+        host: Option<&str>,
+        global: &GlobalOptions,
+    ) -> Result<()> {
+        let config_file = Config::load_config_file()?;
+        let profile_name = Config::resolve_profile_name(
+            profile,
+            std::env::var("LANGFUSE_PROFILE").ok(),
+            &config_file,
+        );
+
+        // Use the saved profile's own keys so LANGFUSE_PUBLIC_KEY and
+        // LANGFUSE_SECRET_KEY can't stand in for it; they only fill gaps
+        let (public_key, secret_key, saved_host) = match config_file.profiles.get(&profile_name) {
+            Some(saved) => (
+                saved.public_key.clone(),
+                Config::profile_secret_key(&profile_name, saved)?,
+                saved.host.clone(),
+            ),
+            None => (None, None, None),
+        };
+
+        let config = build_config(
+            Some(&profile_name),
+            public_key.as_deref(),
+            secret_key.as_deref(),
+            host.or(saved_host.as_deref()),
+            None,
+            None,
+            None,
+            None,
+            false,
+            false,
+            global,
+        )?;
+
+        if !config.is_valid() {
+            anyhow::bail!(
+                "Profile '{}' has no credentials. Run 'lf config set --profile {}'.",
+                config.profile,
+                config.profile
+            );
+        }
+
+        println!(
+            "{}",
+            key_source(&profile_name, public_key.is_some(), secret_key.is_some())
+        );
+        println!("{}", check_connection(&config).await?);
+        Ok(())
     }
 
     fn list_profiles(&self) -> Result<()> {
@@ -268,11 +484,106 @@ impl ConfigCommands {
         } else {
             println!("Configured profiles:");
             println!("─────────────────────");
+            let default_profile = Config::load_config_file()?.default_profile;
             for profile in profiles {
-                println!("  - {profile}");
+                if default_profile.as_deref() == Some(profile.as_str()) {
+                    println!("  - {profile} (default)");
+                } else {
+                    println!("  - {profile}");
+                }
             }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn profile_config(host: &str) -> Config {
+        Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: host.to_string(),
+            profile: "staging".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_connection_success() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [],
+                "meta": {"page": 1, "limit": 1, "totalItems": 0, "totalPages": 0}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let message = check_connection(&profile_config(&mock_server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            message,
+            format!(
+                "Connection to {} succeeded for profile 'staging'",
+                mock_server.uri()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_connection_reports_auth_failure() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&mock_server)
+            .await;
+
+        let err = check_connection(&profile_config(&mock_server.uri()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Connection to {} failed for profile 'staging'",
+                mock_server.uri()
+            )
+        );
+        assert!(format!("{err:#}").contains("Authentication failed"));
+    }
+
+    #[test]
+    fn test_key_source_names_where_each_key_came_from() {
+        assert_eq!(
+            key_source("staging", true, true),
+            "Using keys from profile 'staging'"
+        );
+        assert_eq!(
+            key_source("staging", false, false),
+            "Using keys from LANGFUSE_PUBLIC_KEY and LANGFUSE_SECRET_KEY"
+        );
+        assert_eq!(
+            key_source("staging", true, false),
+            "Using the public key from profile 'staging' and the secret key from LANGFUSE_SECRET_KEY"
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("sk-lf-123"), "'sk-lf-123'");
+        assert_eq!(shell_quote("a b$c"), "'a b$c'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+}
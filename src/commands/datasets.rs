@@ -1,20 +1,36 @@
 // ABOUTME: Command handlers for dataset management operations
-// ABOUTME: Supports list, get, create for datasets, items, and runs
+// ABOUTME: Supports list, get, create for datasets, items, and runs, plus dataset delete and item update, delete and import
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
-
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
+use futures::stream::{self, StreamExt};
+use std::path::Path;
+
+use crate::client::{is_dry_run, ApiError, LangfuseClient};
+use crate::commands::{
+    build_config, confirm_destructive, exit_if_empty, format_and_output, require_credentials,
+    resolve_limit, GlobalOptions,
+};
+use crate::dataset_import::{parse_items, ImportFormat};
+use crate::json_arg::{check_single_stdin, parse_json_arg};
+use crate::record_filter::keep_matching;
 use crate::types::OutputFormat;
 
 #[derive(Debug, Subcommand)]
 pub enum DatasetsCommands {
     /// List datasets
     List {
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -125,15 +141,53 @@ pub enum DatasetsCommands {
         verbose: bool,
     },
 
+    /// Delete a dataset
+    Delete {
+        /// Dataset name
+        name: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// List dataset items
     Items {
         /// Filter by dataset name
         #[arg(short, long)]
         dataset: Option<String>,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -208,15 +262,15 @@ pub enum DatasetsCommands {
         #[arg(short, long)]
         dataset: String,
 
-        /// Input data as JSON string
+        /// Input data as JSON (inline, @file.json, or - for stdin)
         #[arg(short, long)]
         input: String,
 
-        /// Expected output as JSON string
+        /// Expected output as JSON (inline, @file.json, or - for stdin)
         #[arg(short, long)]
         expected_output: Option<String>,
 
-        /// Metadata as JSON string
+        /// Metadata as JSON (inline, @file.json, or - for stdin)
         #[arg(short, long)]
         metadata: Option<String>,
 
@@ -257,14 +311,129 @@ pub enum DatasetsCommands {
         verbose: bool,
     },
 
+    /// Update a dataset item's input, expected output or metadata
+    ItemUpdate {
+        /// Item ID
+        id: String,
+
+        /// Input data as JSON (inline, @file.json, or - for stdin)
+        #[arg(short, long, required_unless_present_any = ["expected_output", "metadata"])]
+        input: Option<String>,
+
+        /// Expected output as JSON (inline, @file.json, or - for stdin)
+        #[arg(short, long)]
+        expected_output: Option<String>,
+
+        /// Metadata as JSON (inline, @file.json, or - for stdin)
+        #[arg(short, long)]
+        metadata: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Delete a dataset item
+    ItemDelete {
+        /// Item ID
+        id: String,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Create dataset items in bulk from a JSONL or CSV file
+    ItemImport {
+        /// Dataset name to add items to
+        #[arg(short, long)]
+        dataset: String,
+
+        /// File with one item per line (JSONL) or per row (CSV) with
+        /// input, expectedOutput and metadata fields
+        file: String,
+
+        /// File format (guessed from the file extension if omitted)
+        #[arg(short, long, value_enum)]
+        format: Option<ImportFormat>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// List runs for a dataset
     Runs {
         /// Dataset name
         dataset: String,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -335,13 +504,111 @@ pub enum DatasetsCommands {
         #[arg(short, long)]
         verbose: bool,
     },
+
+    /// Create a run with a description and metadata before linking items to it
+    RunCreate {
+        /// Dataset name
+        dataset: String,
+
+        /// Run name
+        run: String,
+
+        /// Run description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Metadata as JSON string
+        #[arg(short, long)]
+        metadata: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Link a trace or observation to a dataset item within a run
+    RunItemCreate {
+        /// Run name (created if it doesn't exist)
+        #[arg(short, long)]
+        run: String,
+
+        /// Dataset item ID
+        #[arg(short, long)]
+        item_id: String,
+
+        /// Trace ID that processed the item
+        #[arg(long, required_unless_present = "observation_id")]
+        trace_id: Option<String>,
+
+        /// Observation ID that processed the item
+        #[arg(long)]
+        observation_id: Option<String>,
+
+        /// Metadata as JSON string
+        #[arg(short, long)]
+        metadata: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
 }
 
 impl DatasetsCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             DatasetsCommands::List {
                 limit,
+                all,
+                fail_empty,
                 page,
                 format,
                 output,
@@ -357,29 +624,31 @@ impl DatasetsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
-                let datasets = client.list_datasets(*limit, *page).await?;
+                let datasets = client
+                    .list_datasets(resolve_limit(config.limit, *all), *page)
+                    .await?;
+                let datasets = keep_matching(datasets, &global.filters)?;
 
                 format_and_output(
                     &datasets,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
-                )
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, datasets.len())
             }
 
             DatasetsCommands::Get {
@@ -403,23 +672,20 @@ impl DatasetsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
                 let dataset = client.get_dataset(name).await?;
 
                 format_and_output(
                     &dataset,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
@@ -446,14 +712,10 @@ impl DatasetsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let parsed_metadata: Option<serde_json::Value> = metadata
                     .as_ref()
@@ -467,15 +729,70 @@ impl DatasetsCommands {
 
                 format_and_output(
                     &dataset,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
+            DatasetsCommands::Delete {
+                name,
+                yes,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                if config.dry_run {
+                    if !config.quiet {
+                        eprintln!("Dry run: would delete dataset '{name}'");
+                    }
+                } else if !*yes {
+                    let confirmed = confirm_destructive(&format!(
+                        "Delete dataset '{name}'? This cannot be undone"
+                    ))?;
+                    if !confirmed {
+                        if !config.quiet {
+                            eprintln!("Aborted");
+                        }
+                        return Ok(());
+                    }
+                }
+
+                let client = LangfuseClient::new(&config)?;
+
+                client.delete_dataset(name).await?;
+
+                if config.verbose {
+                    eprintln!("Dataset '{}' deleted successfully", name);
+                }
+
+                Ok(())
+            }
+
             DatasetsCommands::Items {
                 dataset,
                 limit,
+                all,
+                fail_empty,
                 page,
                 format,
                 output,
@@ -491,31 +808,35 @@ impl DatasetsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
                 let items = client
-                    .list_dataset_items(dataset.as_deref(), *limit, *page)
+                    .list_dataset_items(
+                        dataset.as_deref(),
+                        resolve_limit(config.limit, *all),
+                        *page,
+                    )
                     .await?;
+                let items = keep_matching(items, &global.filters)?;
 
                 format_and_output(
                     &items,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
-                )
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, items.len())
             }
 
             DatasetsCommands::ItemGet {
@@ -539,23 +860,20 @@ impl DatasetsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
                 let item = client.get_dataset_item(id).await?;
 
                 format_and_output(
                     &item,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
@@ -585,23 +903,24 @@ impl DatasetsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
-
-                let parsed_input: serde_json::Value = serde_json::from_str(input)?;
-                let parsed_expected: Option<serde_json::Value> = expected_output
-                    .as_ref()
-                    .map(|e| serde_json::from_str(e))
+                require_credentials(&config)?;
+
+                check_single_stdin(&[
+                    ("--input", Some(input)),
+                    ("--expected-output", expected_output.as_deref()),
+                    ("--metadata", metadata.as_deref()),
+                ])?;
+                let parsed_input = parse_json_arg("--input", input)?;
+                let parsed_expected = expected_output
+                    .as_deref()
+                    .map(|e| parse_json_arg("--expected-output", e))
                     .transpose()?;
-                let parsed_metadata: Option<serde_json::Value> = metadata
-                    .as_ref()
-                    .map(|m| serde_json::from_str(m))
+                let parsed_metadata = metadata
+                    .as_deref()
+                    .map(|m| parse_json_arg("--metadata", m))
                     .transpose()?;
 
                 let client = LangfuseClient::new(&config)?;
@@ -618,15 +937,234 @@ impl DatasetsCommands {
 
                 format_and_output(
                     &item,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            DatasetsCommands::ItemUpdate {
+                id,
+                input,
+                expected_output,
+                metadata,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                check_single_stdin(&[
+                    ("--input", input.as_deref()),
+                    ("--expected-output", expected_output.as_deref()),
+                    ("--metadata", metadata.as_deref()),
+                ])?;
+                let parsed_input = input
+                    .as_deref()
+                    .map(|i| parse_json_arg("--input", i))
+                    .transpose()?;
+                let parsed_expected = expected_output
+                    .as_deref()
+                    .map(|e| parse_json_arg("--expected-output", e))
+                    .transpose()?;
+                let parsed_metadata = metadata
+                    .as_deref()
+                    .map(|m| parse_json_arg("--metadata", m))
+                    .transpose()?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                // Updates go through the upsert endpoint, which needs the item's dataset
+                let existing = client.get_dataset_item(id).await?;
+                let dataset_name = existing
+                    .dataset_name
+                    .ok_or_else(|| anyhow::anyhow!("Dataset item '{}' has no dataset name", id))?;
+
+                let item = client
+                    .update_dataset_item(
+                        id,
+                        &dataset_name,
+                        parsed_input.as_ref(),
+                        parsed_expected.as_ref(),
+                        parsed_metadata.as_ref(),
+                    )
+                    .await?;
+
+                format_and_output(
+                    &item,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
 
+            DatasetsCommands::ItemDelete {
+                id,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                client.delete_dataset_item(id).await?;
+
+                if config.verbose {
+                    eprintln!("Dataset item '{}' deleted successfully", id);
+                }
+
+                Ok(())
+            }
+
+            DatasetsCommands::ItemImport {
+                dataset,
+                file,
+                format,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let import_format = format
+                    .or_else(|| ImportFormat::from_path(Path::new(file)))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Can't tell the format of '{}'. Pass --format jsonl or --format csv",
+                            file
+                        )
+                    })?;
+                let content = std::fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file))?;
+                let rows = parse_items(&content, import_format)?;
+                let total = rows.len();
+
+                let client = LangfuseClient::new(&config)?;
+
+                // Create items with bounded concurrency, carrying on past rows that fail
+                let mut results = stream::iter(rows)
+                    .map(|row| {
+                        let client = &client;
+                        async move {
+                            let item = row.item.map_err(|e| (row.line, e))?;
+                            client
+                                .create_dataset_item(
+                                    dataset,
+                                    &item.input,
+                                    item.expected_output.as_ref(),
+                                    item.metadata.as_ref(),
+                                    None,
+                                    None,
+                                )
+                                .await
+                                .map(|_| ())
+                                .or_else(|e| {
+                                    // A dry run prints each request; that row is done
+                                    if is_dry_run(&e) {
+                                        Ok(())
+                                    } else {
+                                        Err((row.line, e.to_string()))
+                                    }
+                                })
+                        }
+                    })
+                    .buffered(config.concurrency.max(1));
+
+                let mut imported = 0;
+                let mut failures: Vec<(usize, String)> = Vec::new();
+                while let Some(result) = results.next().await {
+                    match result {
+                        Ok(_) => imported += 1,
+                        Err(failure) => failures.push(failure),
+                    }
+                    if !config.quiet && !config.dry_run {
+                        eprint!("\rImporting items: {}/{}", imported + failures.len(), total);
+                    }
+                }
+
+                if !config.quiet {
+                    if total > 0 && !config.dry_run {
+                        eprintln!();
+                    }
+                    let verb = if config.dry_run {
+                        "Would import"
+                    } else {
+                        "Imported"
+                    };
+                    eprintln!(
+                        "{} {} of {} items into '{}'",
+                        verb, imported, total, dataset
+                    );
+                }
+                if failures.is_empty() {
+                    return Ok(());
+                }
+
+                eprintln!("Failed lines:");
+                for (line, error) in &failures {
+                    eprintln!("  line {}: {}", line, error);
+                }
+                anyhow::bail!("{} of {} items failed to import", failures.len(), total)
+            }
+
             DatasetsCommands::Runs {
                 dataset,
                 limit,
+                all,
+                fail_empty,
                 page,
                 format,
                 output,
@@ -642,29 +1180,31 @@ impl DatasetsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
-                let runs = client.list_dataset_runs(dataset, *limit, *page).await?;
+                let runs = client
+                    .list_dataset_runs(dataset, resolve_limit(config.limit, *all), *page)
+                    .await?;
+                let runs = keep_matching(runs, &global.filters)?;
 
                 format_and_output(
                     &runs,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
-                )
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, runs.len())
             }
 
             DatasetsCommands::RunGet {
@@ -689,23 +1229,140 @@ impl DatasetsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!(
-                        "Error: Missing credentials. Run 'lf config setup' or set environment variables."
-                    );
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
                 let run_data = client.get_dataset_run(dataset, run).await?;
 
                 format_and_output(
                     &run_data,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            DatasetsCommands::RunCreate {
+                dataset,
+                run,
+                description,
+                metadata,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let parsed_metadata: Option<serde_json::Value> = metadata
+                    .as_ref()
+                    .map(|m| serde_json::from_str(m))
+                    .transpose()?;
+
+                let client = LangfuseClient::new(&config)?;
+                let dataset_run = client
+                    .create_dataset_run(
+                        dataset,
+                        run,
+                        description.as_deref(),
+                        parsed_metadata.as_ref(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        // Deployments without the endpoint create runs on their first run item
+                        match e.downcast_ref::<ApiError>() {
+                            Some(ApiError::NotFoundError(_))
+                            | Some(ApiError::ApiError { status: 405, .. }) => e.context(format!(
+                                "Could not create run '{run}': dataset '{dataset}' does not exist, or this \
+                                 Langfuse deployment creates runs automatically with \
+                                 'lf datasets run-item-create --run {run}'"
+                            )),
+                            _ => e,
+                        }
+                    })?;
+
+                format_and_output(
+                    &dataset_run,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            DatasetsCommands::RunItemCreate {
+                run,
+                item_id,
+                trace_id,
+                observation_id,
+                metadata,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let parsed_metadata: Option<serde_json::Value> = metadata
+                    .as_ref()
+                    .map(|m| serde_json::from_str(m))
+                    .transpose()?;
+
+                let client = LangfuseClient::new(&config)?;
+                let run_item = client
+                    .create_dataset_run_item(
+                        run,
+                        item_id,
+                        trace_id.as_deref(),
+                        observation_id.as_deref(),
+                        parsed_metadata.as_ref(),
+                    )
+                    .await?;
+
+                format_and_output(
+                    &run_item,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
         }
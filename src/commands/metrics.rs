@@ -1,39 +1,46 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use std::io::{self, Read};
 
 use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
-use crate::types::{Aggregation, Measure, MetricsView, OutputFormat, TimeGranularity};
+use crate::commands::{
+    build_config, format_and_output, output_result, require_credentials, GlobalOptions,
+};
+use crate::time_arg::parse_time_arg;
+use crate::types::{
+    Aggregation, Measure, MetricsFilter, MetricsResult, MetricsView, OutputFormat, TimeGranularity,
+};
 
 #[derive(Debug, Subcommand)]
 pub enum MetricsCommands {
     /// Query metrics with aggregations
     Query {
         /// View to query (traces or observations)
-        #[arg(long, value_enum)]
-        view: MetricsView,
+        #[arg(long, value_enum, required_unless_present = "query_file")]
+        view: Option<MetricsView>,
 
         /// Measure to aggregate
-        #[arg(long, value_enum)]
-        measure: Measure,
+        #[arg(long, value_enum, required_unless_present = "query_file")]
+        measure: Option<Measure>,
 
         /// Aggregation function
-        #[arg(long, value_enum)]
-        aggregation: Aggregation,
+        #[arg(long, value_enum, required_unless_present = "query_file")]
+        aggregation: Option<Aggregation>,
 
-        /// Dimensions for grouping (can be specified multiple times)
-        #[arg(short, long)]
+        /// Dimensions for grouping, e.g. -d providedModelName -d environment
+        /// (can be specified multiple times)
+        #[arg(short, long, visible_alias = "dimension")]
         dimensions: Option<Vec<String>>,
 
-        /// Filter from timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         from: Option<String>,
 
-        /// Filter to timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         to: Option<String>,
 
-        /// Time granularity for bucketing
+        /// Time granularity for bucketing [default: auto when --from or --to is given]
         #[arg(long, value_enum)]
         granularity: Option<TimeGranularity>,
 
@@ -41,6 +48,23 @@ pub enum MetricsCommands {
         #[arg(short, long)]
         limit: Option<u32>,
 
+        /// Filter as FIELD:OPERATOR:VALUE with operator =, !=, in or contains
+        /// (e.g. name:=:chat, userId:in:a,b). Can be specified multiple times.
+        #[arg(long = "filter", value_name = "FIELD:OP:VALUE", value_parser = MetricsFilter::parse)]
+        filters: Vec<MetricsFilter>,
+
+        /// Print only the measure totalled across all returned rows (count and sum aggregations)
+        #[arg(long, visible_alias = "count-only", conflicts_with = "query_file")]
+        summary: bool,
+
+        /// Send a full JSON query body from a file ('-' for stdin) instead of building one from flags
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["view", "measure", "aggregation", "dimensions", "from", "to", "granularity", "limit", "filters"]
+        )]
+        query_file: Option<String>,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -72,7 +96,7 @@ pub enum MetricsCommands {
 }
 
 impl MetricsCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             MetricsCommands::Query {
                 view,
@@ -83,6 +107,9 @@ impl MetricsCommands {
                 to,
                 granularity,
                 limit,
+                filters,
+                summary,
+                query_file,
                 format,
                 output,
                 profile,
@@ -102,41 +129,308 @@ impl MetricsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
-                // Convert view to API string
-                let view_str = match view {
-                    MetricsView::Traces => "traces",
-                    MetricsView::Observations => "observations",
-                };
+                let result = if let Some(path) = query_file {
+                    let query = read_query_file(path)?;
+                    client.query_metrics_raw(&query).await?
+                } else {
+                    // clap guarantees these are present without --query-file
+                    let (Some(view), Some(measure), Some(aggregation)) =
+                        (view, measure, aggregation)
+                    else {
+                        anyhow::bail!("--view, --measure and --aggregation are required");
+                    };
+
+                    if let Some(dimensions) = dimensions {
+                        check_dimensions(view, dimensions)?;
+                    }
+                    let granularity =
+                        granularity_or_auto(granularity.as_ref(), from.as_deref(), to.as_deref());
 
-                let result = client
-                    .query_metrics(
-                        view_str,
-                        measure.to_api_string(),
-                        aggregation.to_api_string(),
-                        dimensions.as_deref(),
-                        from.as_deref(),
-                        to.as_deref(),
-                        granularity.as_ref().map(|g| g.to_api_string()),
-                        *limit,
-                    )
-                    .await?;
+                    let result = client
+                        .query_metrics(
+                            view.to_api_string(),
+                            measure.to_api_string(),
+                            aggregation.to_api_string(),
+                            dimensions.as_deref(),
+                            from.as_deref(),
+                            to.as_deref(),
+                            granularity.as_ref().map(|g| g.to_api_string()),
+                            *limit,
+                            filters,
+                        )
+                        .await?;
+
+                    if *summary {
+                        let total = summarize(&result, measure, aggregation)?;
+                        return output_result(
+                            &total.to_string(),
+                            config.output.as_deref(),
+                            *verbose,
+                            global,
+                        );
+                    }
+                    result
+                };
 
                 format_and_output(
                     &result.data,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
         }
     }
 }
+
+/// Reject dimensions the view can't group by, listing the ones it can
+fn check_dimensions(view: &MetricsView, dimensions: &[String]) -> Result<()> {
+    let known = view.dimensions();
+    for dimension in dimensions {
+        if !known.contains(&dimension.as_str()) {
+            anyhow::bail!(
+                "Unknown dimension '{}' for the {} view. Expected one of: {}",
+                dimension,
+                view.to_api_string(),
+                known.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The requested granularity, or `auto` for a time-bounded query so the API
+/// buckets the range by time
+fn granularity_or_auto(
+    granularity: Option<&TimeGranularity>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Option<TimeGranularity> {
+    granularity
+        .cloned()
+        .or_else(|| (from.is_some() || to.is_some()).then_some(TimeGranularity::Auto))
+}
+
+/// Total of the requested measure across every row (e.g. all time buckets).
+/// Only counts and sums add up meaningfully; averages and percentiles don't.
+fn summarize(result: &MetricsResult, measure: &Measure, aggregation: &Aggregation) -> Result<f64> {
+    if !matches!(aggregation, Aggregation::Count | Aggregation::Sum) {
+        anyhow::bail!(
+            "--summary can only total count or sum aggregations, not {}",
+            aggregation.to_api_string()
+        );
+    }
+
+    let column = format!(
+        "{}_{}",
+        aggregation.to_api_string(),
+        measure.to_api_string()
+    );
+    let mut total = 0.0;
+    for row in &result.data {
+        let value = row
+            .get(&column)
+            .or_else(|| row.get(measure.to_api_string()))
+            .with_context(|| format!("Metrics rows have no '{}' column", column))?;
+        total += metric_number(value)
+            .with_context(|| format!("'{}' value {} is not a number", column, value))?;
+    }
+    Ok(total)
+}
+
+/// A metric value as a number; the API returns some (e.g. counts) as strings
+fn metric_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Null => Some(0.0),
+        _ => None,
+    }
+}
+
+/// Read a metrics query body from a file, or stdin when the path is `-`
+fn read_query_file(path: &str) -> Result<serde_json::Value> {
+    let content = if path == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read query file {}", path))?
+    };
+
+    parse_query(&content).with_context(|| format!("Invalid metrics query in {}", path))
+}
+
+/// Parse a metrics query body, which must be a JSON object
+fn parse_query(content: &str) -> Result<serde_json::Value> {
+    let query: serde_json::Value = serde_json::from_str(content)?;
+    if !query.is_object() {
+        anyhow::bail!("expected a JSON object");
+    }
+    Ok(query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_parse_query_accepts_object() {
+        let query = parse_query(
+            r#"{"view": "traces", "metrics": [{"measure": "count", "aggregation": "count"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(query["view"], json!("traces"));
+    }
+
+    #[test]
+    fn test_parse_query_rejects_malformed_json() {
+        let err = parse_query(r#"{"view": "traces",}"#).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_parse_query_rejects_non_object() {
+        let err = parse_query("[1, 2]").unwrap_err();
+        assert_eq!(err.to_string(), "expected a JSON object");
+    }
+
+    #[test]
+    fn test_read_query_file_reports_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("query.json");
+        std::fs::write(&path, "not json").unwrap();
+
+        let err = read_query_file(path.to_str().unwrap()).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Invalid metrics query in"));
+        assert!(message.contains("query.json"));
+    }
+
+    fn metrics_rows(rows: serde_json::Value) -> MetricsResult {
+        serde_json::from_value(json!({ "data": rows })).unwrap()
+    }
+
+    #[test]
+    fn test_summarize_totals_count_across_rows() {
+        let result = metrics_rows(json!([
+            {"time_dimension": "2024-01-01T00:00:00Z", "count_count": 12},
+            {"time_dimension": "2024-01-02T00:00:00Z", "count_count": "7"},
+            {"time_dimension": "2024-01-03T00:00:00Z", "count_count": 0}
+        ]));
+
+        let total = summarize(&result, &Measure::Count, &Aggregation::Count).unwrap();
+        assert_eq!(total, 19.0);
+        assert_eq!(total.to_string(), "19");
+    }
+
+    #[test]
+    fn test_summarize_picks_requested_measure_column() {
+        let result = metrics_rows(json!([
+            {"sum_totalCost": 0.25, "sum_totalTokens": 1000},
+            {"sum_totalCost": 0.5, "sum_totalTokens": 3000}
+        ]));
+
+        let total = summarize(&result, &Measure::TotalCost, &Aggregation::Sum).unwrap();
+        assert_eq!(total, 0.75);
+    }
+
+    #[test]
+    fn test_summarize_rejects_averages() {
+        let result = metrics_rows(json!([{"avg_latency": 1.5}]));
+        let err = summarize(&result, &Measure::Latency, &Aggregation::Avg).unwrap_err();
+        assert!(err.to_string().contains("not avg"));
+    }
+
+    #[test]
+    fn test_check_dimensions_rejects_unknown_field() {
+        let dimensions = vec!["providedModelName".to_string(), "colour".to_string()];
+        let err = check_dimensions(&MetricsView::Observations, &dimensions).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Unknown dimension 'colour' for the observations view"));
+        assert!(message.contains("environment"));
+
+        let dimensions = vec!["userId".to_string(), "environment".to_string()];
+        assert!(check_dimensions(&MetricsView::Traces, &dimensions).is_ok());
+    }
+
+    #[test]
+    fn test_granularity_defaults_to_auto_for_time_range() {
+        assert!(matches!(
+            granularity_or_auto(None, Some("2024-01-01T00:00:00Z"), None),
+            Some(TimeGranularity::Auto)
+        ));
+        assert!(matches!(
+            granularity_or_auto(
+                Some(&TimeGranularity::Day),
+                Some("2024-01-01T00:00:00Z"),
+                None
+            ),
+            Some(TimeGranularity::Day)
+        ));
+        assert!(granularity_or_auto(None, None, None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_sends_each_dimension_as_a_field() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/public/metrics"))
+            .and(body_partial_json(json!({
+                "view": "observations",
+                "dimensions": [{"field": "providedModelName"}, {"field": "environment"}],
+                "fromTimestamp": "2024-01-01T00:00:00Z",
+                "granularity": "auto"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"providedModelName": "gpt-4", "environment": "production", "count_count": 3}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("metrics.json");
+        let command = MetricsCommands::Query {
+            view: Some(MetricsView::Observations),
+            measure: Some(Measure::Count),
+            aggregation: Some(Aggregation::Count),
+            dimensions: Some(vec![
+                "providedModelName".to_string(),
+                "environment".to_string(),
+            ]),
+            from: Some("2024-01-01T00:00:00Z".to_string()),
+            to: None,
+            granularity: None,
+            limit: None,
+            filters: vec![],
+            summary: false,
+            query_file: None,
+            format: Some(OutputFormat::Json),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let rows: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(rows[0]["environment"], "production");
+    }
+}
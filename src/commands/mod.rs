@@ -6,36 +6,535 @@ pub mod prompts;
 pub mod scores;
 pub mod sessions;
 pub mod traces;
+pub mod whoami;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use clap::Args;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
 
+use crate::client::{
+    ApiError, DEFAULT_CONCURRENCY, DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_TIMEOUT_SECS,
+    MAX_PAGE_SIZE, UNLIMITED,
+};
 use crate::config::Config;
-use crate::formatters::format_output;
-use crate::types::OutputFormat;
+use crate::formatters::{format_output, FormatOptions, NumberFormat};
+use crate::record_filter::{parse_condition, Condition};
+use crate::redact::Redactor;
+use crate::timezone::{convert_timestamps, parse_timezone};
+use crate::types::{ErrorFormat, OutputFormat};
+
+/// Options accepted by every command
+#[derive(Debug, Clone, Default, Args)]
+pub struct GlobalOptions {
+    /// Replace a field's value with "[REDACTED]" in output; dotted paths like
+    /// usage.totalCost reach into nested objects (can be specified multiple times)
+    #[arg(long, global = true, value_name = "FIELD", visible_alias = "redact")]
+    pub censor: Vec<String>,
+
+    /// Redact substrings matching a regex in all string values
+    #[arg(long, global = true, value_name = "REGEX")]
+    pub censor_pattern: Option<String>,
+
+    /// Keep only records where a field (dotted paths allowed) matches: FIELD=VALUE,
+    /// FIELD!=VALUE or FIELD~=VALUE for a substring; applied client-side to list
+    /// results (can be specified multiple times)
+    #[arg(
+        id = "where_conditions",
+        long = "where",
+        global = true,
+        value_name = "CONDITION",
+        value_parser = parse_condition
+    )]
+    pub filters: Vec<Condition>,
+
+    /// Number rendering for table/csv/markdown: auto, fixed:N, thousands or thousands:N
+    #[arg(long, global = true, default_value = "auto")]
+    pub number_format: NumberFormat,
+
+    /// Only show these fields, in this order (comma-separated, e.g. id,name,timestamp)
+    #[arg(
+        long,
+        global = true,
+        value_name = "FIELDS",
+        value_delimiter = ',',
+        visible_alias = "columns"
+    )]
+    pub fields: Vec<String>,
+
+    /// Leave out these fields, keeping the rest (comma-separated, e.g. input,output).
+    /// Applied after --fields
+    #[arg(long, global = true, value_name = "FIELDS", value_delimiter = ',')]
+    pub exclude_fields: Vec<String>,
+
+    /// Read the --fields list from a file (one per line or comma-separated; # starts
+    /// a comment). --fields takes precedence when both are given
+    #[arg(long, global = true, value_name = "PATH")]
+    pub fields_file: Option<PathBuf>,
+
+    /// Gzip-compress output (appends .gz to --output paths that lack it)
+    #[arg(long, global = true, visible_alias = "compress")]
+    pub gzip: bool,
+
+    /// Append to the --output file instead of overwriting it, one write per run
+    #[arg(long, global = true, conflicts_with = "gzip")]
+    pub append: bool,
+
+    /// Split list output into files of at most N records (requires --output-dir)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        requires = "split_output_dir",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub split_size: Option<usize>,
+
+    /// Directory to write split output files (part-0001.csv, part-0002.csv, ...)
+    #[arg(
+        id = "split_output_dir",
+        long = "output-dir",
+        global = true,
+        value_name = "DIR",
+        requires = "split_size"
+    )]
+    pub output_dir: Option<PathBuf>,
+
+    /// Timeout in seconds for each API request (0 for no timeout)
+    #[arg(long, global = true, value_name = "SECS", default_value_t = DEFAULT_TIMEOUT_SECS)]
+    pub timeout: u64,
+
+    /// Seconds allowed to establish each connection, for slow or VPN networks
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECS",
+        visible_alias = "timeout-connect",
+        default_value_t = DEFAULT_CONNECT_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub connect_timeout: u64,
+
+    /// Proxy URL for all requests (defaults to HTTPS_PROXY or ALL_PROXY; NO_PROXY is honoured)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// User-Agent header for API requests [default: lf/<version>]
+    #[arg(long, global = true, value_name = "UA")]
+    pub user_agent: Option<String>,
+
+    /// Timeout in seconds for each page request when listing; a page that times out is retried
+    #[arg(long, global = true, value_name = "SECS")]
+    pub timeout_per_page: Option<u64>,
+
+    /// Flatten nested objects into dotted columns (e.g. usage.input) for table/csv/markdown
+    #[arg(long, global = true)]
+    pub flatten: bool,
+
+    /// Sort records by this field (dotted paths allowed); records without it come last
+    #[arg(long, global = true, value_name = "FIELD")]
+    pub sort: Option<String>,
+
+    /// Reverse the record order (descending with --sort)
+    #[arg(long, global = true)]
+    pub reverse: bool,
+
+    /// Wrap table cells at N characters (nested JSON is then shown in full rather than cut at 50)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        visible_alias = "wrap",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub max_col_width: Option<u16>,
+
+    /// Omit the header row from CSV and TSV output (pair with --fields for stable columns)
+    #[arg(long, global = true)]
+    pub no_header: bool,
+
+    /// Print JSON on a single line instead of pretty-printed (no effect on other formats)
+    #[arg(long, global = true)]
+    pub compact: bool,
+
+    /// Plain ASCII tables with no colors or ANSI escapes
+    #[arg(
+        long,
+        global = true,
+        env = "NO_COLOR",
+        action = clap::ArgAction::SetTrue,
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_color: bool,
+
+    /// Suppress informational messages on stderr; errors are still shown. Overrides --verbose.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// How to report a failure on stderr: text, or json for scripts and orchestration tools
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub error_format: ErrorFormat,
+
+    /// Print the method, URL and JSON body of each create, update or delete request instead of sending it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Show timestamps in this IANA time zone (e.g. America/New_York); JSON and
+    /// JSONL keep UTC unless --convert-timestamps is also given
+    #[arg(long, global = true, value_name = "ZONE", value_parser = parse_timezone)]
+    pub timezone: Option<Tz>,
+
+    /// Apply --timezone to JSON and JSONL output too
+    #[arg(long, global = true, requires = "timezone")]
+    pub convert_timestamps: bool,
+
+    /// Report each page fetched while listing on stderr (also enabled by --verbose)
+    #[arg(long, global = true)]
+    pub progress: bool,
+
+    /// Page output longer than the terminal through $LF_PAGER, $PAGER or less
+    /// (also enabled by setting LF_PAGER)
+    #[arg(long, global = true)]
+    pub pager: bool,
+
+    /// Maximum number of API requests in flight when fetching pages or per-record lookups
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_CONCURRENCY)]
+    pub concurrency: usize,
+
+    /// Items to request per page when listing (capped at the API maximum of 100)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = MAX_PAGE_SIZE,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub page_size: u32,
+}
+
+impl GlobalOptions {
+    /// Formatter options derived from the global flags
+    pub fn format_options(&self) -> Result<FormatOptions> {
+        Ok(FormatOptions {
+            number_format: self.number_format,
+            fields: self.selected_fields()?,
+            exclude_fields: self.exclude_fields.clone(),
+            no_color: self.no_color,
+            flatten: self.flatten,
+            compact: self.compact,
+            sort: self.sort.clone(),
+            reverse: self.reverse,
+            no_header: self.no_header,
+            max_col_width: self.max_col_width.map(usize::from),
+            table_width: None,
+        })
+    }
+
+    /// Fields to show: --fields if given, otherwise those listed in --fields-file
+    fn selected_fields(&self) -> Result<Option<Vec<String>>> {
+        if !self.fields.is_empty() {
+            return Ok(Some(self.fields.clone()));
+        }
+        let Some(path) = &self.fields_file else {
+            return Ok(None);
+        };
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fields file {}", path.display()))?;
+        let fields = parse_fields_list(&content);
+        if fields.is_empty() {
+            anyhow::bail!("Fields file {} lists no fields", path.display());
+        }
+        Ok(Some(fields))
+    }
+
+    /// The zone to show timestamps in for this format, if any. JSON stays in
+    /// UTC for machine readers unless --convert-timestamps asks otherwise.
+    pub fn display_timezone(&self, format: OutputFormat) -> Option<Tz> {
+        let machine_readable = matches!(format, OutputFormat::Json | OutputFormat::Jsonl);
+        self.timezone
+            .filter(|_| self.convert_timestamps || !machine_readable)
+    }
+}
+
+/// Field names from a fields file: separated by newlines or commas, with
+/// blank entries and `#` comments ignored
+fn parse_fields_list(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(str::to_string)
+        .collect()
+}
 
 /// Output result to stdout or file
-pub fn output_result(content: &str, output_path: Option<&str>, verbose: bool) -> Result<()> {
+pub fn output_result(
+    content: &str,
+    output_path: Option<&str>,
+    verbose: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let verbose = verbose && !global.quiet;
+
+    if global.gzip {
+        return output_gzip(content, output_path, verbose);
+    }
+
+    if let Some(path) = output_path {
+        if global.append {
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            // An empty result (e.g. JSONL with no records) adds nothing, not a blank line
+            if !content.is_empty() {
+                writeln!(file, "{content}")?;
+            }
+        } else {
+            fs::write(path, content)?;
+        }
+        if verbose {
+            eprintln!("Output written to: {path}");
+        }
+    } else {
+        let height = console::Term::stdout().size_checked().map(|(rows, _)| rows);
+        let env = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        match pager_command(
+            content,
+            output_path,
+            global,
+            io::stdout().is_terminal(),
+            height,
+            env,
+        ) {
+            Some(pager) if page(content, &pager).is_ok() => {}
+            _ => println!("{content}"),
+        }
+    }
+    Ok(())
+}
+
+/// The pager to show `content` through, if any. Paging needs `--pager` or
+/// LF_PAGER, output bound for a terminal rather than a file or pipe, and more
+/// lines than the terminal has rows. The program comes from LF_PAGER, then
+/// PAGER, then `less`.
+fn pager_command(
+    content: &str,
+    output_path: Option<&str>,
+    global: &GlobalOptions,
+    stdout_is_tty: bool,
+    terminal_height: Option<u16>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let lf_pager = env("LF_PAGER");
+    if !(global.pager || lf_pager.is_some()) || output_path.is_some() || !stdout_is_tty {
+        return None;
+    }
+    if content.lines().count() < usize::from(terminal_height?) {
+        return None;
+    }
+    Some(
+        lf_pager
+            .or_else(|| env("PAGER"))
+            .unwrap_or_else(|| "less".to_string()),
+    )
+}
+
+/// Feed `content` to a pager program (with its arguments, e.g. "less -R")
+/// and wait for the user to quit it
+fn page(content: &str, pager: &str) -> Result<()> {
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or("less");
+    let mut child = Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything
+        let _ = writeln!(stdin, "{content}");
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Write gzip-compressed output to stdout or file
+fn output_gzip(content: &str, output_path: Option<&str>, verbose: bool) -> Result<()> {
     if let Some(path) = output_path {
-        fs::write(path, content)?;
+        let path = gzip_path(path);
+        fs::write(&path, gzip(content.as_bytes())?)?;
         if verbose {
             eprintln!("Output written to: {path}");
         }
     } else {
-        println!("{content}");
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(&gzip(format!("{content}\n").as_bytes())?)?;
+        stdout.flush()?;
     }
     Ok(())
 }
 
-/// Format and output data
+/// Compress bytes with gzip
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Append a .gz extension unless the path already has one
+fn gzip_path(path: &str) -> String {
+    if path.ends_with(".gz") {
+        path.to_string()
+    } else {
+        format!("{path}.gz")
+    }
+}
+
+/// Format and output data, applying any redaction rules first
 pub fn format_and_output<T: serde::Serialize>(
     data: &T,
     format: OutputFormat,
     output_path: Option<&str>,
     verbose: bool,
+    global: &GlobalOptions,
 ) -> Result<()> {
-    let formatted = format_output(data, format)?;
-    output_result(&formatted, output_path, verbose)
+    let verbose = verbose && !global.quiet;
+    let redactor = Redactor::new(&global.censor, global.censor_pattern.as_deref())?;
+
+    let mut options = global.format_options()?;
+    // Tables shown on a terminal are wrapped to fit its width
+    if output_path.is_none() && io::stdout().is_terminal() {
+        options.table_width = console::Term::stdout()
+            .size_checked()
+            .map(|(_, columns)| usize::from(columns));
+    }
+
+    let timezone = global.display_timezone(format);
+
+    if let (Some(size), Some(dir)) = (global.split_size, &global.output_dir) {
+        let mut value = serde_json::to_value(data)?;
+        redactor.apply(&mut value);
+        if let Some(tz) = timezone {
+            convert_timestamps(&mut value, tz);
+        }
+        return output_split(value, format, size, dir, verbose, global);
+    }
+
+    let repeats_header = matches!(
+        format,
+        OutputFormat::Table
+            | OutputFormat::Csv
+            | OutputFormat::Tsv
+            | OutputFormat::Markdown
+            | OutputFormat::Html
+    );
+    if global.append && output_path.is_some() && repeats_header && !global.quiet {
+        eprintln!(
+            "Warning: --append repeats the header row of table, csv, tsv, markdown and html output on every run; use --format jsonl for a continuous file"
+        );
+    }
+
+    let formatted = if redactor.is_empty() && timezone.is_none() {
+        format_output(data, format, &options)?
+    } else {
+        let mut value = serde_json::to_value(data)?;
+        redactor.apply(&mut value);
+        if let Some(tz) = timezone {
+            convert_timestamps(&mut value, tz);
+        }
+        format_output(&value, format, &options)?
+    };
+
+    output_result(&formatted, output_path, verbose, global)
+}
+
+/// Write records to numbered part files of at most `size` records each.
+/// Each part is formatted independently, so CSV parts all carry a header row.
+fn output_split(
+    value: serde_json::Value,
+    format: OutputFormat,
+    size: usize,
+    dir: &Path,
+    verbose: bool,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let records = match value {
+        serde_json::Value::Array(records) => records,
+        other => vec![other],
+    };
+
+    fs::create_dir_all(dir)?;
+
+    // Order the whole set before splitting, then leave each part's order alone
+    let options = global.format_options()?;
+    let records = match options.ordered(serde_json::Value::Array(records)) {
+        serde_json::Value::Array(records) => records,
+        other => vec![other],
+    };
+    let options = FormatOptions {
+        sort: None,
+        reverse: false,
+        ..options
+    };
+
+    for (i, chunk) in records.chunks(size).enumerate() {
+        let formatted = format_output(&chunk, format, &options)?;
+        let path = split_part_path(dir, i + 1, format);
+        output_result(&formatted, path.to_str(), verbose, global)?;
+    }
+
+    Ok(())
+}
+
+/// Path of the nth (1-based) split output file
+fn split_part_path(dir: &Path, part: usize, format: OutputFormat) -> PathBuf {
+    dir.join(format!("part-{part:04}.{}", format.extension()))
+}
+
+/// The limit to pass to list calls, where `--all` means no limit
+pub fn resolve_limit(limit: u32, all: bool) -> u32 {
+    if all {
+        UNLIMITED
+    } else {
+        limit
+    }
+}
+
+/// Exit status for `--fail-empty` when a list matched nothing
+pub const EMPTY_EXIT_CODE: i32 = 2;
+
+/// With `--fail-empty`, exit with EMPTY_EXIT_CODE if nothing matched.
+/// Call after writing the output so the empty result is still printed.
+pub fn exit_if_empty(fail_empty: bool, count: usize) -> Result<()> {
+    if fail_empty && count == 0 {
+        std::process::exit(EMPTY_EXIT_CODE);
+    }
+    Ok(())
+}
+
+/// Ask a y/N question before a destructive action, defaulting to no
+pub fn confirm_destructive(prompt: &str) -> Result<bool> {
+    dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .context("Could not prompt for confirmation; pass --yes to delete")
+}
+
+/// Fail with `ApiError::MissingCredentials` unless `config` has both keys
+pub fn require_credentials(config: &Config) -> Result<()> {
+    if !config.is_valid() {
+        return Err(ApiError::MissingCredentials.into());
+    }
+    Ok(())
 }
 
 /// Helper to build config from CLI args
@@ -51,8 +550,322 @@ pub fn build_config(
     output: Option<&str>,
     verbose: bool,
     no_color: bool,
+    global: &GlobalOptions,
 ) -> Result<Config> {
-    Config::load(
-        profile, public_key, secret_key, host, format, limit, page, output, verbose, no_color,
-    )
+    let mut config = Config::load(
+        profile,
+        public_key,
+        secret_key,
+        host,
+        format,
+        limit,
+        page,
+        output,
+        verbose,
+        no_color || global.no_color,
+    )?;
+    config.page_timeout = global.timeout_per_page.map(Duration::from_secs);
+    config.concurrency = global.concurrency;
+    config.page_size = global.page_size;
+    config.timeout_secs = global.timeout;
+    config.connect_timeout_secs = global.connect_timeout;
+    config.proxy = Config::resolve_proxy(global.proxy.as_deref(), |name| std::env::var(name).ok());
+    config.user_agent = global.user_agent.clone();
+    config.quiet = global.quiet;
+    config.verbose = verbose && !global.quiet;
+    config.progress = (global.progress || verbose) && !global.quiet;
+    config.dry_run = global.dry_run;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_config_quiet_overrides_verbose() {
+        let global = GlobalOptions {
+            quiet: true,
+            ..Default::default()
+        };
+
+        let config = build_config(
+            Some("quiet-test"),
+            Some("pk-test"),
+            Some("sk-test"),
+            Some("https://example.com"),
+            None,
+            None,
+            None,
+            None,
+            true,
+            false,
+            &global,
+        )
+        .unwrap();
+
+        assert!(config.quiet);
+        assert!(!config.verbose);
+        assert!(!config.progress);
+    }
+
+    #[test]
+    fn test_exit_if_empty_passes_when_not_requested_or_not_empty() {
+        assert!(exit_if_empty(false, 0).is_ok());
+        assert!(exit_if_empty(true, 3).is_ok());
+    }
+
+    #[test]
+    fn test_split_part_path() {
+        assert_eq!(
+            split_part_path(Path::new("out"), 1, OutputFormat::Csv),
+            Path::new("out/part-0001.csv")
+        );
+        assert_eq!(
+            split_part_path(Path::new("out"), 12, OutputFormat::Jsonl),
+            Path::new("out/part-0012.jsonl")
+        );
+    }
+
+    #[test]
+    fn test_split_output_writes_parts_with_headers() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = GlobalOptions {
+            split_size: Some(2),
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let data = serde_json::json!([
+            {"id": "1"},
+            {"id": "2"},
+            {"id": "3"}
+        ]);
+
+        format_and_output(&data, OutputFormat::Csv, None, false, &global).unwrap();
+
+        let part1 = fs::read_to_string(temp_dir.path().join("part-0001.csv")).unwrap();
+        let part2 = fs::read_to_string(temp_dir.path().join("part-0002.csv")).unwrap();
+        assert_eq!(part1.lines().collect::<Vec<_>>(), ["id", "1", "2"]);
+        assert_eq!(part2.lines().collect::<Vec<_>>(), ["id", "3"]);
+        assert!(!temp_dir.path().join("part-0003.csv").exists());
+    }
+
+    #[test]
+    fn test_gzip_path_appends_extension() {
+        assert_eq!(gzip_path("traces.json"), "traces.json.gz");
+        assert_eq!(gzip_path("traces.json.gz"), "traces.json.gz");
+    }
+
+    #[test]
+    fn test_output_result_writes_gzip_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("traces.json");
+        let global = GlobalOptions {
+            gzip: true,
+            ..Default::default()
+        };
+
+        output_result("[1, 2, 3]", path.to_str(), false, &global).unwrap();
+
+        assert!(!path.exists());
+        let compressed = fs::read(temp_dir.path().join("traces.json.gz")).unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(&compressed[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "[1, 2, 3]");
+    }
+
+    #[test]
+    fn test_gzip_output_round_trips_to_plain_formatting() {
+        let temp_dir = TempDir::new().unwrap();
+        let plain = temp_dir.path().join("plain.jsonl");
+        let compressed = temp_dir.path().join("compressed.jsonl");
+        let records = serde_json::json!([
+            {"id": "t-1", "name": "chat", "usage": {"total": 42}},
+            {"id": "t-2", "name": "search", "usage": {"total": 7}}
+        ]);
+
+        format_and_output(
+            &records,
+            OutputFormat::Jsonl,
+            plain.to_str(),
+            false,
+            &GlobalOptions::default(),
+        )
+        .unwrap();
+        let global = GlobalOptions {
+            gzip: true,
+            ..Default::default()
+        };
+        format_and_output(
+            &records,
+            OutputFormat::Jsonl,
+            compressed.to_str(),
+            false,
+            &global,
+        )
+        .unwrap();
+
+        let bytes = fs::read(temp_dir.path().join("compressed.jsonl.gz")).unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(&bytes[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, fs::read_to_string(&plain).unwrap());
+    }
+
+    #[test]
+    fn test_output_result_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("traces.jsonl");
+        let global = GlobalOptions {
+            append: true,
+            ..Default::default()
+        };
+
+        output_result("{\"id\":\"a\"}", path.to_str(), false, &global).unwrap();
+        output_result("", path.to_str(), false, &global).unwrap();
+        output_result("{\"id\":\"b\"}", path.to_str(), false, &global).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"id\":\"a\"}\n{\"id\":\"b\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_pager_not_used_when_writing_to_file() {
+        let global = GlobalOptions {
+            pager: true,
+            ..Default::default()
+        };
+        let content = "line\n".repeat(100);
+        let env = |_: &str| Some("less -R".to_string());
+
+        assert_eq!(
+            pager_command(&content, Some("out.txt"), &global, true, Some(24), env),
+            None
+        );
+        assert_eq!(
+            pager_command(&content, None, &global, true, Some(24), env),
+            Some("less -R".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pager_only_for_long_output_on_a_terminal() {
+        let global = GlobalOptions {
+            pager: true,
+            ..Default::default()
+        };
+        let no_env = |_: &str| None;
+        let long = "line\n".repeat(100);
+
+        assert_eq!(
+            pager_command(&long, None, &global, false, Some(24), no_env),
+            None
+        );
+        assert_eq!(
+            pager_command("short", None, &global, true, Some(24), no_env),
+            None
+        );
+        assert_eq!(
+            pager_command(&long, None, &global, true, Some(24), no_env),
+            Some("less".to_string())
+        );
+        assert_eq!(
+            pager_command(
+                &long,
+                None,
+                &GlobalOptions::default(),
+                true,
+                Some(24),
+                no_env
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_output_result_with_pager_writes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("traces.json");
+        let global = GlobalOptions {
+            pager: true,
+            ..Default::default()
+        };
+
+        output_result("[]", path.to_str(), false, &global).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_timezone_leaves_json_in_utc_unless_converting() {
+        let tz = crate::timezone::parse_timezone("America/New_York").unwrap();
+        let global = GlobalOptions {
+            timezone: Some(tz),
+            ..Default::default()
+        };
+        assert_eq!(global.display_timezone(OutputFormat::Table), Some(tz));
+        assert_eq!(global.display_timezone(OutputFormat::Csv), Some(tz));
+        assert_eq!(global.display_timezone(OutputFormat::Json), None);
+        assert_eq!(global.display_timezone(OutputFormat::Jsonl), None);
+
+        let global = GlobalOptions {
+            convert_timestamps: true,
+            ..global
+        };
+        assert_eq!(global.display_timezone(OutputFormat::Json), Some(tz));
+    }
+
+    #[test]
+    fn test_parse_fields_list_accepts_lines_commas_and_comments() {
+        let content = "# standard report columns\nid\nname, userId\n\ntimestamp  # UTC\n";
+        assert_eq!(
+            parse_fields_list(content),
+            ["id", "name", "userId", "timestamp"]
+        );
+    }
+
+    #[test]
+    fn test_fields_file_selects_columns() {
+        let temp_dir = TempDir::new().unwrap();
+        let fields_file = temp_dir.path().join("fields.txt");
+        fs::write(&fields_file, "id\nuserId\n").unwrap();
+        let output = temp_dir.path().join("traces.csv");
+        let global = GlobalOptions {
+            fields_file: Some(fields_file),
+            ..Default::default()
+        };
+        let traces = serde_json::json!([
+            {"id": "t-1", "name": "chat", "userId": "alice", "timestamp": "2024-01-01T00:00:00Z"}
+        ]);
+
+        format_and_output(&traces, OutputFormat::Csv, output.to_str(), false, &global).unwrap();
+
+        let csv = fs::read_to_string(&output).unwrap();
+        assert_eq!(csv.lines().collect::<Vec<_>>(), ["id,userId", "t-1,alice"]);
+    }
+
+    #[test]
+    fn test_fields_flag_overrides_fields_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let fields_file = temp_dir.path().join("fields.txt");
+        fs::write(&fields_file, "id,userId").unwrap();
+        let global = GlobalOptions {
+            fields: vec!["name".to_string()],
+            fields_file: Some(fields_file),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            global.format_options().unwrap().fields,
+            Some(vec!["name".to_string()])
+        );
+    }
 }
@@ -1,9 +1,21 @@
 use anyhow::Result;
-use clap::Subcommand;
-
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
-use crate::types::{ObservationType, OutputFormat};
+use clap::{Subcommand, ValueEnum};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use crate::client::{LangfuseClient, UNLIMITED};
+use crate::commands::{
+    build_config, exit_if_empty, format_and_output, output_result, require_credentials,
+    resolve_limit, GlobalOptions,
+};
+use crate::histogram;
+use crate::record_filter::keep_matching;
+use crate::time_arg::{check_time_window, parse_time_arg};
+use crate::types::{Observation, ObservationLevel, ObservationType, OutputFormat};
+
+/// Default number of descendant levels fetched by `get --with-children`
+const DEFAULT_CHILD_DEPTH: usize = 10;
 
 #[derive(Debug, Subcommand)]
 pub enum ObservationsCommands {
@@ -18,24 +30,53 @@ pub enum ObservationsCommands {
         name: Option<String>,
 
         /// Filter by observation type
-        #[arg(long, value_enum)]
+        #[arg(long, value_enum, ignore_case = true)]
         r#type: Option<ObservationType>,
 
+        /// Filter by level
+        #[arg(long, value_enum, ignore_case = true)]
+        level: Option<ObservationLevel>,
+
+        /// Filter by parent observation ID
+        #[arg(long)]
+        parent_id: Option<String>,
+
         /// Filter by user ID
         #[arg(short, long)]
         user_id: Option<String>,
 
-        /// Filter from start time (ISO 8601 format)
-        #[arg(long)]
+        /// Filter from start time (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         from: Option<String>,
 
-        /// Filter to start time (ISO 8601 format)
-        #[arg(long)]
+        /// Filter to start time (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         to: Option<String>,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Filter by environment (e.g., production, staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Only keep observations using this model (e.g., gpt-4o). Applied after
+        /// fetching, so --limit counts observations before filtering
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Output a histogram of value counts for this field instead of the records
+        #[arg(long, value_name = "FIELD")]
+        count_by: Option<String>,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -70,11 +111,96 @@ pub enum ObservationsCommands {
         verbose: bool,
     },
 
+    /// Count observations matching the filters without fetching them, or
+    /// break the count down by a field with --group-by
+    Count {
+        /// Filter by trace ID
+        #[arg(short, long)]
+        trace_id: Option<String>,
+
+        /// Filter by observation name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Filter by observation type
+        #[arg(long, value_enum, ignore_case = true)]
+        r#type: Option<ObservationType>,
+
+        /// Filter by level
+        #[arg(long, value_enum, ignore_case = true)]
+        level: Option<ObservationLevel>,
+
+        /// Filter by parent observation ID
+        #[arg(long)]
+        parent_id: Option<String>,
+
+        /// Filter by user ID
+        #[arg(short, long)]
+        user_id: Option<String>,
+
+        /// Filter from start time (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        from: Option<String>,
+
+        /// Filter to start time (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        to: Option<String>,
+
+        /// Filter by environment (e.g., production, staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Only count observations using this model (requires --group-by, which
+        /// fetches the observations)
+        #[arg(long, requires = "group_by")]
+        model: Option<String>,
+
+        /// Fetch every matching observation and count them per value of this field
+        #[arg(long, value_enum, value_name = "FIELD")]
+        group_by: Option<ObservationGroup>,
+
+        /// Output format for --group-by counts
+        #[arg(short, long, value_enum, requires = "group_by")]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Get a specific observation by ID
     Get {
         /// Observation ID
         id: String,
 
+        /// Include descendant observations, nested under a `children` field
+        #[arg(long)]
+        with_children: bool,
+
+        /// How many levels of descendants to fetch with --with-children
+        #[arg(long, value_name = "N", default_value_t = DEFAULT_CHILD_DEPTH, requires = "with_children")]
+        max_depth: usize,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -106,16 +232,23 @@ pub enum ObservationsCommands {
 }
 
 impl ObservationsCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             ObservationsCommands::List {
                 trace_id,
                 name,
                 r#type,
+                level,
+                parent_id,
                 user_id,
                 from,
                 to,
+                env,
+                model,
                 limit,
+                all,
+                fail_empty,
+                count_by,
                 page,
                 format,
                 output,
@@ -131,17 +264,17 @@ impl ObservationsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
+
+                check_time_window(from.as_deref(), to.as_deref())?;
 
                 let client = LangfuseClient::new(&config)?;
 
@@ -152,24 +285,137 @@ impl ObservationsCommands {
                         trace_id.as_deref(),
                         name.as_deref(),
                         obs_type_str,
+                        level.map(|l| l.to_api_string()),
+                        parent_id.as_deref(),
                         user_id.as_deref(),
                         from.as_deref(),
                         to.as_deref(),
-                        *limit,
+                        env.as_deref(),
+                        resolve_limit(config.limit, *all),
                         *page,
                     )
                     .await?;
+                let observations = filter_by_model(observations, model.as_deref());
+                let observations = keep_matching(observations, &global.filters)?;
+
+                if let Some(field) = count_by {
+                    format_and_output(
+                        &histogram::count_by(&observations, field)?,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    )?;
+                    return exit_if_empty(*fail_empty, observations.len());
+                }
 
                 format_and_output(
                     &observations,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, observations.len())
+            }
+
+            ObservationsCommands::Count {
+                trace_id,
+                name,
+                r#type,
+                level,
+                parent_id,
+                user_id,
+                from,
+                to,
+                env,
+                model,
+                group_by,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                check_time_window(from.as_deref(), to.as_deref())?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                if let Some(group) = group_by {
+                    let observations = client
+                        .list_observations(
+                            trace_id.as_deref(),
+                            name.as_deref(),
+                            r#type.as_ref().map(|t| t.to_api_string()),
+                            level.map(|l| l.to_api_string()),
+                            parent_id.as_deref(),
+                            user_id.as_deref(),
+                            from.as_deref(),
+                            to.as_deref(),
+                            env.as_deref(),
+                            UNLIMITED,
+                            1,
+                        )
+                        .await?;
+                    let observations = filter_by_model(observations, model.as_deref());
+
+                    return format_and_output(
+                        &group_counts(&observations, *group)?,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    );
+                }
+
+                let total = client
+                    .count_observations(
+                        trace_id.as_deref(),
+                        name.as_deref(),
+                        r#type.as_ref().map(|t| t.to_api_string()),
+                        level.map(|l| l.to_api_string()),
+                        parent_id.as_deref(),
+                        user_id.as_deref(),
+                        from.as_deref(),
+                        to.as_deref(),
+                        env.as_deref(),
+                    )
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("The API did not report a total observation count")
+                    })?;
+
+                output_result(
+                    &total.to_string(),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
 
             ObservationsCommands::Get {
                 id,
+                with_children,
+                max_depth,
                 format,
                 output,
                 profile,
@@ -189,24 +435,318 @@ impl ObservationsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
                 let observation = client.get_observation(id).await?;
 
+                if *with_children {
+                    let subtree =
+                        observation_subtree(&client, observation, *max_depth, config.concurrency)
+                            .await?;
+                    return format_and_output(
+                        &subtree,
+                        config.format_or(OutputFormat::Json),
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    );
+                }
+
                 format_and_output(
                     &observation,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
         }
     }
 }
+
+/// Fields `observations count --group-by` can break the count down by
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ObservationGroup {
+    Type,
+    Model,
+    Level,
+    Name,
+    Environment,
+}
+
+impl ObservationGroup {
+    /// The observation field this groups by
+    fn field(self) -> &'static str {
+        match self {
+            ObservationGroup::Type => "type",
+            ObservationGroup::Model => "model",
+            ObservationGroup::Level => "level",
+            ObservationGroup::Name => "name",
+            ObservationGroup::Environment => "environment",
+        }
+    }
+}
+
+/// How many observations share each value of the grouping field, most common first
+fn group_counts(
+    observations: &[Observation],
+    group: ObservationGroup,
+) -> Result<Vec<histogram::ValueCount>> {
+    histogram::count_by(observations, group.field())
+}
+
+/// Keep only observations whose model is exactly `model`. The list endpoint
+/// has no model filter, so this runs on the fetched page(s).
+fn filter_by_model(observations: Vec<Observation>, model: Option<&str>) -> Vec<Observation> {
+    match model {
+        Some(model) => observations
+            .into_iter()
+            .filter(|o| o.model.as_deref() == Some(model))
+            .collect(),
+        None => observations,
+    }
+}
+
+/// The observation as JSON with its descendants nested under `children`,
+/// fetched one level at a time by `parentObservationId` down to `max_depth`
+async fn observation_subtree(
+    client: &LangfuseClient,
+    root: Observation,
+    max_depth: usize,
+    concurrency: usize,
+) -> Result<Value> {
+    let mut children: HashMap<String, Vec<Observation>> = HashMap::new();
+    let mut seen: HashSet<String> = HashSet::from([root.id.clone()]);
+    let mut frontier = vec![root.id.clone()];
+
+    for _ in 0..max_depth {
+        if frontier.is_empty() {
+            break;
+        }
+
+        let levels: Vec<(String, Vec<Observation>)> = stream::iter(frontier)
+            .map(|parent_id| async move {
+                let found = client
+                    .list_observations(
+                        None,
+                        None,
+                        None,
+                        None,
+                        Some(&parent_id),
+                        None,
+                        None,
+                        None,
+                        None,
+                        UNLIMITED,
+                        1,
+                    )
+                    .await?;
+                Ok::<_, anyhow::Error>((parent_id, found))
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        frontier = Vec::new();
+        for (parent_id, found) in levels {
+            // Guard against cycles: each observation is placed once
+            let found: Vec<Observation> = found
+                .into_iter()
+                .filter(|o| seen.insert(o.id.clone()))
+                .collect();
+            frontier.extend(found.iter().map(|o| o.id.clone()));
+            children.insert(parent_id, found);
+        }
+    }
+
+    nest(root, &mut children)
+}
+
+/// Attach fetched children recursively; nodes past the depth limit get no field
+fn nest(
+    observation: Observation,
+    children: &mut HashMap<String, Vec<Observation>>,
+) -> Result<Value> {
+    let own = children.remove(&observation.id);
+    let mut value = serde_json::to_value(observation)?;
+
+    if let (Some(own), Value::Object(map)) = (own, &mut value) {
+        let nested = own
+            .into_iter()
+            .map(|child| nest(child, children))
+            .collect::<Result<Vec<_>>>()?;
+        map.insert("children".to_string(), Value::Array(nested));
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock_children(server: &MockServer, parent: &str, ids: &[&str]) {
+        let data: Vec<Value> = ids
+            .iter()
+            .map(|id| json!({"id": id, "parentObservationId": parent}))
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/api/public/observations"))
+            .and(query_param("parentObservationId", parent))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": data,
+                "meta": {"page": 1, "limit": 100, "totalItems": ids.len(), "totalPages": 1}
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn test_group_counts_by_type_and_model() {
+        let observations: Vec<Observation> = serde_json::from_value(json!([
+            {"id": "1", "type": "GENERATION", "model": "gpt-4o"},
+            {"id": "2", "type": "SPAN"},
+            {"id": "3", "type": "GENERATION", "model": "gpt-4o"},
+            {"id": "4", "type": "GENERATION", "model": "claude-3-5-sonnet"},
+            {"id": "5", "type": "EVENT"}
+        ]))
+        .unwrap();
+
+        let by_type = group_counts(&observations, ObservationGroup::Type).unwrap();
+        let by_type: Vec<(&str, usize)> = by_type
+            .iter()
+            .map(|row| (row.value.as_str(), row.count))
+            .collect();
+        assert_eq!(by_type, [("GENERATION", 3), ("EVENT", 1), ("SPAN", 1)]);
+
+        let by_model = group_counts(&observations, ObservationGroup::Model).unwrap();
+        let by_model: Vec<(&str, usize)> = by_model
+            .iter()
+            .map(|row| (row.value.as_str(), row.count))
+            .collect();
+        assert_eq!(
+            by_model,
+            [("(none)", 2), ("gpt-4o", 2), ("claude-3-5-sonnet", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_model_filter_keeps_matching_observations() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/observations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "obs-1", "model": "gpt-4"},
+                    {"id": "obs-2", "model": "gpt-4o"},
+                    {"id": "obs-3"},
+                    {"id": "obs-4", "model": "gpt-4"}
+                ],
+                "meta": {"page": 1, "limit": 50, "totalItems": 4, "totalPages": 1}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("observations.json");
+        let command = ObservationsCommands::List {
+            trace_id: None,
+            name: None,
+            r#type: None,
+            level: None,
+            parent_id: None,
+            user_id: None,
+            from: None,
+            to: None,
+            env: None,
+            model: Some("gpt-4".to_string()),
+            limit: None,
+            all: false,
+            fail_empty: false,
+            count_by: None,
+            page: 1,
+            format: Some(OutputFormat::Json),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let listed: Vec<Value> =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|o| o["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, ["obs-1", "obs-4"]);
+    }
+
+    #[tokio::test]
+    async fn test_observation_subtree_nests_two_levels() {
+        let mock_server = MockServer::start().await;
+        mock_children(&mock_server, "root", &["child-a", "child-b"]).await;
+        mock_children(&mock_server, "child-a", &["grandchild"]).await;
+        mock_children(&mock_server, "child-b", &[]).await;
+        mock_children(&mock_server, "grandchild", &[]).await;
+
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: mock_server.uri(),
+            ..Default::default()
+        };
+        let client = LangfuseClient::new(&config).unwrap();
+        let root: Observation = serde_json::from_value(json!({"id": "root"})).unwrap();
+
+        let subtree = observation_subtree(&client, root, DEFAULT_CHILD_DEPTH, 2)
+            .await
+            .unwrap();
+
+        let ids = |node: &Value| -> Vec<String> {
+            node["children"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|c| c["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(subtree["id"], "root");
+        assert_eq!(ids(&subtree), vec!["child-a", "child-b"]);
+        assert_eq!(ids(&subtree["children"][0]), vec!["grandchild"]);
+        assert_eq!(subtree["children"][0]["children"][0]["children"], json!([]));
+        assert_eq!(subtree["children"][1]["children"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_observation_subtree_stops_at_max_depth() {
+        let mock_server = MockServer::start().await;
+        mock_children(&mock_server, "root", &["child"]).await;
+
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: mock_server.uri(),
+            ..Default::default()
+        };
+        let client = LangfuseClient::new(&config).unwrap();
+        let root: Observation = serde_json::from_value(json!({"id": "root"})).unwrap();
+
+        let subtree = observation_subtree(&client, root, 1, 2).await.unwrap();
+
+        assert_eq!(subtree["children"][0]["id"], "child");
+        assert!(subtree["children"][0].get("children").is_none());
+    }
+}
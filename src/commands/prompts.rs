@@ -1,13 +1,29 @@
 // ABOUTME: Command handlers for prompt management operations
-// ABOUTME: Supports list, get, create-text, create-chat, label, and delete
+// ABOUTME: Supports list, get, create-text, create-chat, label, export, import, and delete
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use dialoguer::Input;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use regex::Regex;
+use std::fmt;
 use std::io::{self, Read};
-
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output, output_result};
-use crate::types::{ChatMessage, OutputFormat, PromptContent};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::client::{is_dry_run, ApiError, LangfuseClient, UNLIMITED};
+use crate::commands::{
+    build_config, confirm_destructive, exit_if_empty, format_and_output, output_result,
+    require_credentials, resolve_limit, GlobalOptions,
+};
+use crate::formatters::JsonFormatter;
+use crate::prompt_diff;
+use crate::prompt_files::{self, PromptFile};
+use crate::record_filter::keep_matching;
+use crate::types::{ChatMessage, OutputFormat, Prompt, PromptContent, PromptMeta, PromptVersion};
+
+/// Characters of content shown per prompt by `prompts list --content`
+const CONTENT_PREVIEW_CHARS: usize = 80;
 
 #[derive(Debug, Subcommand)]
 pub enum PromptsCommands {
@@ -25,9 +41,22 @@ pub enum PromptsCommands {
         #[arg(short, long)]
         tag: Option<String>,
 
-        /// Maximum number of results
-        #[arg(long, default_value = "50")]
-        limit: u32,
+        /// Include a preview of each prompt's latest content (slower: one extra
+        /// request per prompt)
+        #[arg(long)]
+        content: bool,
+
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
 
         /// Page number
         #[arg(long, default_value = "1")]
@@ -63,6 +92,7 @@ pub enum PromptsCommands {
     },
 
     /// Get a specific prompt by name
+    #[command(disable_version_flag = true)]
     Get {
         /// Prompt name
         name: String,
@@ -75,10 +105,18 @@ pub enum PromptsCommands {
         #[arg(short, long)]
         label: Option<String>,
 
+        /// Label (or version:N) to try if the requested one doesn't exist (repeatable, tried in order)
+        #[arg(long, value_name = "LABEL")]
+        fallback_label: Vec<PromptSelector>,
+
         /// Output raw content only (for piping)
         #[arg(long)]
         raw: bool,
 
+        /// Report the version number and labels the request resolved to on stderr
+        #[arg(long, visible_alias = "version-label")]
+        resolve: bool,
+
         /// Output format (ignored if --raw)
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -108,6 +146,237 @@ pub enum PromptsCommands {
         verbose: bool,
     },
 
+    /// Render a prompt with {{variable}} placeholders filled in
+    #[command(disable_version_flag = true)]
+    Render {
+        /// Prompt name
+        name: String,
+
+        /// Specific version number
+        #[arg(long)]
+        version: Option<i32>,
+
+        /// Fetch by label (default: production)
+        #[arg(short, long)]
+        label: Option<String>,
+
+        /// Variable to substitute (can be specified multiple times)
+        #[arg(long, value_name = "KEY=VALUE", value_parser = parse_var)]
+        var: Vec<(String, String)>,
+
+        /// Output format for chat prompts (default: json); text prompts are printed as-is
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// List every version of a prompt with its labels
+    Versions {
+        /// Prompt name
+        name: String,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Find prompts whose latest content contains some text (case-insensitive)
+    Search {
+        /// Text to look for
+        query: String,
+
+        /// Search the version with this label instead of latest, skipping prompts without it
+        #[arg(short, long)]
+        label: Option<String>,
+
+        /// Only search prompts with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Exit with status 2 if no prompt matches (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Write every version of a prompt to files in --output-dir for version control
+    Export {
+        /// Prompt name
+        #[arg(required_unless_present = "all")]
+        name: Option<String>,
+
+        /// Export every prompt in the project
+        #[arg(long, conflicts_with = "name")]
+        all: bool,
+
+        /// Directory to write the prompt files to (created if missing)
+        // Shares the global --output-dir id so clap lets this flag shadow it;
+        // --split-size is rejected so the directory never feeds split output.
+        #[arg(
+            id = "split_output_dir",
+            long = "output-dir",
+            value_name = "DIR",
+            conflicts_with = "split_size"
+        )]
+        output_dir: PathBuf,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Recreate prompts from files written by `prompts export`
+    Import {
+        /// A prompt file (.txt with its .meta.json, or a chat .json) or a directory of them
+        file_or_dir: String,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Show what changed between two versions of a prompt
+    Diff {
+        /// Prompt name
+        name: String,
+
+        /// Version to compare from
+        from_version: i32,
+
+        /// Version to compare to
+        to_version: i32,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Create a text prompt
     CreateText {
         /// Prompt name
@@ -134,6 +403,15 @@ pub enum PromptsCommands {
         #[arg(long)]
         config: Option<String>,
 
+        /// Skip creation if content and config match the latest version
+        #[arg(long)]
+        if_changed: bool,
+
+        /// Idempotency key, so a repeated request doesn't create a second
+        /// version [default: a fresh key per run]
+        #[arg(long)]
+        idempotency_key: Option<String>,
+
         /// Output format
         #[arg(long, value_enum)]
         format: Option<OutputFormat>,
@@ -189,6 +467,20 @@ pub enum PromptsCommands {
         #[arg(long)]
         config: Option<String>,
 
+        /// Skip creation if content and config match the latest version
+        #[arg(long)]
+        if_changed: bool,
+
+        /// Idempotency key, so a repeated request doesn't create a second
+        /// version [default: a fresh key per run]
+        #[arg(long)]
+        idempotency_key: Option<String>,
+
+        /// Send the input JSON array as the prompt verbatim, for message shapes
+        /// the typed messages don't cover (tool messages, content arrays)
+        #[arg(long, conflicts_with = "if_changed")]
+        raw_body: bool,
+
         /// Output format
         #[arg(long, value_enum)]
         format: Option<OutputFormat>,
@@ -219,6 +511,7 @@ pub enum PromptsCommands {
     },
 
     /// Set labels on a prompt version
+    #[command(disable_version_flag = true)]
     Label {
         /// Prompt name
         name: String,
@@ -226,10 +519,18 @@ pub enum PromptsCommands {
         /// Version number
         version: i32,
 
-        /// Labels to set
+        /// Labels to set (replacing the version's labels unless --add or --remove is given)
         #[arg(short, long, required = true)]
         labels: Vec<String>,
 
+        /// Add the labels to the version's current labels
+        #[arg(long, conflicts_with = "remove")]
+        add: bool,
+
+        /// Remove the labels from the version, keeping its other labels
+        #[arg(long)]
+        remove: bool,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -260,6 +561,7 @@ pub enum PromptsCommands {
     },
 
     /// Delete a prompt
+    #[command(disable_version_flag = true)]
     Delete {
         /// Prompt name
         name: String,
@@ -272,6 +574,10 @@ pub enum PromptsCommands {
         #[arg(short, long)]
         label: Option<String>,
 
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
         /// Profile name
         #[arg(long)]
         profile: Option<String>,
@@ -294,6 +600,153 @@ pub enum PromptsCommands {
     },
 }
 
+/// A prompt version to fetch, either by label or by version number
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptSelector {
+    Label(String),
+    Version(i32),
+}
+
+impl FromStr for PromptSelector {
+    type Err = anyhow::Error;
+
+    /// Parse `version:N` as a version number, anything else as a label
+    fn from_str(s: &str) -> Result<Self> {
+        match s.strip_prefix("version:") {
+            Some(v) => {
+                Ok(PromptSelector::Version(v.parse().with_context(|| {
+                    format!("Invalid version number in '{s}'")
+                })?))
+            }
+            None => Ok(PromptSelector::Label(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for PromptSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptSelector::Label(l) => write!(f, "label '{l}'"),
+            PromptSelector::Version(v) => write!(f, "version {v}"),
+        }
+    }
+}
+
+/// Describe the primary version/label selection for messages
+fn describe_selection(version: Option<i32>, label: Option<&str>) -> String {
+    match (version, label) {
+        (Some(v), Some(l)) => format!("version {v} with label '{l}'"),
+        (Some(v), None) => format!("version {v}"),
+        (None, Some(l)) => format!("label '{l}'"),
+        (None, None) => "label 'production'".to_string(),
+    }
+}
+
+/// Fetch a prompt, trying each fallback in order when the requested
+/// version/label doesn't exist. Errors other than not-found are returned immediately.
+async fn get_prompt_with_fallback(
+    client: &LangfuseClient,
+    name: &str,
+    version: Option<i32>,
+    label: Option<&str>,
+    fallbacks: &[PromptSelector],
+    verbose: bool,
+) -> Result<Prompt> {
+    let mut tried = vec![describe_selection(version, label)];
+
+    match client.get_prompt(name, version, label).await {
+        Ok(prompt) => return Ok(prompt),
+        Err(e) if fallbacks.is_empty() || !is_not_found(&e) => return Err(e),
+        Err(_) => {}
+    }
+
+    for fallback in fallbacks {
+        let result = match fallback {
+            PromptSelector::Label(l) => client.get_prompt(name, None, Some(l)).await,
+            PromptSelector::Version(v) => client.get_prompt(name, Some(*v), None).await,
+        };
+
+        match result {
+            Ok(prompt) => {
+                if verbose {
+                    eprintln!("Using fallback {fallback} for prompt '{name}'");
+                }
+                return Ok(prompt);
+            }
+            Err(e) if !is_not_found(&e) => return Err(e),
+            Err(_) => tried.push(fallback.to_string()),
+        }
+    }
+
+    anyhow::bail!("Prompt '{name}' not found. Tried: {}", tried.join(", "))
+}
+
+/// Whether an error is the API's not-found response
+fn is_not_found(e: &anyhow::Error) -> bool {
+    matches!(e.downcast_ref(), Some(ApiError::NotFoundError(_)))
+}
+
+/// Parse a `--var key=value` argument
+fn parse_var(s: &str) -> Result<(String, String)> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => anyhow::bail!("Invalid variable '{s}'. Expected KEY=VALUE"),
+    }
+}
+
+/// Matches `{{name}}` placeholders, allowing whitespace inside the braces
+fn placeholder_regex() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_.-]+)\s*\}\}").expect("valid placeholder regex")
+}
+
+/// Substitute variables into a prompt's text or each chat message's content.
+/// Placeholders without a matching variable are left in place.
+fn render_prompt(content: &PromptContent, vars: &[(String, String)]) -> PromptContent {
+    let re = placeholder_regex();
+    let render = |text: &str| {
+        re.replace_all(text, |caps: &regex::Captures| {
+            vars.iter()
+                .rev()
+                .find(|(key, _)| key == &caps[1])
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+    };
+
+    match content {
+        PromptContent::Text(text) => PromptContent::Text(render(text)),
+        PromptContent::Chat(messages) => PromptContent::Chat(
+            messages
+                .iter()
+                .map(|m| ChatMessage {
+                    content: render(&m.content),
+                    ..m.clone()
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Placeholders still present in rendered content, in order of first appearance
+fn unresolved_placeholders(content: &PromptContent) -> Vec<String> {
+    let re = placeholder_regex();
+    let texts: Vec<&str> = match content {
+        PromptContent::Text(text) => vec![text],
+        PromptContent::Chat(messages) => messages.iter().map(|m| m.content.as_str()).collect(),
+    };
+
+    let mut found: Vec<String> = Vec::new();
+    for text in texts {
+        for m in re.find_iter(text) {
+            if !found.iter().any(|f| f == m.as_str()) {
+                found.push(m.as_str().to_string());
+            }
+        }
+    }
+    found
+}
+
 fn read_content(file: Option<&str>) -> Result<String> {
     match file {
         Some(path) => Ok(std::fs::read_to_string(path)?),
@@ -305,14 +758,285 @@ fn read_content(file: Option<&str>) -> Result<String> {
     }
 }
 
-impl PromptsCommands {
-    pub async fn execute(&self) -> Result<()> {
-        match self {
-            PromptsCommands::List {
-                name,
-                label,
+/// Chat messages for `--raw-body`: any JSON array, passed through untouched
+fn raw_chat_messages(content: &str) -> Result<serde_json::Value> {
+    let messages: serde_json::Value =
+        serde_json::from_str(content).context("--raw-body input is not valid JSON")?;
+    if !messages.is_array() {
+        anyhow::bail!("--raw-body input must be a JSON array of messages");
+    }
+    Ok(messages)
+}
+
+/// `prompts get --resolve`: which concrete version was fetched, e.g.
+/// `greeting: version 7 (labels: production, latest)`
+fn resolution_note(prompt: &Prompt) -> String {
+    let labels = if prompt.labels.is_empty() {
+        "no labels".to_string()
+    } else {
+        format!("labels: {}", prompt.labels.join(", "))
+    };
+    format!("{}: version {} ({labels})", prompt.name, prompt.version)
+}
+
+/// `prompts label --add/--remove`: the version's current labels with `labels`
+/// added, or taken away when `remove` is set. Langfuse manages the reserved
+/// `latest` label itself, so it is never sent back.
+fn merged_labels(current: &[String], labels: &[String], remove: bool) -> Vec<String> {
+    let mut merged: Vec<String> = current
+        .iter()
+        .filter(|l| l.as_str() != "latest" && !(remove && labels.contains(l)))
+        .cloned()
+        .collect();
+    if !remove {
+        for label in labels {
+            if !merged.contains(label) {
+                merged.push(label.clone());
+            }
+        }
+    }
+    merged
+}
+
+/// What `prompts delete` removes, in words for the confirmation prompt
+fn delete_target(name: &str, version: Option<i32>, label: Option<&str>) -> String {
+    match (version, label) {
+        (None, None) => format!("prompt '{name}' and all of its versions"),
+        (Some(v), None) => format!("version {v} of prompt '{name}'"),
+        (None, Some(l)) => format!("the versions of prompt '{name}' labelled '{l}'"),
+        (Some(v), Some(l)) => format!("version {v} of prompt '{name}' if it is labelled '{l}'"),
+    }
+}
+
+/// Ask before deleting. Deleting a whole prompt takes every version with it,
+/// so that needs the prompt name typed back; a version or label needs y/N.
+fn confirm_delete(name: &str, version: Option<i32>, label: Option<&str>) -> Result<bool> {
+    let target = delete_target(name, version, label);
+
+    if version.is_none() && label.is_none() {
+        let typed: String = Input::new()
+            .with_prompt(format!(
+                "This deletes {target} and cannot be undone. Type the prompt name to confirm"
+            ))
+            .allow_empty(true)
+            .interact_text()
+            .context("Could not prompt for confirmation; pass --yes to delete")?;
+        return Ok(typed.trim() == name);
+    }
+
+    confirm_destructive(&format!("Delete {target}? This cannot be undone"))
+}
+
+/// Check whether the latest version of a prompt already has this content and config.
+/// A prompt that doesn't exist yet never matches.
+async fn latest_matches(
+    client: &LangfuseClient,
+    name: &str,
+    content: &PromptContent,
+    config: Option<&serde_json::Value>,
+) -> Result<bool> {
+    match client.get_prompt(name, None, Some("latest")).await {
+        Ok(latest) => Ok(prompt_matches(&latest, content, config)),
+        Err(e) if is_not_found(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// A prompt's content as plain text: the text itself, or one `role: content`
+/// line per chat message (placeholders shown as `[placeholder: name]`)
+fn content_text(content: &PromptContent) -> String {
+    match content {
+        PromptContent::Text(text) => text.clone(),
+        PromptContent::Chat(messages) => messages
+            .iter()
+            .map(|m| {
+                if m.is_placeholder() {
+                    format!("[placeholder: {}]", m.name.as_deref().unwrap_or_default())
+                } else {
+                    format!("{}: {}", m.role, m.content)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// The start of some text on one line, whitespace collapsed, cut to
+/// `max_chars` characters with `...` when there is more
+fn preview(text: &str, max_chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= max_chars {
+        line
+    } else {
+        let cut: String = line.chars().take(max_chars).collect();
+        format!("{cut}...")
+    }
+}
+
+/// The version with `label` of each listed prompt, fetched `concurrency` at
+/// a time and returned in list order
+async fn fetch_labelled(
+    client: &LangfuseClient,
+    prompts: Vec<PromptMeta>,
+    label: &str,
+    concurrency: usize,
+) -> Result<Vec<Prompt>> {
+    stream::iter(prompts)
+        .map(|meta| async move { client.get_prompt(&meta.name, None, Some(label)).await })
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await
+}
+
+/// `prompts list --content`: each prompt's listing plus a `content` preview
+/// of its latest version
+async fn with_content_previews(
+    client: &LangfuseClient,
+    prompts: Vec<PromptMeta>,
+    concurrency: usize,
+) -> Result<Vec<serde_json::Value>> {
+    let mut rows = Vec::with_capacity(prompts.len());
+    for meta in &prompts {
+        rows.push(serde_json::to_value(meta)?);
+    }
+    let latest = fetch_labelled(client, prompts, "latest", concurrency).await?;
+
+    for (row, prompt) in rows.iter_mut().zip(&latest) {
+        row["content"] = preview(&content_text(&prompt.prompt), CONTENT_PREVIEW_CHARS).into();
+    }
+    Ok(rows)
+}
+
+/// One `prompts search` result row per line of the prompt's content that
+/// contains `query`, ignoring case
+fn search_matches(prompt: &Prompt, query: &str) -> Vec<serde_json::Value> {
+    let needle = query.to_lowercase();
+    content_text(&prompt.prompt)
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let at = find_ignore_case(line, &needle)?;
+            Some(serde_json::json!({
+                "name": prompt.name,
+                "version": prompt.version,
+                "line": i + 1,
+                "snippet": snippet(line, at, needle.chars().count()),
+            }))
+        })
+        .collect()
+}
+
+/// Character index of the first case-insensitive occurrence of `needle`
+/// (already lowercased) in `line`
+fn find_ignore_case(line: &str, needle: &str) -> Option<usize> {
+    line.char_indices()
+        .position(|(at, _)| line[at..].to_lowercase().starts_with(needle))
+}
+
+/// The matched line, trimmed to about `CONTENT_PREVIEW_CHARS` characters
+/// around a match of `len` characters starting at character `at`
+fn snippet(line: &str, at: usize, len: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let context = CONTENT_PREVIEW_CHARS.saturating_sub(len) / 2;
+    let start = at.saturating_sub(context);
+    let end = (at + len + context).min(chars.len());
+
+    let mut snippet = chars[start..end]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string();
+    if start > 0 {
+        snippet = format!("...{snippet}");
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// Compare a prompt version against new content and config.
+/// A missing config is treated the same as an empty one.
+fn prompt_matches(
+    existing: &Prompt,
+    content: &PromptContent,
+    config: Option<&serde_json::Value>,
+) -> bool {
+    let is_empty = |c: Option<&serde_json::Value>| match c {
+        None | Some(serde_json::Value::Null) => true,
+        Some(serde_json::Value::Object(obj)) => obj.is_empty(),
+        _ => false,
+    };
+
+    let config_matches = if is_empty(config) {
+        is_empty(existing.config.as_ref())
+    } else {
+        existing.config.as_ref() == config
+    };
+
+    existing.prompt == *content && config_matches
+}
+
+/// Recreate an exported prompt version with its labels, tags, config and
+/// commit message. `latest` is left out since the API assigns it itself.
+async fn import_prompt(client: &LangfuseClient, file: &PromptFile) -> Result<Prompt> {
+    let meta = file.meta();
+    let labels: Vec<String> = meta
+        .labels
+        .iter()
+        .filter(|l| *l != "latest")
+        .cloned()
+        .collect();
+
+    match file {
+        PromptFile::Text { text, .. } => {
+            client
+                .create_text_prompt(
+                    &meta.name,
+                    text,
+                    Some(&labels),
+                    Some(&meta.tags),
+                    meta.config.as_ref(),
+                    meta.commit_message.as_deref(),
+                    None,
+                )
+                .await
+        }
+        PromptFile::Chat(chat) => {
+            client
+                .create_chat_prompt(
+                    &meta.name,
+                    &chat.prompt,
+                    Some(&labels),
+                    Some(&meta.tags),
+                    meta.config.as_ref(),
+                    meta.commit_message.as_deref(),
+                    None,
+                )
+                .await
+        }
+    }
+}
+
+/// Whether the API refused to create a version because it already exists
+fn is_conflict(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<ApiError>(),
+        Some(ApiError::ApiError { status: 409, .. })
+    )
+}
+
+impl PromptsCommands {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
+        match self {
+            PromptsCommands::List {
+                name,
+                label,
                 tag,
+                content,
                 limit,
+                all,
+                fail_empty,
                 page,
                 format,
                 output,
@@ -328,17 +1052,15 @@ impl PromptsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
@@ -347,24 +1069,49 @@ impl PromptsCommands {
                         name.as_deref(),
                         label.as_deref(),
                         tag.as_deref(),
-                        *limit,
+                        resolve_limit(config.limit, *all),
                         *page,
                     )
                     .await?;
 
+                if *content {
+                    if config.verbose {
+                        eprintln!("Fetching content for {} prompts...", prompts.len());
+                    }
+                    let rows = keep_matching(
+                        with_content_previews(&client, prompts, config.concurrency).await?,
+                        &global.filters,
+                    )?;
+                    format_and_output(
+                        &rows,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    )?;
+                    return exit_if_empty(*fail_empty, rows.len());
+                }
+
+                let prompts = keep_matching(prompts, &global.filters)?;
+
                 format_and_output(
                     &prompts,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
-                )
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, prompts.len())
             }
 
             PromptsCommands::Get {
                 name,
                 version,
                 label,
+                fallback_label,
                 raw,
+                resolve,
                 format,
                 output,
                 profile,
@@ -384,33 +1131,406 @@ impl PromptsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
-                let prompt = client.get_prompt(name, *version, label.as_deref()).await?;
+                let prompt = get_prompt_with_fallback(
+                    &client,
+                    name,
+                    *version,
+                    label.as_deref(),
+                    fallback_label,
+                    config.verbose,
+                )
+                .await?;
+
+                if *resolve {
+                    eprintln!("{}", resolution_note(&prompt));
+                }
 
                 if *raw {
                     let content = match &prompt.prompt {
                         PromptContent::Text(s) => s.clone(),
-                        PromptContent::Chat(msgs) => serde_json::to_string_pretty(msgs)?,
+                        PromptContent::Chat(msgs) => JsonFormatter::format(msgs, global.compact)?,
                     };
-                    output_result(&content, output.as_deref(), *verbose)
+                    output_result(&content, config.output.as_deref(), *verbose, global)
                 } else {
                     format_and_output(
                         &prompt,
-                        format.unwrap_or(OutputFormat::Json),
-                        output.as_deref(),
+                        config.format_or(OutputFormat::Json),
+                        config.output.as_deref(),
                         *verbose,
+                        global,
                     )
                 }
             }
 
+            PromptsCommands::Render {
+                name,
+                version,
+                label,
+                var,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let prompt = client.get_prompt(name, *version, label.as_deref()).await?;
+                let rendered = render_prompt(&prompt.prompt, var);
+
+                let remaining = unresolved_placeholders(&rendered);
+                if !remaining.is_empty() {
+                    eprintln!(
+                        "Warning: unsubstituted placeholders: {}",
+                        remaining.join(", ")
+                    );
+                }
+
+                match rendered {
+                    PromptContent::Text(text) => {
+                        output_result(&text, config.output.as_deref(), *verbose, global)
+                    }
+                    PromptContent::Chat(messages) => format_and_output(
+                        &messages,
+                        config.format_or(OutputFormat::Json),
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    ),
+                }
+            }
+
+            PromptsCommands::Versions {
+                name,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let versions: Vec<PromptVersion> = client
+                    .list_prompt_versions(name)
+                    .await?
+                    .into_iter()
+                    .map(PromptVersion::from)
+                    .collect();
+
+                format_and_output(
+                    &versions,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            PromptsCommands::Search {
+                query,
+                label,
+                tag,
+                fail_empty,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let prompts = client
+                    .list_prompts(None, label.as_deref(), tag.as_deref(), UNLIMITED, 1)
+                    .await?;
+                if config.verbose {
+                    eprintln!("Searching {} prompts...", prompts.len());
+                }
+
+                let mut matches = Vec::new();
+                // Search the labelled version rather than latest when --label is given
+                let label = label.as_deref().unwrap_or("latest");
+                for prompt in fetch_labelled(&client, prompts, label, config.concurrency).await? {
+                    matches.extend(search_matches(&prompt, query));
+                }
+
+                format_and_output(
+                    &matches,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, matches.len())
+            }
+
+            PromptsCommands::Export {
+                name,
+                all,
+                output_dir,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let dir = output_dir.as_path();
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let names: Vec<String> = if *all {
+                    client
+                        .list_prompts(None, None, None, UNLIMITED, 1)
+                        .await?
+                        .into_iter()
+                        .map(|p| p.name)
+                        .collect()
+                } else {
+                    name.iter().cloned().collect()
+                };
+
+                let mut exported = 0;
+                for name in &names {
+                    for prompt in client.list_prompt_versions(name).await? {
+                        for path in prompt_files::write_prompt(dir, &prompt)? {
+                            if config.verbose {
+                                eprintln!("Wrote {}", path.display());
+                            }
+                        }
+                        exported += 1;
+                    }
+                }
+
+                if !config.quiet {
+                    eprintln!(
+                        "Exported {} versions of {} prompts to {}",
+                        exported,
+                        names.len(),
+                        dir.display()
+                    );
+                }
+
+                Ok(())
+            }
+
+            PromptsCommands::Import {
+                file_or_dir,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let path = Path::new(file_or_dir);
+                let files = if path.is_dir() {
+                    prompt_files::read_dir(path, |skipped, reason| {
+                        if !config.quiet {
+                            eprintln!("Skipped {}: {}", skipped.display(), reason);
+                        }
+                    })?
+                } else {
+                    match prompt_files::read_prompt(path)? {
+                        Some(file) => vec![(path.to_path_buf(), file)],
+                        None => anyhow::bail!(
+                            "'{}' is not a prompt file. Expected a .txt (with its .meta.json) or a chat .json",
+                            file_or_dir
+                        ),
+                    }
+                };
+
+                let client = LangfuseClient::new(&config)?;
+
+                // One at a time, so each prompt's versions are created in order
+                let (mut imported, mut skipped, mut failed) = (0, 0, 0);
+                for (path, file) in &files {
+                    match import_prompt(&client, file).await {
+                        Ok(prompt) => {
+                            imported += 1;
+                            if config.verbose {
+                                eprintln!(
+                                    "Imported {} as {} v{}",
+                                    path.display(),
+                                    prompt.name,
+                                    prompt.version
+                                );
+                            }
+                        }
+                        Err(e) if is_dry_run(&e) => imported += 1,
+                        Err(e) if is_conflict(&e) => {
+                            skipped += 1;
+                            if !config.quiet {
+                                eprintln!("Skipped {}: version already exists", path.display());
+                            }
+                        }
+                        Err(e) => {
+                            failed += 1;
+                            eprintln!("Failed {}: {}", path.display(), e);
+                        }
+                    }
+                }
+
+                if !config.quiet {
+                    eprintln!(
+                        "{} {} of {} prompt versions ({} skipped, {} failed)",
+                        if config.dry_run {
+                            "Would import"
+                        } else {
+                            "Imported"
+                        },
+                        imported,
+                        files.len(),
+                        skipped,
+                        failed
+                    );
+                }
+                if failed > 0 {
+                    anyhow::bail!(
+                        "{} of {} prompt versions failed to import",
+                        failed,
+                        files.len()
+                    );
+                }
+
+                Ok(())
+            }
+
+            PromptsCommands::Diff {
+                name,
+                from_version,
+                to_version,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let (from, to) = tokio::try_join!(
+                    client.get_prompt(name, Some(*from_version), None),
+                    client.get_prompt(name, Some(*to_version), None),
+                )?;
+
+                output_result(
+                    prompt_diff::diff_prompts(&from, &to).trim_end(),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
             PromptsCommands::CreateText {
                 name,
                 file,
@@ -418,6 +1538,8 @@ impl PromptsCommands {
                 labels,
                 tags,
                 config: cfg,
+                if_changed,
+                idempotency_key,
                 format,
                 output,
                 profile,
@@ -437,12 +1559,10 @@ impl PromptsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !app_config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&app_config)?;
 
                 let content = read_content(file.as_deref())?;
                 let parsed_config: Option<serde_json::Value> =
@@ -450,6 +1570,19 @@ impl PromptsCommands {
 
                 let client = LangfuseClient::new(&app_config)?;
 
+                if *if_changed
+                    && latest_matches(
+                        &client,
+                        name,
+                        &PromptContent::Text(content.clone()),
+                        parsed_config.as_ref(),
+                    )
+                    .await?
+                {
+                    println!("no change");
+                    return Ok(());
+                }
+
                 let prompt = client
                     .create_text_prompt(
                         name,
@@ -458,14 +1591,16 @@ impl PromptsCommands {
                         tags.as_deref(),
                         parsed_config.as_ref(),
                         message.as_deref(),
+                        idempotency_key.as_deref(),
                     )
                     .await?;
 
                 format_and_output(
                     &prompt,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    app_config.format,
+                    app_config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
@@ -476,6 +1611,9 @@ impl PromptsCommands {
                 labels,
                 tags,
                 config: cfg,
+                if_changed,
+                idempotency_key,
+                raw_body,
                 format,
                 output,
                 profile,
@@ -495,21 +1633,55 @@ impl PromptsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !app_config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&app_config)?;
 
                 let content = read_content(file.as_deref())?;
-                let messages: Vec<ChatMessage> = serde_json::from_str(&content)?;
                 let parsed_config: Option<serde_json::Value> =
                     cfg.as_ref().map(|c| serde_json::from_str(c)).transpose()?;
 
                 let client = LangfuseClient::new(&app_config)?;
 
-                let prompt = client
+                if *raw_body {
+                    let messages = raw_chat_messages(&content)?;
+                    let prompt: serde_json::Value = client
+                        .create_chat_prompt(
+                            name,
+                            &messages,
+                            labels.as_deref(),
+                            tags.as_deref(),
+                            parsed_config.as_ref(),
+                            message.as_deref(),
+                            idempotency_key.as_deref(),
+                        )
+                        .await?;
+                    return format_and_output(
+                        &prompt,
+                        app_config.format,
+                        app_config.output.as_deref(),
+                        *verbose,
+                        global,
+                    );
+                }
+
+                let messages: Vec<ChatMessage> = serde_json::from_str(&content)?;
+
+                if *if_changed
+                    && latest_matches(
+                        &client,
+                        name,
+                        &PromptContent::Chat(messages.clone()),
+                        parsed_config.as_ref(),
+                    )
+                    .await?
+                {
+                    println!("no change");
+                    return Ok(());
+                }
+
+                let prompt: Prompt = client
                     .create_chat_prompt(
                         name,
                         &messages,
@@ -517,14 +1689,16 @@ impl PromptsCommands {
                         tags.as_deref(),
                         parsed_config.as_ref(),
                         message.as_deref(),
+                        idempotency_key.as_deref(),
                     )
                     .await?;
 
                 format_and_output(
                     &prompt,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    app_config.format,
+                    app_config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
@@ -532,6 +1706,8 @@ impl PromptsCommands {
                 name,
                 version,
                 labels,
+                add,
+                remove,
                 format,
                 output,
                 profile,
@@ -551,22 +1727,28 @@ impl PromptsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
-                let prompt = client.update_prompt_labels(name, *version, labels).await?;
+                let labels = if *add || *remove {
+                    let current = client.get_prompt(name, Some(*version), None).await?;
+                    merged_labels(&current.labels, labels, *remove)
+                } else {
+                    labels.clone()
+                };
+
+                let prompt = client.update_prompt_labels(name, *version, &labels).await?;
 
                 format_and_output(
                     &prompt,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
@@ -574,6 +1756,7 @@ impl PromptsCommands {
                 name,
                 version,
                 label,
+                yes,
                 profile,
                 public_key,
                 secret_key,
@@ -591,11 +1774,23 @@ impl PromptsCommands {
                     None,
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
+                require_credentials(&config)?;
+
+                if config.dry_run {
+                    if !config.quiet {
+                        eprintln!(
+                            "Dry run: would delete {}",
+                            delete_target(name, *version, label.as_deref())
+                        );
+                    }
+                } else if !*yes && !confirm_delete(name, *version, label.as_deref())? {
+                    if !config.quiet {
+                        eprintln!("Aborted");
+                    }
+                    return Ok(());
                 }
 
                 let client = LangfuseClient::new(&config)?;
@@ -604,7 +1799,7 @@ impl PromptsCommands {
                     .delete_prompt(name, *version, label.as_deref())
                     .await?;
 
-                if *verbose {
+                if config.verbose {
                     eprintln!("Prompt '{}' deleted successfully", name);
                 }
 
@@ -613,3 +1808,773 @@ impl PromptsCommands {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_client(host: &str) -> LangfuseClient {
+        let config = Config {
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: host.to_string(),
+            ..Default::default()
+        };
+        LangfuseClient::new(&config).unwrap()
+    }
+
+    #[test]
+    fn test_parse_prompt_selector() {
+        assert_eq!(
+            "latest".parse::<PromptSelector>().unwrap(),
+            PromptSelector::Label("latest".to_string())
+        );
+        assert_eq!(
+            "version:1".parse::<PromptSelector>().unwrap(),
+            PromptSelector::Version(1)
+        );
+        assert!("version:abc".parse::<PromptSelector>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_falls_back_in_order() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("label", "production"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("label", "latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting",
+                "version": 4,
+                "type": "text",
+                "prompt": "Hello",
+                "labels": ["latest"]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let fallbacks = vec![
+            PromptSelector::Label("latest".to_string()),
+            PromptSelector::Version(1),
+        ];
+
+        let prompt = get_prompt_with_fallback(
+            &client,
+            "greeting",
+            None,
+            Some("production"),
+            &fallbacks,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(prompt.version, 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_prompt_fallback_exhausted_lists_attempts() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(&mock_server.uri());
+        let fallbacks = vec![
+            PromptSelector::Label("latest".to_string()),
+            PromptSelector::Version(1),
+        ];
+
+        let err = get_prompt_with_fallback(&client, "greeting", None, None, &fallbacks, false)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("Prompt 'greeting' not found"));
+        assert!(err.contains("label 'production', label 'latest', version 1"));
+    }
+
+    fn text_prompt(content: &str, config: Option<serde_json::Value>) -> Prompt {
+        Prompt {
+            name: "greeting".to_string(),
+            version: 3,
+            prompt_type: "text".to_string(),
+            prompt: PromptContent::Text(content.to_string()),
+            labels: vec!["latest".to_string()],
+            tags: vec![],
+            config,
+            commit_message: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_var() {
+        assert_eq!(
+            parse_var("name=Ada").unwrap(),
+            ("name".to_string(), "Ada".to_string())
+        );
+        assert_eq!(
+            parse_var("q=a=b").unwrap(),
+            ("q".to_string(), "a=b".to_string())
+        );
+        assert!(parse_var("name").is_err());
+        assert!(parse_var("=value").is_err());
+    }
+
+    #[test]
+    fn test_render_text_prompt() {
+        let content = PromptContent::Text("Hello {{name}}, welcome to {{ place }}!".to_string());
+
+        let rendered = render_prompt(&content, &vars(&[("name", "Ada"), ("place", "London")]));
+
+        assert_eq!(
+            rendered,
+            PromptContent::Text("Hello Ada, welcome to London!".to_string())
+        );
+        assert!(unresolved_placeholders(&rendered).is_empty());
+    }
+
+    #[test]
+    fn test_render_chat_prompt() {
+        let content = PromptContent::Chat(vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: "You are a {{persona}}.".to_string(),
+                ..Default::default()
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: "Summarise {{document}}".to_string(),
+                ..Default::default()
+            },
+        ]);
+
+        let rendered = render_prompt(&content, &vars(&[("persona", "pirate")]));
+
+        assert_eq!(
+            rendered,
+            PromptContent::Chat(vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: "You are a pirate.".to_string(),
+                    ..Default::default()
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: "Summarise {{document}}".to_string(),
+                    ..Default::default()
+                },
+            ])
+        );
+        assert_eq!(unresolved_placeholders(&rendered), ["{{document}}"]);
+    }
+
+    #[test]
+    fn test_prompt_matches_identical_text() {
+        let existing = text_prompt("Hello {{name}}", Some(json!({"temperature": 0.7})));
+        let content = PromptContent::Text("Hello {{name}}".to_string());
+
+        assert!(prompt_matches(
+            &existing,
+            &content,
+            Some(&json!({"temperature": 0.7}))
+        ));
+    }
+
+    #[test]
+    fn test_prompt_matches_detects_content_change() {
+        let existing = text_prompt("Hello {{name}}", None);
+        let content = PromptContent::Text("Hi {{name}}".to_string());
+
+        assert!(!prompt_matches(&existing, &content, None));
+    }
+
+    #[test]
+    fn test_prompt_matches_detects_config_change() {
+        let existing = text_prompt("Hello", Some(json!({"temperature": 0.7})));
+        let content = PromptContent::Text("Hello".to_string());
+
+        assert!(!prompt_matches(
+            &existing,
+            &content,
+            Some(&json!({"temperature": 0.2}))
+        ));
+        assert!(!prompt_matches(&existing, &content, None));
+    }
+
+    #[test]
+    fn test_prompt_matches_treats_empty_config_as_missing() {
+        let existing = text_prompt("Hello", Some(json!({})));
+        let content = PromptContent::Text("Hello".to_string());
+
+        assert!(prompt_matches(&existing, &content, None));
+    }
+
+    #[test]
+    fn test_prompt_matches_chat_messages() {
+        let messages = vec![ChatMessage {
+            role: "system".to_string(),
+            content: "You are helpful".to_string(),
+            ..Default::default()
+        }];
+        let existing = Prompt {
+            prompt_type: "chat".to_string(),
+            prompt: PromptContent::Chat(messages.clone()),
+            ..text_prompt("", None)
+        };
+
+        assert!(prompt_matches(
+            &existing,
+            &PromptContent::Chat(messages),
+            None
+        ));
+        assert!(!prompt_matches(
+            &existing,
+            &PromptContent::Text("You are helpful".to_string()),
+            None
+        ));
+    }
+
+    fn created_prompt(body: serde_json::Value) -> ResponseTemplate {
+        ResponseTemplate::new(201).set_body_json(json!({
+            "name": body["name"],
+            "version": 1,
+            "type": body["type"],
+            "prompt": body["prompt"],
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_import_text_prompt_applies_metadata() {
+        let mock_server = MockServer::start().await;
+
+        let expected = json!({
+            "name": "support/greeting",
+            "type": "text",
+            "prompt": "Hello {{name}}",
+            "labels": ["production"],
+            "tags": ["support"],
+            "config": {"temperature": 0.2},
+            "commitMessage": "Be friendlier"
+        });
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .and(body_partial_json(&expected))
+            .respond_with(created_prompt(expected.clone()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let file = PromptFile::Text {
+            meta: prompt_files::PromptFileMeta {
+                name: "support/greeting".to_string(),
+                version: 3,
+                labels: vec!["production".to_string(), "latest".to_string()],
+                tags: vec!["support".to_string()],
+                config: Some(json!({"temperature": 0.2})),
+                commit_message: Some("Be friendlier".to_string()),
+            },
+            text: "Hello {{name}}".to_string(),
+        };
+
+        let prompt = import_prompt(&test_client(&mock_server.uri()), &file)
+            .await
+            .unwrap();
+
+        assert_eq!(prompt.name, "support/greeting");
+    }
+
+    #[tokio::test]
+    async fn test_import_chat_prompt_applies_metadata() {
+        let mock_server = MockServer::start().await;
+
+        let expected = json!({
+            "name": "assistant",
+            "type": "chat",
+            "prompt": [{"role": "system", "content": "You are helpful."}],
+            "labels": ["staging"],
+            "tags": []
+        });
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .and(body_partial_json(&expected))
+            .respond_with(created_prompt(expected.clone()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let file = PromptFile::Chat(prompt_files::ChatPromptFile {
+            meta: prompt_files::PromptFileMeta {
+                name: "assistant".to_string(),
+                version: 1,
+                labels: vec!["staging".to_string()],
+                tags: vec![],
+                config: None,
+                commit_message: None,
+            },
+            prompt: vec![ChatMessage {
+                role: "system".to_string(),
+                content: "You are helpful.".to_string(),
+                ..Default::default()
+            }],
+        });
+
+        let prompt = import_prompt(&test_client(&mock_server.uri()), &file)
+            .await
+            .unwrap();
+
+        assert!(matches!(prompt.prompt, PromptContent::Chat(_)));
+    }
+
+    #[tokio::test]
+    async fn test_create_chat_raw_body_forwards_messages_unchanged() {
+        let mock_server = MockServer::start().await;
+
+        let messages = json!([
+            {"role": "user", "content": "What's the weather in Paris?"},
+            {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"id": "call_1", "type": "function",
+                    "function": {"name": "weather", "arguments": "{\"city\":\"Paris\"}"}}]
+            },
+            {"role": "tool", "tool_call_id": "call_1", "content": "18C, sunny"}
+        ]);
+        let expected = json!({"name": "weather-agent", "type": "chat", "prompt": messages});
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .and(body_json(&expected))
+            .and(header("Idempotency-Key", "deploy-42"))
+            .respond_with(created_prompt(expected.clone()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("messages.json");
+        std::fs::write(&file, messages.to_string()).unwrap();
+        let output = temp_dir.path().join("prompt.json");
+
+        let command = PromptsCommands::CreateChat {
+            name: "weather-agent".to_string(),
+            file: Some(file.to_string_lossy().to_string()),
+            message: None,
+            labels: None,
+            tags: None,
+            config: None,
+            if_changed: false,
+            idempotency_key: Some("deploy-42".to_string()),
+            raw_body: true,
+            format: Some(OutputFormat::Json),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let created: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(created["prompt"], messages);
+    }
+
+    #[test]
+    fn test_raw_chat_messages_requires_array() {
+        assert!(raw_chat_messages(r#"[{"role": "tool", "content": "ok"}]"#).is_ok());
+        let err = raw_chat_messages(r#"{"role": "user"}"#).unwrap_err();
+        assert!(err.to_string().contains("JSON array"));
+        assert!(raw_chat_messages("not json").is_err());
+    }
+
+    #[test]
+    fn test_resolution_note_reports_version_and_labels() {
+        let prompt: Prompt = serde_json::from_value(json!({
+            "name": "greeting",
+            "version": 7,
+            "type": "text",
+            "prompt": "Hello {{name}}",
+            "labels": ["production", "latest"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            resolution_note(&prompt),
+            "greeting: version 7 (labels: production, latest)"
+        );
+    }
+
+    #[test]
+    fn test_delete_target_names_what_is_deleted() {
+        assert_eq!(
+            delete_target("greeting", None, None),
+            "prompt 'greeting' and all of its versions"
+        );
+        assert_eq!(
+            delete_target("greeting", Some(3), None),
+            "version 3 of prompt 'greeting'"
+        );
+        assert_eq!(
+            delete_target("greeting", None, Some("staging")),
+            "the versions of prompt 'greeting' labelled 'staging'"
+        );
+    }
+
+    /// A `prompts label` command for version 2 of "greeting"
+    fn label_command(host: String, labels: &[&str], add: bool, remove: bool) -> PromptsCommands {
+        PromptsCommands::Label {
+            name: "greeting".to_string(),
+            version: 2,
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            add,
+            remove,
+            format: Some(OutputFormat::Json),
+            output: None,
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(host),
+            verbose: false,
+        }
+    }
+
+    /// Mock the current version 2 (labelled production, staging and latest),
+    /// expecting it to be fetched `fetches` times
+    async fn mount_label_version(mock_server: &MockServer, fetches: u64) {
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("version", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting", "version": 2, "type": "text", "prompt": "Hi",
+                "labels": ["production", "staging", "latest"]
+            })))
+            .expect(fetches)
+            .mount(mock_server)
+            .await;
+    }
+
+    /// Mock the label update, expecting exactly `new_labels` to be sent
+    async fn mount_label_patch(mock_server: &MockServer, new_labels: serde_json::Value) {
+        Mock::given(method("PATCH"))
+            .and(path("/api/public/v2/prompts/greeting/versions/2"))
+            .and(body_json(json!({"newLabels": new_labels})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting", "version": 2, "type": "text", "prompt": "Hi",
+                "labels": new_labels
+            })))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_label_add_unions_with_current_labels() {
+        let mock_server = MockServer::start().await;
+        mount_label_version(&mock_server, 1).await;
+        mount_label_patch(&mock_server, json!(["production", "staging", "canary"])).await;
+
+        label_command(mock_server.uri(), &["canary", "staging"], true, false)
+            .execute(&GlobalOptions::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_label_remove_drops_only_that_label() {
+        let mock_server = MockServer::start().await;
+        mount_label_version(&mock_server, 1).await;
+        mount_label_patch(&mock_server, json!(["production"])).await;
+
+        label_command(mock_server.uri(), &["staging"], false, true)
+            .execute(&GlobalOptions::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_label_without_flags_replaces() {
+        let mock_server = MockServer::start().await;
+        mount_label_version(&mock_server, 0).await;
+        mount_label_patch(&mock_server, json!(["canary"])).await;
+
+        label_command(mock_server.uri(), &["canary"], false, false)
+            .execute(&GlobalOptions::default())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delete_with_yes_skips_confirmation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let command = PromptsCommands::Delete {
+            name: "greeting".to_string(),
+            version: None,
+            label: None,
+            yes: true,
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_conflict_is_detected() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(409).set_body_string("Prompt version exists"))
+            .mount(&mock_server)
+            .await;
+
+        let file = PromptFile::Text {
+            meta: prompt_files::PromptFileMeta {
+                name: "greeting".to_string(),
+                version: 1,
+                labels: vec![],
+                tags: vec![],
+                config: None,
+                commit_message: None,
+            },
+            text: "Hello".to_string(),
+        };
+
+        let err = import_prompt(&test_client(&mock_server.uri()), &file)
+            .await
+            .unwrap_err();
+
+        assert!(is_conflict(&err));
+    }
+
+    #[tokio::test]
+    async fn test_list_content_previews_each_prompt() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "greeting", "versions": [1, 2], "labels": ["latest"], "tags": []},
+                    {"name": "assistant", "versions": [1], "labels": ["latest"], "tags": []}
+                ],
+                "meta": {"page": 1, "limit": 50, "totalItems": 2, "totalPages": 1}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("label", "latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting", "version": 2, "type": "text",
+                "prompt": format!("Hello {{{{name}}}},\n  welcome! {}", "x".repeat(100))
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/assistant"))
+            .and(query_param("label", "latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "assistant", "version": 1, "type": "chat",
+                "prompt": [{"role": "system", "content": "You are helpful."}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("prompts.json");
+        let command = PromptsCommands::List {
+            name: None,
+            label: None,
+            tag: None,
+            content: true,
+            limit: None,
+            all: false,
+            fail_empty: false,
+            page: 1,
+            format: Some(OutputFormat::Json),
+            output: Some(out.to_str().unwrap().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let rows: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        let greeting = rows[0]["content"].as_str().unwrap();
+        assert!(greeting.starts_with("Hello {{name}}, welcome! xxx"));
+        assert!(greeting.ends_with("..."));
+        assert_eq!(greeting.chars().count(), CONTENT_PREVIEW_CHARS + 3);
+        assert_eq!(rows[1]["name"], "assistant");
+        assert_eq!(rows[1]["content"], "system: You are helpful.");
+    }
+
+    #[tokio::test]
+    async fn test_search_reports_only_matching_prompt() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "greeting", "versions": [1], "labels": ["latest"], "tags": []},
+                    {"name": "refunds", "versions": [3], "labels": ["latest"], "tags": []}
+                ],
+                "meta": {"page": 1, "limit": 50, "totalItems": 2, "totalPages": 1}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/greeting"))
+            .and(query_param("label", "latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "greeting", "version": 1, "type": "text",
+                "prompt": "Say hello to {{name}}."
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/refunds"))
+            .and(query_param("label", "latest"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "refunds", "version": 3, "type": "chat",
+                "prompt": [
+                    {"role": "system", "content": "You handle billing questions."},
+                    {"role": "user", "content": "Explain our Refund Policy in one paragraph."}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("matches.json");
+        let command = PromptsCommands::Search {
+            query: "refund policy".to_string(),
+            label: None,
+            tag: None,
+            fail_empty: false,
+            format: Some(OutputFormat::Json),
+            output: Some(out.to_str().unwrap().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let rows: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(
+            rows,
+            json!([{
+                "name": "refunds",
+                "version": 3,
+                "line": 2,
+                "snippet": "user: Explain our Refund Policy in one paragraph."
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_with_label_searches_that_version() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts"))
+            .and(query_param("label", "production"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"name": "refunds", "versions": [2, 3], "labels": ["production", "latest"], "tags": []}
+                ],
+                "meta": {"page": 1, "limit": 50, "totalItems": 1, "totalPages": 1}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/v2/prompts/refunds"))
+            .and(query_param("label", "production"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "refunds", "version": 2, "type": "text",
+                "prompt": "Quote the refund policy."
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("matches.json");
+        let command = PromptsCommands::Search {
+            query: "refund policy".to_string(),
+            label: Some("production".to_string()),
+            tag: None,
+            fail_empty: false,
+            format: Some(OutputFormat::Json),
+            output: Some(out.to_str().unwrap().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let rows: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(rows[0]["version"], 2);
+    }
+
+    #[test]
+    fn test_search_snippet_trims_long_lines_around_match() {
+        let line = format!("{} needle {}", "a".repeat(100), "b".repeat(100));
+        let at = find_ignore_case(&line, "needle").unwrap();
+
+        let snippet = snippet(&line, at, 6);
+
+        assert!(snippet.starts_with("...aaa"));
+        assert!(snippet.ends_with("bbb..."));
+        assert!(snippet.contains(" needle "));
+        assert_eq!(snippet.chars().count(), CONTENT_PREVIEW_CHARS + 6);
+    }
+}
@@ -1,9 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::builder::NonEmptyStringValueParser;
 use clap::Subcommand;
-
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
-use crate::types::OutputFormat;
+use futures::stream::{self, StreamExt};
+
+use crate::client::{is_dry_run, LangfuseClient};
+use crate::commands::{
+    build_config, exit_if_empty, format_and_output, output_result, require_credentials,
+    resolve_limit, GlobalOptions,
+};
+use crate::histogram;
+use crate::record_filter::keep_matching;
+use crate::score_import::parse_scores;
+use crate::time_arg::parse_time_arg;
+use crate::types::{OutputFormat, ScoreDataType, ScoreValue};
 
 #[derive(Debug, Subcommand)]
 pub enum ScoresCommands {
@@ -13,9 +22,9 @@ pub enum ScoresCommands {
         #[arg(short, long)]
         name: String,
 
-        /// Score value (numeric)
+        /// Score value (a number, a category for CATEGORICAL, or true/false for BOOLEAN)
         #[arg(short, long)]
-        value: f64,
+        value: String,
 
         /// Trace ID to attach the score to
         #[arg(short, long)]
@@ -30,13 +39,18 @@ pub enum ScoresCommands {
         session_id: Option<String>,
 
         /// Data type (NUMERIC, CATEGORICAL, BOOLEAN)
-        #[arg(short, long)]
-        data_type: Option<String>,
+        #[arg(short, long, value_enum, ignore_case = true)]
+        data_type: Option<ScoreDataType>,
 
         /// Comment or annotation
         #[arg(short, long)]
         comment: Option<String>,
 
+        /// Score ID. Also used as the idempotency key, so re-running with the
+        /// same ID updates that score instead of adding a duplicate
+        #[arg(long)]
+        id: Option<String>,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -66,23 +80,74 @@ pub enum ScoresCommands {
         verbose: bool,
     },
 
+    /// Import scores from a CSV file
+    Import {
+        /// CSV file with traceId (or observationId/sessionId), name, value
+        /// and optional comment columns
+        file: String,
+
+        /// Data type of every value in the file (NUMERIC, CATEGORICAL, BOOLEAN)
+        #[arg(short, long, value_enum, ignore_case = true)]
+        data_type: Option<ScoreDataType>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(long)]
+        verbose: bool,
+    },
+
     /// List scores with optional filters
     List {
         /// Filter by score name
         #[arg(short, long)]
         name: Option<String>,
 
-        /// Filter from timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter by trace ID
+        #[arg(short, long, value_parser = NonEmptyStringValueParser::new())]
+        trace_id: Option<String>,
+
+        /// Filter by observation ID (combines with --trace-id)
+        #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+        observation_id: Option<String>,
+
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         from: Option<String>,
 
-        /// Filter to timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         to: Option<String>,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Output a histogram of value counts for this field instead of the records
+        #[arg(long, value_name = "FIELD")]
+        count_by: Option<String>,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -117,6 +182,53 @@ pub enum ScoresCommands {
         verbose: bool,
     },
 
+    /// Count scores matching the filters without fetching them
+    Count {
+        /// Filter by score name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Filter by trace ID
+        #[arg(short, long, value_parser = NonEmptyStringValueParser::new())]
+        trace_id: Option<String>,
+
+        /// Filter by observation ID (combines with --trace-id)
+        #[arg(long, value_parser = NonEmptyStringValueParser::new())]
+        observation_id: Option<String>,
+
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        from: Option<String>,
+
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        to: Option<String>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Get a specific score by ID
     Get {
         /// Score ID
@@ -150,10 +262,82 @@ pub enum ScoresCommands {
         #[arg(short, long)]
         verbose: bool,
     },
+
+    /// Correct a score's value or add a comment
+    Update {
+        /// Score ID
+        id: String,
+
+        /// New value (a number, a category for CATEGORICAL, or true/false for BOOLEAN)
+        #[arg(short, long, required_unless_present = "comment")]
+        value: Option<String>,
+
+        /// New comment
+        #[arg(short, long)]
+        comment: Option<String>,
+
+        /// Data type of the new value (NUMERIC, CATEGORICAL, BOOLEAN)
+        #[arg(short, long, value_enum, ignore_case = true, requires = "value")]
+        data_type: Option<ScoreDataType>,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(long)]
+        verbose: bool,
+    },
+
+    /// Delete a score by ID
+    Delete {
+        /// Score ID
+        id: String,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
 }
 
 impl ScoresCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             ScoresCommands::Create {
                 name,
@@ -163,6 +347,7 @@ impl ScoresCommands {
                 session_id,
                 data_type,
                 comment,
+                id,
                 format,
                 output,
                 profile,
@@ -182,40 +367,148 @@ impl ScoresCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
+
+                let value = ScoreValue::parse(value, *data_type)?;
 
                 let client = LangfuseClient::new(&config)?;
 
                 let result = client
                     .create_score(
                         name,
-                        *value,
+                        &value,
                         trace_id.as_deref(),
                         observation_id.as_deref(),
                         session_id.as_deref(),
-                        data_type.as_deref(),
+                        data_type.map(ScoreDataType::to_api_string),
                         comment.as_deref(),
+                        id.as_deref(),
                     )
                     .await?;
 
                 format_and_output(
                     &result,
-                    format.unwrap_or(OutputFormat::Json),
-                    output.as_deref(),
+                    config.format_or(OutputFormat::Json),
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
 
+            ScoresCommands::Import {
+                file,
+                data_type,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let content = std::fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file))?;
+                let rows = parse_scores(&content, *data_type)?;
+                let total = rows.len();
+
+                let client = LangfuseClient::new(&config)?;
+
+                // Create scores with bounded concurrency, carrying on past rows that fail
+                let mut results = stream::iter(rows)
+                    .map(|row| {
+                        let client = &client;
+                        async move {
+                            let score = row.score.map_err(|e| (row.line, e))?;
+                            client
+                                .create_score(
+                                    &score.name,
+                                    &score.value,
+                                    score.trace_id.as_deref(),
+                                    score.observation_id.as_deref(),
+                                    score.session_id.as_deref(),
+                                    data_type.map(ScoreDataType::to_api_string),
+                                    score.comment.as_deref(),
+                                    None,
+                                )
+                                .await
+                                .map(|_| ())
+                                .or_else(|e| {
+                                    // A dry run prints each request; that row is done
+                                    if is_dry_run(&e) {
+                                        Ok(())
+                                    } else {
+                                        Err((row.line, e.to_string()))
+                                    }
+                                })
+                        }
+                    })
+                    .buffered(config.concurrency.max(1));
+
+                let mut imported = 0;
+                let mut failures: Vec<(usize, String)> = Vec::new();
+                while let Some(result) = results.next().await {
+                    match result {
+                        Ok(_) => imported += 1,
+                        Err(failure) => failures.push(failure),
+                    }
+                    if !config.quiet && !config.dry_run {
+                        eprint!(
+                            "\rImporting scores: {}/{}",
+                            imported + failures.len(),
+                            total
+                        );
+                    }
+                }
+
+                if !config.quiet {
+                    if total > 0 && !config.dry_run {
+                        eprintln!();
+                    }
+                    let verb = if config.dry_run {
+                        "Would import"
+                    } else {
+                        "Imported"
+                    };
+                    eprintln!("{} {} of {} scores", verb, imported, total);
+                }
+                if failures.is_empty() {
+                    return Ok(());
+                }
+
+                eprintln!("Failed lines:");
+                for (line, error) in &failures {
+                    eprintln!("  line {}: {}", line, error);
+                }
+                anyhow::bail!("{} of {} scores failed to import", failures.len(), total)
+            }
+
             ScoresCommands::List {
                 name,
+                trace_id,
+                observation_id,
                 from,
                 to,
                 limit,
+                all,
+                fail_empty,
+                count_by,
                 page,
                 format,
                 output,
@@ -231,35 +524,100 @@ impl ScoresCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
                 let scores = client
                     .list_scores(
                         name.as_deref(),
+                        trace_id.as_deref(),
+                        observation_id.as_deref(),
                         from.as_deref(),
                         to.as_deref(),
-                        *limit,
+                        resolve_limit(config.limit, *all),
                         *page,
                     )
                     .await?;
+                let scores = keep_matching(scores, &global.filters)?;
+
+                if let Some(field) = count_by {
+                    format_and_output(
+                        &histogram::count_by(&scores, field)?,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    )?;
+                    return exit_if_empty(*fail_empty, scores.len());
+                }
 
                 format_and_output(
                     &scores,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, scores.len())
+            }
+
+            ScoresCommands::Count {
+                name,
+                trace_id,
+                observation_id,
+                from,
+                to,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let total = client
+                    .count_scores(
+                        name.as_deref(),
+                        trace_id.as_deref(),
+                        observation_id.as_deref(),
+                        from.as_deref(),
+                        to.as_deref(),
+                    )
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("The API did not report a total score count"))?;
+
+                output_result(
+                    &total.to_string(),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
 
@@ -284,12 +642,10 @@ impl ScoresCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
@@ -297,11 +653,101 @@ impl ScoresCommands {
 
                 format_and_output(
                     &score,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            ScoresCommands::Update {
+                id,
+                value,
+                comment,
+                data_type,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let value = value
+                    .as_deref()
+                    .map(|v| ScoreValue::parse(v, *data_type))
+                    .transpose()?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let score = client
+                    .update_score(
+                        id,
+                        value.as_ref(),
+                        comment.as_deref(),
+                        data_type.map(ScoreDataType::to_api_string),
+                    )
+                    .await?;
+
+                format_and_output(
+                    &score,
+                    config.format_or(OutputFormat::Json),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
+
+            ScoresCommands::Delete {
+                id,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                client.delete_score(id).await?;
+
+                if config.verbose {
+                    eprintln!("Score '{}' deleted successfully", id);
+                }
+
+                Ok(())
+            }
         }
     }
 }
@@ -1,30 +1,131 @@
 use anyhow::Result;
 use clap::Subcommand;
+use futures::stream::{self, StreamExt, TryStreamExt};
 
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
-use crate::types::OutputFormat;
+use crate::client::{LangfuseClient, UNLIMITED};
+use crate::commands::{
+    build_config, exit_if_empty, format_and_output, require_credentials, resolve_limit,
+    GlobalOptions,
+};
+use crate::record_filter::keep_matching;
+use crate::time_arg::parse_time_arg;
+use crate::types::{Observation, OutputFormat, SessionExport, SessionMetrics, Trace};
 
 #[derive(Debug, Subcommand)]
 pub enum SessionsCommands {
     /// List sessions with optional filters
     List {
-        /// Filter from timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         from: Option<String>,
 
-        /// Filter to timestamp (ISO 8601 format)
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        to: Option<String>,
+
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Page number
+        #[arg(short, long, default_value = "1")]
+        page: u32,
+
+        /// Output format
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
         #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Export sessions with the number of traces in each.
+    ///
+    /// Makes one extra API request per session to look up its trace count.
+    Export {
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        from: Option<String>,
+
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         to: Option<String>,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Maximum number of sessions [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
         page: u32,
 
+        /// Output format (default: csv)
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Summarise a session's traces, observations, tokens and cost
+    Metrics {
+        /// Session ID
+        id: String,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -94,12 +195,14 @@ pub enum SessionsCommands {
 }
 
 impl SessionsCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             SessionsCommands::List {
                 from,
                 to,
                 limit,
+                all,
+                fail_empty,
                 page,
                 format,
                 output,
@@ -115,29 +218,186 @@ impl SessionsCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
                 let sessions = client
-                    .list_sessions(from.as_deref(), to.as_deref(), *limit, *page)
+                    .list_sessions(
+                        from.as_deref(),
+                        to.as_deref(),
+                        resolve_limit(config.limit, *all),
+                        *page,
+                    )
                     .await?;
+                let sessions = keep_matching(sessions, &global.filters)?;
 
                 format_and_output(
                     &sessions,
-                    format.unwrap_or(OutputFormat::Table),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, sessions.len())
+            }
+
+            SessionsCommands::Export {
+                from,
+                to,
+                limit,
+                all,
+                page,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    *limit,
+                    Some(*page),
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let sessions = client
+                    .list_sessions(
+                        from.as_deref(),
+                        to.as_deref(),
+                        resolve_limit(config.limit, *all),
+                        *page,
+                    )
+                    .await?;
+
+                if config.verbose {
+                    eprintln!("Counting traces for {} sessions...", sessions.len());
+                }
+
+                // Look up trace counts with bounded concurrency, preserving session order
+                let exports: Vec<SessionExport> = stream::iter(sessions)
+                    .map(|session| {
+                        let client = &client;
+                        async move {
+                            let trace_count = client
+                                .count_traces(None, None, Some(&session.id), None, None, None, None)
+                                .await?;
+                            Ok::<_, anyhow::Error>(SessionExport {
+                                id: session.id,
+                                created_at: session.created_at,
+                                project_id: session.project_id,
+                                trace_count,
+                            })
+                        }
+                    })
+                    .buffered(config.concurrency.max(1))
+                    .try_collect()
+                    .await?;
+
+                format_and_output(
+                    &exports,
+                    config.format_or(OutputFormat::Csv),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )
+            }
+
+            SessionsCommands::Metrics {
+                id,
+                format,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    *format,
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                // Fails with a clear not-found error for an unknown session
+                let session = client.get_session(id).await?;
+                let traces = client
+                    .list_traces(
+                        None,
+                        None,
+                        Some(id),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        UNLIMITED,
+                        1,
+                    )
+                    .await?;
+
+                // One observation listing per trace, with bounded concurrency
+                let observations: Vec<Vec<Observation>> = stream::iter(&traces)
+                    .map(|trace| {
+                        client.list_observations(
+                            Some(&trace.id),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            UNLIMITED,
+                            1,
+                        )
+                    })
+                    .buffered(config.concurrency.max(1))
+                    .try_collect()
+                    .await?;
+                let observations: Vec<Observation> = observations.into_iter().flatten().collect();
+
+                format_and_output(
+                    &session_metrics(&session.id, &traces, &observations),
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
 
@@ -163,12 +423,10 @@ impl SessionsCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
@@ -177,18 +435,88 @@ impl SessionsCommands {
                 // Fetch traces if requested
                 if *with_traces {
                     let traces = client
-                        .list_traces(None, None, Some(id), None, None, None, 100, 1)
+                        .list_traces(None, None, Some(id), None, None, None, None, None, 100, 1)
                         .await?;
                     session.traces = traces;
                 }
 
                 format_and_output(
                     &session,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
         }
     }
 }
+
+/// Totals over a session's traces and their observations. Observations that
+/// report no usage count as zero tokens and zero cost.
+fn session_metrics(
+    session_id: &str,
+    traces: &[Trace],
+    observations: &[Observation],
+) -> SessionMetrics {
+    let mut metrics = SessionMetrics {
+        session_id: session_id.to_string(),
+        trace_count: traces.len(),
+        observation_count: observations.len(),
+        ..Default::default()
+    };
+
+    for usage in observations.iter().filter_map(|o| o.usage.as_ref()) {
+        metrics.total_tokens += usage
+            .total
+            .unwrap_or_else(|| usage.input.unwrap_or(0) + usage.output.unwrap_or(0));
+        metrics.total_cost += usage
+            .total_cost
+            .unwrap_or_else(|| usage.input_cost.unwrap_or(0.0) + usage.output_cost.unwrap_or(0.0));
+    }
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_session_metrics_sums_usage() {
+        let traces: Vec<Trace> =
+            serde_json::from_value(json!([{"id": "t-1"}, {"id": "t-2"}])).unwrap();
+        let observations: Vec<Observation> = serde_json::from_value(json!([
+            {"id": "o-1", "traceId": "t-1", "usage": {"total": 150, "totalCost": 0.25}},
+            {"id": "o-2", "traceId": "t-1", "usage": {"input": 40, "output": 10,
+                "inputCost": 0.01, "outputCost": 0.02}},
+            {"id": "o-3", "traceId": "t-2"}
+        ]))
+        .unwrap();
+
+        let metrics = session_metrics("sess-1", &traces, &observations);
+
+        assert_eq!(metrics.session_id, "sess-1");
+        assert_eq!(metrics.trace_count, 2);
+        assert_eq!(metrics.observation_count, 3);
+        assert_eq!(metrics.total_tokens, 200);
+        assert!((metrics.total_cost - 0.28).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_session_metrics_without_observations_is_zero() {
+        let traces: Vec<Trace> = serde_json::from_value(json!([{"id": "t-1"}])).unwrap();
+
+        let metrics = session_metrics("sess-1", &traces, &[]);
+
+        assert_eq!(
+            metrics,
+            SessionMetrics {
+                session_id: "sess-1".to_string(),
+                trace_count: 1,
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -1,11 +1,27 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
 
-use crate::client::LangfuseClient;
-use crate::commands::{build_config, format_and_output};
-use crate::types::OutputFormat;
+use crate::client::{LangfuseClient, UNLIMITED};
+use crate::commands::{
+    build_config, confirm_destructive, exit_if_empty, format_and_output, gzip_path, output_result,
+    require_credentials, resolve_limit, split_part_path, GlobalOptions,
+};
+use crate::formatters::format_output;
+use crate::histogram;
+use crate::record_filter::keep_matching;
+use crate::redact::Redactor;
+use crate::time_arg::parse_time_arg;
+use crate::timezone::convert_timestamps;
+use crate::tree;
+use crate::types::{OutputFormat, SortOrder, Trace};
 
 #[derive(Debug, Subcommand)]
+#[allow(clippy::large_enum_variant)]
 pub enum TracesCommands {
     /// List traces with optional filters
     List {
@@ -25,17 +41,50 @@ pub enum TracesCommands {
         #[arg(short, long)]
         tags: Option<Vec<String>>,
 
-        /// Filter from timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         from: Option<String>,
 
-        /// Filter to timestamp (ISO 8601 format)
-        #[arg(long)]
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
         to: Option<String>,
 
-        /// Maximum number of results
-        #[arg(short, long, default_value = "50")]
-        limit: u32,
+        /// Filter by environment (e.g., production, staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Only show traces with at least this many observations (applied after fetching)
+        #[arg(long)]
+        min_observations: Option<usize>,
+
+        /// Only show traces with at most this many observations (applied after fetching)
+        #[arg(long)]
+        max_observations: Option<usize>,
+
+        /// Maximum number of results, 0 for no limit [default: 50, or the profile's limit]
+        #[arg(short, long)]
+        limit: Option<u32>,
+
+        /// Fetch every page, ignoring --limit
+        #[arg(long, conflicts_with = "limit")]
+        all: bool,
+
+        /// Exit with status 2 if no results match (after printing the empty output)
+        #[arg(long)]
+        fail_empty: bool,
+
+        /// Output a histogram of value counts for this field instead of the records
+        #[arg(long, value_name = "FIELD")]
+        count_by: Option<String>,
+
+        /// Page by timestamp instead of page number, so traces arriving
+        /// mid-listing can't cause duplicates or skips
+        #[arg(long, conflicts_with = "page")]
+        stable: bool,
+
+        /// Order by timestamp: asc (oldest first) or desc (newest first, the default)
+        #[arg(long, value_enum, ignore_case = true, conflicts_with = "stable")]
+        order: Option<SortOrder>,
 
         /// Page number
         #[arg(short, long, default_value = "1")]
@@ -70,6 +119,124 @@ pub enum TracesCommands {
         verbose: bool,
     },
 
+    /// Count traces matching the filters without fetching them
+    Count {
+        /// Filter by trace name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Filter by user ID
+        #[arg(short, long)]
+        user_id: Option<String>,
+
+        /// Filter by session ID
+        #[arg(short, long)]
+        session_id: Option<String>,
+
+        /// Filter by tags (can be specified multiple times)
+        #[arg(short, long)]
+        tags: Option<Vec<String>>,
+
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        from: Option<String>,
+
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        to: Option<String>,
+
+        /// Filter by environment (e.g., production, staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Export every matching trace to a JSONL file, writing each page as it
+    /// arrives so large projects don't have to fit in memory
+    #[command(group(
+        clap::ArgGroup::new("export_target")
+            .args(["output", "split_size"])
+            .required(true)
+            .conflicts_with_all(["sort", "reverse"])
+    ))]
+    Export {
+        /// Filter by trace name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Filter by user ID
+        #[arg(short, long)]
+        user_id: Option<String>,
+
+        /// Filter by session ID
+        #[arg(short, long)]
+        session_id: Option<String>,
+
+        /// Filter by tags (can be specified multiple times)
+        #[arg(short, long)]
+        tags: Option<Vec<String>>,
+
+        /// Filter from timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        from: Option<String>,
+
+        /// Filter to timestamp (ISO 8601, or relative like -7d, -12h, now, today)
+        #[arg(long, allow_hyphen_values = true, value_parser = parse_time_arg)]
+        to: Option<String>,
+
+        /// Filter by environment (e.g., production, staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// JSONL file to write (gets a .gz extension with --gzip), unless
+        /// --split-size writes numbered parts to --output-dir instead
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
     /// Get a specific trace by ID
     Get {
         /// Trace ID
@@ -83,6 +250,14 @@ pub enum TracesCommands {
         #[arg(long)]
         summary: bool,
 
+        /// Show observations as an indented parent/child tree with latencies
+        #[arg(long, conflicts_with_all = ["format", "summary"])]
+        tree: bool,
+
+        /// Include the trace's scores under a `scores` field
+        #[arg(long, conflicts_with = "tree")]
+        include_scores: bool,
+
         /// Output format
         #[arg(short, long, value_enum)]
         format: Option<OutputFormat>,
@@ -111,10 +286,40 @@ pub enum TracesCommands {
         #[arg(short, long)]
         verbose: bool,
     },
+
+    /// Delete a trace by ID
+    Delete {
+        /// Trace ID
+        id: String,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Profile name
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Langfuse public key
+        #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+        public_key: Option<String>,
+
+        /// Langfuse secret key
+        #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+        secret_key: Option<String>,
+
+        /// Langfuse host URL
+        #[arg(long, env = "LANGFUSE_HOST")]
+        host: Option<String>,
+
+        /// Verbose output
+        #[arg(short, long)]
+        verbose: bool,
+    },
 }
 
 impl TracesCommands {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
         match self {
             TracesCommands::List {
                 name,
@@ -123,7 +328,15 @@ impl TracesCommands {
                 tags,
                 from,
                 to,
+                env,
+                min_observations,
+                max_observations,
                 limit,
+                all,
+                fail_empty,
+                count_by,
+                stable,
+                order,
                 page,
                 format,
                 output,
@@ -139,38 +352,235 @@ impl TracesCommands {
                     secret_key.as_deref(),
                     host.as_deref(),
                     *format,
-                    Some(*limit),
+                    *limit,
                     Some(*page),
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let limit = resolve_limit(config.limit, *all);
+                let mut traces = if *stable {
+                    client
+                        .list_traces_stable(
+                            name.as_deref(),
+                            user_id.as_deref(),
+                            session_id.as_deref(),
+                            tags.as_deref(),
+                            from.as_deref(),
+                            to.as_deref(),
+                            env.as_deref(),
+                            limit,
+                        )
+                        .await?
+                } else {
+                    client
+                        .list_traces(
+                            name.as_deref(),
+                            user_id.as_deref(),
+                            session_id.as_deref(),
+                            tags.as_deref(),
+                            from.as_deref(),
+                            to.as_deref(),
+                            env.as_deref(),
+                            order.map(SortOrder::to_order_by),
+                            limit,
+                            *page,
+                        )
+                        .await?
+                };
+
+                traces = keep_matching(traces, &global.filters)?;
+
+                if min_observations.is_some() || max_observations.is_some() {
+                    traces =
+                        filter_by_observation_count(traces, *min_observations, *max_observations);
                 }
 
+                if let Some(field) = count_by {
+                    format_and_output(
+                        &histogram::count_by(&traces, field)?,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    )?;
+                    return exit_if_empty(*fail_empty, traces.len());
+                }
+
+                format_and_output(
+                    &traces,
+                    config.format,
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
+                )?;
+
+                exit_if_empty(*fail_empty, traces.len())
+            }
+
+            TracesCommands::Export {
+                name,
+                user_id,
+                session_id,
+                tags,
+                from,
+                to,
+                env,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    Some(OutputFormat::Jsonl),
+                    None,
+                    None,
+                    output.as_deref(),
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
                 let client = LangfuseClient::new(&config)?;
 
-                let traces = client
-                    .list_traces(
+                let (mut target, destination) =
+                    match (output, global.split_size, &global.output_dir) {
+                        (Some(output), _, _) => {
+                            let path = if global.gzip {
+                                gzip_path(output)
+                            } else {
+                                output.clone()
+                            };
+                            let file = ExportFile::create(&path, global.gzip, global.append)?;
+                            (
+                                ExportTarget::File {
+                                    file,
+                                    path: path.clone(),
+                                },
+                                path,
+                            )
+                        }
+                        (None, Some(size), Some(dir)) => {
+                            std::fs::create_dir_all(dir)?;
+                            let parts = ExportParts {
+                                dir: dir.clone(),
+                                size,
+                                gzip: global.gzip,
+                                append: global.append,
+                                part: 0,
+                                written: 0,
+                                current: None,
+                            };
+                            (ExportTarget::Parts(parts), dir.display().to_string())
+                        }
+                        _ => anyhow::bail!("Pass --output, or --split-size with --output-dir"),
+                    };
+                let redactor = Redactor::new(&global.censor, global.censor_pattern.as_deref())?;
+                let timezone = global.display_timezone(OutputFormat::Jsonl);
+                let options = global.format_options()?;
+
+                let mut exported = 0;
+                client
+                    .export_traces(
                         name.as_deref(),
                         user_id.as_deref(),
                         session_id.as_deref(),
                         tags.as_deref(),
                         from.as_deref(),
                         to.as_deref(),
-                        *limit,
-                        *page,
+                        env.as_deref(),
+                        |trace| {
+                            let mut value = serde_json::to_value(trace)?;
+                            if !global.filters.iter().all(|c| c.matches(&value)) {
+                                return Ok(());
+                            }
+                            redactor.apply(&mut value);
+                            if let Some(tz) = timezone {
+                                convert_timestamps(&mut value, tz);
+                            }
+                            target.write_line(&format_output(
+                                &value,
+                                OutputFormat::Jsonl,
+                                &options,
+                            )?)?;
+                            exported += 1;
+                            Ok(())
+                        },
                     )
                     .await?;
+                target.finish()?;
 
-                format_and_output(
-                    &traces,
-                    format.unwrap_or(OutputFormat::Table),
+                if !global.quiet {
+                    eprintln!("Exported {exported} traces to {destination}");
+                }
+                Ok(())
+            }
+
+            TracesCommands::Count {
+                name,
+                user_id,
+                session_id,
+                tags,
+                from,
+                to,
+                env,
+                output,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
                     output.as_deref(),
                     *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                let client = LangfuseClient::new(&config)?;
+
+                let total = client
+                    .count_traces(
+                        name.as_deref(),
+                        user_id.as_deref(),
+                        session_id.as_deref(),
+                        tags.as_deref(),
+                        from.as_deref(),
+                        to.as_deref(),
+                        env.as_deref(),
+                    )
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("The API did not report a total trace count"))?;
+
+                output_result(
+                    &total.to_string(),
+                    config.output.as_deref(),
+                    *verbose,
+                    global,
                 )
             }
 
@@ -178,6 +588,8 @@ impl TracesCommands {
                 id,
                 with_observations,
                 summary,
+                tree,
+                include_scores,
                 format,
                 output,
                 profile,
@@ -197,22 +609,42 @@ impl TracesCommands {
                     output.as_deref(),
                     *verbose,
                     false,
+                    global,
                 )?;
 
-                if !config.is_valid() {
-                    eprintln!("Error: Missing credentials. Run 'lf config setup' or set environment variables.");
-                    std::process::exit(1);
-                }
+                require_credentials(&config)?;
 
                 let client = LangfuseClient::new(&config)?;
 
                 let mut trace = client.get_trace(id).await?;
 
                 // Fetch observations if requested
-                if *with_observations {
+                if *with_observations || *tree {
                     let observations = client
-                        .list_observations(Some(id), None, None, None, None, None, 100, 1)
+                        .list_observations(
+                            Some(id),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            100,
+                            1,
+                        )
                         .await?;
+
+                    if *tree {
+                        return output_result(
+                            &tree::render_trace_tree(&trace, &observations),
+                            config.output.as_deref(),
+                            *verbose,
+                            global,
+                        );
+                    }
+
                     trace.observations = observations
                         .into_iter()
                         .map(|o| {
@@ -226,17 +658,102 @@ impl TracesCommands {
                         .collect();
                 }
 
+                if *include_scores {
+                    let scores = client
+                        .list_scores(None, Some(id), None, None, None, UNLIMITED, 1)
+                        .await?;
+                    let mut trace = serde_json::to_value(&trace)?;
+                    trace["scores"] = serde_json::to_value(scores)?;
+                    return format_and_output(
+                        &trace,
+                        config.format,
+                        config.output.as_deref(),
+                        *verbose,
+                        global,
+                    );
+                }
+
                 format_and_output(
                     &trace,
-                    format.unwrap_or(OutputFormat::Table),
-                    output.as_deref(),
+                    config.format,
+                    config.output.as_deref(),
                     *verbose,
+                    global,
                 )
             }
+
+            TracesCommands::Delete {
+                id,
+                yes,
+                profile,
+                public_key,
+                secret_key,
+                host,
+                verbose,
+            } => {
+                let config = build_config(
+                    profile.as_deref(),
+                    public_key.as_deref(),
+                    secret_key.as_deref(),
+                    host.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    *verbose,
+                    false,
+                    global,
+                )?;
+
+                require_credentials(&config)?;
+
+                if config.dry_run {
+                    if !config.quiet {
+                        eprintln!("Dry run: would delete trace '{id}'");
+                    }
+                } else if !*yes {
+                    let confirmed = confirm_destructive(&format!(
+                        "Delete trace '{id}'? This cannot be undone"
+                    ))?;
+                    if !confirmed {
+                        if !config.quiet {
+                            eprintln!("Aborted");
+                        }
+                        return Ok(());
+                    }
+                }
+
+                let client = LangfuseClient::new(&config)?;
+
+                client.delete_trace(id).await?;
+
+                if config.verbose {
+                    eprintln!("Trace '{}' deleted successfully", id);
+                }
+
+                Ok(())
+            }
         }
     }
 }
 
+/// Keeps traces whose observation count is within the given bounds.
+/// Counts the observation IDs returned by the list endpoint, so the filter
+/// runs on the fetched page(s) and may return fewer than `--limit` traces.
+fn filter_by_observation_count(
+    traces: Vec<Trace>,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Vec<Trace> {
+    traces
+        .into_iter()
+        .filter(|t| {
+            let count = t.observations.len();
+            min.is_none_or(|m| count >= m) && max.is_none_or(|m| count <= m)
+        })
+        .collect()
+}
+
 /// Strips large content fields (input, output) from an observation JSON value.
 fn strip_observation_content(mut obs: serde_json::Value) -> serde_json::Value {
     if let Some(obj) = obs.as_object_mut() {
@@ -246,10 +763,606 @@ fn strip_observation_content(mut obs: serde_json::Value) -> serde_json::Value {
     obs
 }
 
+/// Buffered file for `traces export`, optionally gzip-compressed. Traces
+/// are written to a temporary file beside `path` that only replaces it once
+/// `finish` succeeds, so a failed export leaves any existing file untouched.
+/// With `--append` they go straight onto the end of the existing file.
+struct ExportFile {
+    writer: Option<ExportWriter>,
+    temp_path: Option<PathBuf>,
+    path: PathBuf,
+}
+
+enum ExportWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl ExportFile {
+    fn create(path: &str, gzip: bool, append: bool) -> Result<Self> {
+        let path = PathBuf::from(path);
+        let (file, temp_path) = if append {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            (file, None)
+        } else {
+            let file_name = path
+                .file_name()
+                .with_context(|| format!("Invalid output path {}", path.display()))?;
+            let temp_path = path.with_file_name(format!(
+                ".{}.{}.partial",
+                file_name.to_string_lossy(),
+                std::process::id()
+            ));
+            let file = File::create(&temp_path)
+                .with_context(|| format!("Failed to create {}", temp_path.display()))?;
+            (file, Some(temp_path))
+        };
+
+        let file = BufWriter::new(file);
+        let writer = if gzip {
+            ExportWriter::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            ExportWriter::Plain(file)
+        };
+
+        Ok(ExportFile {
+            writer: Some(writer),
+            temp_path,
+            path,
+        })
+    }
+
+    /// Flush everything to disk, writing the gzip trailer if compressing, and
+    /// move the finished file into place
+    fn finish(mut self) -> Result<()> {
+        match self.writer.take().expect("export file already finished") {
+            ExportWriter::Plain(mut file) => file.flush()?,
+            ExportWriter::Gzip(encoder) => encoder.finish()?.flush()?,
+        }
+        match self.temp_path.take() {
+            Some(temp_path) => std::fs::rename(&temp_path, &self.path)
+                .with_context(|| format!("Failed to move the export into {}", self.path.display())),
+            None => Ok(()),
+        }
+    }
+
+    fn writer(&mut self) -> io::Result<&mut ExportWriter> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| io::Error::other("export file already finished"))
+    }
+}
+
+impl Drop for ExportFile {
+    /// An export that never finished leaves no temporary file behind (after
+    /// a successful `finish` it has already been renamed away)
+    fn drop(&mut self) {
+        if let Some(temp_path) = &self.temp_path {
+            let _ = std::fs::remove_file(temp_path);
+        }
+    }
+}
+
+/// Where `traces export` writes: one file, or numbered parts with
+/// `--split-size`
+enum ExportTarget {
+    File { file: ExportFile, path: String },
+    Parts(ExportParts),
+}
+
+/// Numbered part files of at most `size` traces in `dir`, each finished
+/// before the next is started
+struct ExportParts {
+    dir: PathBuf,
+    size: usize,
+    gzip: bool,
+    append: bool,
+    part: usize,
+    written: usize,
+    current: Option<ExportFile>,
+}
+
+impl ExportTarget {
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        let file = match self {
+            ExportTarget::File { file, .. } => file,
+            ExportTarget::Parts(parts) => parts.file_for_next()?,
+        };
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            ExportTarget::File { file, path } => file
+                .finish()
+                .with_context(|| format!("Failed to write {path}")),
+            ExportTarget::Parts(mut parts) => parts.finish_current(),
+        }
+    }
+}
+
+impl ExportParts {
+    /// The part file the next trace goes into, starting a new part when the
+    /// current one is full
+    fn file_for_next(&mut self) -> Result<&mut ExportFile> {
+        if self.current.is_none() || self.written == self.size {
+            self.finish_current()?;
+            self.part += 1;
+            self.written = 0;
+            let path = split_part_path(&self.dir, self.part, OutputFormat::Jsonl);
+            let mut path = path.to_string_lossy().to_string();
+            if self.gzip {
+                path = gzip_path(&path);
+            }
+            self.current = Some(ExportFile::create(&path, self.gzip, self.append)?);
+        }
+        self.written += 1;
+        Ok(self.current.as_mut().expect("part file was just opened"))
+    }
+
+    fn finish_current(&mut self) -> Result<()> {
+        match self.current.take() {
+            Some(file) => {
+                let path = file.path.display().to_string();
+                file.finish()
+                    .with_context(|| format!("Failed to write {path}"))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for ExportFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.writer()? {
+            ExportWriter::Plain(file) => file.write(buf),
+            ExportWriter::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.writer()? {
+            ExportWriter::Plain(file) => file.flush(),
+            ExportWriter::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_dry_run_delete_skips_confirmation_and_request() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let command = TracesCommands::Delete {
+            id: "trace-1".to_string(),
+            yes: false,
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+        let global = GlobalOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+
+        // Without a terminal, prompting would fail with a different error
+        let err = command.execute(&global).await.unwrap_err();
+        assert!(crate::client::is_dry_run(&err));
+    }
+
+    /// A `traces list` command writing JSON to `output`
+    fn list_command(host: String, limit: u32, output: &std::path::Path) -> TracesCommands {
+        TracesCommands::List {
+            name: None,
+            user_id: None,
+            session_id: None,
+            tags: None,
+            from: None,
+            to: None,
+            env: None,
+            min_observations: None,
+            max_observations: None,
+            limit: Some(limit),
+            all: false,
+            fail_empty: false,
+            count_by: None,
+            stable: false,
+            order: None,
+            page: 1,
+            format: Some(OutputFormat::Json),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(host),
+            verbose: false,
+        }
+    }
+
+    /// Mock a page of two traces per entry in `fetches`, expecting page N to
+    /// be fetched `fetches[N - 1]` times
+    async fn mount_trace_pages(mock_server: &MockServer, fetches: &[u64]) {
+        let pages = fetches.len();
+        for (i, times) in fetches.iter().enumerate() {
+            let page = i + 1;
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page.to_string()))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": [{"id": format!("t-{page}a")}, {"id": format!("t-{page}b")}],
+                    "meta": {"page": page, "limit": 2, "totalItems": pages * 2, "totalPages": pages}
+                })))
+                .expect(*times)
+                .mount(mock_server)
+                .await;
+        }
+    }
+
+    fn output_ids(output: &std::path::Path) -> Vec<String> {
+        let traces: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap();
+        traces
+            .iter()
+            .map(|t| t["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_limit_zero_fetches_every_page() {
+        let mock_server = MockServer::start().await;
+        mount_trace_pages(&mock_server, &[1, 1, 1]).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("traces.json");
+        list_command(mock_server.uri(), 0, &output)
+            .execute(&GlobalOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output_ids(&output),
+            ["t-1a", "t-1b", "t-2a", "t-2b", "t-3a", "t-3b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_limit_two_stops_after_first_page() {
+        let mock_server = MockServer::start().await;
+        mount_trace_pages(&mock_server, &[1, 0, 0]).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("traces.json");
+        list_command(mock_server.uri(), 2, &output)
+            .execute(&GlobalOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(output_ids(&output), ["t-1a", "t-1b"]);
+    }
+
+    #[tokio::test]
+    async fn test_count_prints_total_items() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("limit", "1"))
+            .and(query_param("name", "chat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "trace-1"}],
+                "meta": {"page": 1, "limit": 1, "totalItems": 4321, "totalPages": 4321}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("count.txt");
+        let command = TracesCommands::Count {
+            name: Some("chat".to_string()),
+            user_id: None,
+            session_id: None,
+            tags: None,
+            from: None,
+            to: None,
+            env: None,
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "4321");
+    }
+
+    #[tokio::test]
+    async fn test_failed_export_leaves_existing_file_untouched() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "t-1"}],
+                "meta": {"page": 1, "limit": 1, "totalItems": 2, "totalPages": 2}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("traces.jsonl");
+        std::fs::write(&output, "{\"id\":\"previous\"}\n").unwrap();
+        let command = TracesCommands::Export {
+            name: None,
+            user_id: None,
+            session_id: None,
+            tags: None,
+            from: None,
+            to: None,
+            env: None,
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        assert!(command.execute(&GlobalOptions::default()).await.is_err());
+
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "{\"id\":\"previous\"}\n"
+        );
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_export_writes_one_line_per_trace_across_pages() {
+        let mock_server = MockServer::start().await;
+
+        for (page, ids) in [
+            ("1", vec!["t-1", "t-2"]),
+            ("2", vec!["t-3", "t-4"]),
+            ("3", vec!["t-5"]),
+        ] {
+            let data: Vec<_> = ids.iter().map(|id| json!({"id": id})).collect();
+            Mock::given(method("GET"))
+                .and(path("/api/public/traces"))
+                .and(query_param("page", page))
+                .and(query_param("environment", "production"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "data": data,
+                    "meta": {"page": 1, "limit": 2, "totalItems": 5, "totalPages": 3}
+                })))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("traces.jsonl");
+        let command = TracesCommands::Export {
+            name: None,
+            user_id: None,
+            session_id: None,
+            tags: None,
+            from: None,
+            to: None,
+            env: Some("production".to_string()),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+        let global = GlobalOptions {
+            page_size: 2,
+            quiet: true,
+            ..Default::default()
+        };
+
+        command.execute(&global).await.unwrap();
+
+        let content = std::fs::read_to_string(&output).unwrap();
+        let ids: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let trace: serde_json::Value = serde_json::from_str(line).unwrap();
+                trace["id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ids, ["t-1", "t-2", "t-3", "t-4", "t-5"]);
+    }
+
+    /// A `traces export` command against `host`, writing to `output` if given
+    fn export_command(host: String, output: Option<&std::path::Path>) -> TracesCommands {
+        TracesCommands::Export {
+            name: None,
+            user_id: None,
+            session_id: None,
+            tags: None,
+            from: None,
+            to: None,
+            env: None,
+            output: output.map(|p| p.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(host),
+            verbose: false,
+        }
+    }
+
+    async fn mount_export_traces(mock_server: &MockServer, traces: serde_json::Value) {
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": traces,
+                "meta": {"page": 1, "limit": 100, "totalItems": 5, "totalPages": 1}
+            })))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_export_applies_where_fields_and_append() {
+        let mock_server = MockServer::start().await;
+        mount_export_traces(
+            &mock_server,
+            json!([
+                {"id": "t-1", "name": "chat", "input": "hi"},
+                {"id": "t-2", "name": "search", "input": "find"},
+                {"id": "t-3", "name": "chat", "input": "bye"}
+            ]),
+        )
+        .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("traces.jsonl");
+        std::fs::write(&output, "{\"id\":\"previous\"}\n").unwrap();
+        let global = GlobalOptions {
+            filters: vec![crate::record_filter::parse_condition("name=chat").unwrap()],
+            fields: vec!["id".to_string(), "input".to_string()],
+            exclude_fields: vec!["input".to_string()],
+            append: true,
+            quiet: true,
+            ..Default::default()
+        };
+
+        export_command(mock_server.uri(), Some(&output))
+            .execute(&global)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&output).unwrap(),
+            "{\"id\":\"previous\"}\n{\"id\":\"t-1\"}\n{\"id\":\"t-3\"}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_splits_into_parts() {
+        let mock_server = MockServer::start().await;
+        let traces: Vec<_> = (1..=5).map(|i| json!({"id": format!("t-{i}")})).collect();
+        mount_export_traces(&mock_server, json!(traces)).await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("parts");
+        let global = GlobalOptions {
+            split_size: Some(2),
+            output_dir: Some(dir.clone()),
+            quiet: true,
+            ..Default::default()
+        };
+
+        export_command(mock_server.uri(), None)
+            .execute(&global)
+            .await
+            .unwrap();
+
+        let part_ids = |n: &str| -> Vec<String> {
+            std::fs::read_to_string(dir.join(n))
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    let trace: serde_json::Value = serde_json::from_str(line).unwrap();
+                    trace["id"].as_str().unwrap().to_string()
+                })
+                .collect()
+        };
+        assert_eq!(part_ids("part-0001.jsonl"), ["t-1", "t-2"]);
+        assert_eq!(part_ids("part-0002.jsonl"), ["t-3", "t-4"]);
+        assert_eq!(part_ids("part-0003.jsonl"), ["t-5"]);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_include_scores_attaches_trace_scores() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/api/public/traces/trace-1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"id": "trace-1", "name": "chat"})),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/scores"))
+            .and(query_param("traceId", "trace-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [
+                    {"id": "score-1", "traceId": "trace-1", "name": "accuracy", "value": 0.9},
+                    {"id": "score-2", "traceId": "trace-1", "name": "helpful", "value": 1.0}
+                ],
+                "meta": {"page": 1, "limit": 100, "totalItems": 2, "totalPages": 1}
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("trace.json");
+        let command = TracesCommands::Get {
+            id: "trace-1".to_string(),
+            with_observations: false,
+            summary: false,
+            tree: false,
+            include_scores: true,
+            format: Some(OutputFormat::Json),
+            output: Some(output.to_string_lossy().to_string()),
+            profile: None,
+            public_key: Some("pk-test".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let trace: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(trace["id"], "trace-1");
+        assert_eq!(trace["name"], "chat");
+        let names: Vec<&str> = trace["scores"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["accuracy", "helpful"]);
+    }
 
     #[test]
     fn test_strip_observation_content_removes_input_output() {
@@ -285,6 +1398,35 @@ mod tests {
         );
     }
 
+    fn trace_with_observations(id: &str, count: usize) -> Trace {
+        serde_json::from_value(json!({
+            "id": id,
+            "observations": (0..count).map(|i| format!("obs-{i}")).collect::<Vec<_>>()
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_by_observation_count_bounds() {
+        let traces = vec![
+            trace_with_observations("empty", 0),
+            trace_with_observations("small", 2),
+            trace_with_observations("large", 10),
+        ];
+
+        let min_only = filter_by_observation_count(traces.clone(), Some(2), None);
+        let ids: Vec<&str> = min_only.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["small", "large"]);
+
+        let max_only = filter_by_observation_count(traces.clone(), None, Some(2));
+        let ids: Vec<&str> = max_only.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["empty", "small"]);
+
+        let both = filter_by_observation_count(traces, Some(1), Some(5));
+        let ids: Vec<&str> = both.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["small"]);
+    }
+
     #[test]
     fn test_strip_observation_content_handles_missing_fields() {
         let obs = json!({
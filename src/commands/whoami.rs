@@ -0,0 +1,169 @@
+use anyhow::Result;
+use clap::Args;
+use serde_json::{json, Value};
+
+use crate::client::LangfuseClient;
+use crate::commands::{build_config, format_and_output, require_credentials, GlobalOptions};
+use crate::config::Config;
+use crate::types::{OutputFormat, Project};
+
+/// Project name shown when the keys can't see any projects
+const NO_PROJECTS: &str = "(no projects)";
+
+/// Arguments for `lf whoami`
+#[derive(Debug, Args)]
+pub struct WhoamiCommand {
+    /// Output format
+    #[arg(short, long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Output file path
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Profile name
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Langfuse public key
+    #[arg(long, env = "LANGFUSE_PUBLIC_KEY")]
+    public_key: Option<String>,
+
+    /// Langfuse secret key
+    #[arg(long, env = "LANGFUSE_SECRET_KEY")]
+    secret_key: Option<String>,
+
+    /// Langfuse host URL
+    #[arg(long, env = "LANGFUSE_HOST")]
+    host: Option<String>,
+
+    /// Verbose output
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+impl WhoamiCommand {
+    pub async fn execute(&self, global: &GlobalOptions) -> Result<()> {
+        let config = build_config(
+            self.profile.as_deref(),
+            self.public_key.as_deref(),
+            self.secret_key.as_deref(),
+            self.host.as_deref(),
+            self.format,
+            None,
+            None,
+            self.output.as_deref(),
+            self.verbose,
+            false,
+            global,
+        )?;
+
+        require_credentials(&config)?;
+
+        let client = LangfuseClient::new(&config)?;
+        let projects = client.get_projects().await?;
+
+        format_and_output(
+            &identity_rows(&config, &projects),
+            config.format,
+            config.output.as_deref(),
+            self.verbose,
+            global,
+        )
+    }
+}
+
+/// One row per accessible project, each carrying the resolved profile, host
+/// and masked public key. Keys without projects still get a row, marked
+/// "(no projects)", so the profile and host are always shown.
+fn identity_rows(config: &Config, projects: &[Project]) -> Vec<Value> {
+    let public_key = config
+        .public_key
+        .as_deref()
+        .map(Config::mask_key)
+        .unwrap_or_default();
+
+    let row = |project_id: Value, project_name: Value| {
+        json!({
+            "profile": config.profile,
+            "host": config.host,
+            "publicKey": public_key,
+            "projectId": project_id,
+            "projectName": project_name,
+        })
+    };
+
+    if projects.is_empty() {
+        return vec![row(Value::Null, json!(NO_PROJECTS))];
+    }
+
+    projects
+        .iter()
+        .map(|project| row(json!(project.id), json!(project.name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_whoami_shows_project() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/public/projects"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": "proj-1", "name": "Support Bot"}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("whoami.json");
+        let command = WhoamiCommand {
+            format: Some(OutputFormat::Json),
+            output: Some(out.to_str().unwrap().to_string()),
+            profile: Some("whoami-test".to_string()),
+            public_key: Some("pk-lf-1234567890".to_string()),
+            secret_key: Some("sk-test".to_string()),
+            host: Some(mock_server.uri()),
+            verbose: false,
+        };
+
+        command.execute(&GlobalOptions::default()).await.unwrap();
+
+        let rows: Value = serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(rows[0]["projectId"], "proj-1");
+        assert_eq!(rows[0]["projectName"], "Support Bot");
+        assert_eq!(rows[0]["profile"], "whoami-test");
+        assert_eq!(rows[0]["host"], mock_server.uri());
+        assert_eq!(rows[0]["publicKey"], "pk-lf-12********");
+    }
+
+    #[test]
+    fn test_identity_rows_without_projects_keep_profile_and_host() {
+        let config = Config {
+            profile: "empty".to_string(),
+            host: "https://langfuse.example.com".to_string(),
+            public_key: Some("pk-lf-1234567890".to_string()),
+            ..Config::default()
+        };
+
+        let rows = identity_rows(&config, &[]);
+
+        assert_eq!(
+            rows,
+            vec![json!({
+                "profile": "empty",
+                "host": "https://langfuse.example.com",
+                "publicKey": "pk-lf-12********",
+                "projectId": null,
+                "projectName": "(no projects)",
+            })]
+        );
+    }
+}
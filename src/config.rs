@@ -3,13 +3,64 @@ use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::client::{
+    DEFAULT_CONCURRENCY, DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_TIMEOUT_SECS, MAX_PAGE_SIZE,
+};
 use crate::types::OutputFormat;
+use clap::ValueEnum;
 
 const DEFAULT_HOST: &str = "https://cloud.langfuse.com";
 const DEFAULT_PROFILE: &str = "default";
 const DEFAULT_LIMIT: u32 = 50;
+const KEYCHAIN_SERVICE: &str = "lf";
+/// `secret_key` value meaning "look the secret up in the OS keychain"
+pub const KEYRING_SENTINEL: &str = "keyring";
+
+/// Keys of `ConfigFile` and `Profile`; anything else in the file is kept
+/// untouched by `config migrate`
+const CONFIG_FILE_KEYS: &[&str] = &["default_profile", "profiles"];
+const PROFILE_KEYS: &[&str] = &[
+    "public_key",
+    "secret_key",
+    "host",
+    "keychain",
+    "format",
+    "limit",
+    "output",
+];
+
+/// Storage for profile secret keys kept outside the config file
+pub trait SecretStore {
+    fn get(&self, profile_name: &str) -> Result<String>;
+    fn set(&self, profile_name: &str, secret_key: &str) -> Result<()>;
+    fn delete(&self, profile_name: &str) -> Result<()>;
+}
+
+/// The OS keychain: macOS Keychain, Windows Credential Manager or the Linux keyring
+pub struct OsKeychain;
+
+impl OsKeychain {
+    fn entry(profile_name: &str) -> Result<keyring::Entry> {
+        Ok(keyring::Entry::new(KEYCHAIN_SERVICE, profile_name)?)
+    }
+}
+
+impl SecretStore for OsKeychain {
+    fn get(&self, profile_name: &str) -> Result<String> {
+        Ok(Self::entry(profile_name)?.get_password()?)
+    }
+
+    fn set(&self, profile_name: &str, secret_key: &str) -> Result<()> {
+        Ok(Self::entry(profile_name)?.set_password(secret_key)?)
+    }
+
+    fn delete(&self, profile_name: &str) -> Result<()> {
+        Ok(Self::entry(profile_name)?.delete_credential()?)
+    }
+}
 
 /// Profile configuration stored in config file
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,15 +68,56 @@ pub struct Profile {
     pub public_key: Option<String>,
     pub secret_key: Option<String>,
     pub host: Option<String>,
+    /// Secret key is stored in the OS keychain under the profile name
+    /// (also implied by `secret_key: keyring`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keychain: bool,
+    /// Output format used when --format isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+    /// Result limit used when --limit isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Output file used when --output isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Profile {
+    /// Whether the secret key lives in the OS keychain rather than the file
+    pub fn secret_in_keychain(&self) -> bool {
+        self.keychain || self.secret_key.as_deref() == Some(KEYRING_SENTINEL)
+    }
+}
+
+/// Copy entries of `old` whose keys aren't in `known` into `map`, so that
+/// settings this version doesn't understand survive a rewrite
+fn keep_unknown_keys(map: &mut serde_yaml::Mapping, old: &serde_yaml::Mapping, known: &[&str]) {
+    for (key, value) in old {
+        if key.as_str().is_none_or(|k| !known.contains(&k)) && !map.contains_key(key) {
+            map.insert(key.clone(), value.clone());
+        }
+    }
 }
 
 /// Configuration file structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ConfigFile {
+    /// Profile used when none is given on the command line or in LANGFUSE_PROFILE
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
     #[serde(default)]
     pub profiles: HashMap<String, Profile>,
 }
 
+/// Format, limit and output resolved from the flag, env and profile layers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputDefaults {
+    pub format: Option<OutputFormat>,
+    pub limit: Option<u32>,
+    pub output: Option<String>,
+}
+
 /// Runtime configuration with resolved values
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -35,11 +127,33 @@ pub struct Config {
     pub host: String,
     pub profile: String,
     pub format: OutputFormat,
+    /// Whether the format came from the flag, env or profile rather than the built-in default
+    pub format_set: bool,
     pub limit: u32,
     pub page: u32,
     pub output: Option<String>,
     pub verbose: bool,
     pub no_color: bool,
+    /// Timeout for each page request during paginated listing
+    pub page_timeout: Option<Duration>,
+    /// Maximum number of requests in flight when fetching pages
+    pub concurrency: usize,
+    /// Items requested per page during paginated listing
+    pub page_size: u32,
+    /// Suppress informational stderr output (takes precedence over verbose)
+    pub quiet: bool,
+    /// Report each fetched page on stderr while listing
+    pub progress: bool,
+    /// Print mutating requests instead of sending them
+    pub dry_run: bool,
+    /// Overall timeout for each HTTP request in seconds (0 for none)
+    pub timeout_secs: u64,
+    /// Time allowed to establish each connection, in seconds
+    pub connect_timeout_secs: u64,
+    /// Proxy URL for all requests (from --proxy, HTTPS_PROXY or ALL_PROXY)
+    pub proxy: Option<String>,
+    /// User-Agent header to send instead of the default `lf/<version>`
+    pub user_agent: Option<String>,
 }
 
 impl Default for Config {
@@ -50,18 +164,42 @@ impl Default for Config {
             host: DEFAULT_HOST.to_string(),
             profile: DEFAULT_PROFILE.to_string(),
             format: OutputFormat::Table,
+            format_set: false,
             limit: DEFAULT_LIMIT,
             page: 1,
             output: None,
             verbose: false,
             no_color: false,
+            page_timeout: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            page_size: MAX_PAGE_SIZE,
+            quiet: false,
+            progress: false,
+            dry_run: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            proxy: None,
+            user_agent: None,
         }
     }
 }
 
 impl Config {
-    /// Get the config file path
+    /// Get the config file path: LF_CONFIG or LANGFUSE_CONFIG when set,
+    /// otherwise config.yml in the platform config directory
     pub fn config_path() -> Option<PathBuf> {
+        Self::config_path_from(|name| std::env::var(name).ok())
+    }
+
+    fn config_path_from(env: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+        if let Some(path) = ["LF_CONFIG", "LANGFUSE_CONFIG"]
+            .into_iter()
+            .filter_map(&env)
+            .find(|path| !path.trim().is_empty())
+        {
+            return Some(PathBuf::from(path));
+        }
+
         if let Some(proj_dirs) = ProjectDirs::from("", "", "langfuse") {
             let config_dir = proj_dirs.config_dir();
             Some(config_dir.join("config.yml"))
@@ -73,19 +211,21 @@ impl Config {
 
     /// Load configuration file
     pub fn load_config_file() -> Result<ConfigFile> {
-        let path = Self::config_path();
-
-        if let Some(path) = path {
-            if path.exists() {
-                let contents = fs::read_to_string(&path)
-                    .with_context(|| format!("Failed to read config file: {path:?}"))?;
-                let config: ConfigFile = serde_yaml::from_str(&contents)
-                    .with_context(|| "Failed to parse config file")?;
-                return Ok(config);
-            }
+        match Self::config_path() {
+            Some(path) => Self::load_config_file_from(&path),
+            None => Ok(ConfigFile::default()),
+        }
+    }
+
+    /// Load the configuration file at `path`, or the default if it doesn't exist
+    pub fn load_config_file_from(path: &Path) -> Result<ConfigFile> {
+        if !path.exists() {
+            return Ok(ConfigFile::default());
         }
 
-        Ok(ConfigFile::default())
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {path:?}"))?;
+        serde_yaml::from_str(&contents).with_context(|| "Failed to parse config file")
     }
 
     /// Save configuration file
@@ -93,6 +233,11 @@ impl Config {
         let path = Self::config_path()
             .ok_or_else(|| anyhow::anyhow!("Could not determine config file path"))?;
 
+        Self::save_config_file_to(&path, config_file)
+    }
+
+    /// Save the configuration file to `path`, creating its directory if needed
+    pub fn save_config_file_to(path: &Path, config_file: &ConfigFile) -> Result<()> {
         // Create directory if it doesn't exist
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
@@ -102,22 +247,143 @@ impl Config {
         let contents =
             serde_yaml::to_string(config_file).with_context(|| "Failed to serialize config")?;
 
-        fs::write(&path, contents)
+        Self::write_config_contents(path, &contents)
+    }
+
+    /// Write the config file's YAML, readable only by the owner on Unix
+    fn write_config_contents(path: &Path, contents: &str) -> Result<()> {
+        fs::write(path, contents)
             .with_context(|| format!("Failed to write config file: {path:?}"))?;
 
         // Set restrictive permissions on Unix systems
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&path)?.permissions();
+            let mut perms = fs::metadata(path)?.permissions();
             perms.set_mode(0o600);
-            fs::set_permissions(&path, perms)?;
+            fs::set_permissions(path, perms)?;
         }
 
         Ok(())
     }
 
-    /// Load configuration with priority: CLI options > env vars > config file > defaults
+    /// Rewrite the config file in the current canonical shape (see
+    /// `migrate_config_yaml`). Returns its path and the changes, or None when
+    /// there is no config file. The file is only written when something
+    /// changed, and never with `dry_run`.
+    pub fn migrate_config_file(dry_run: bool) -> Result<Option<(PathBuf, Vec<String>)>> {
+        let Some(path) = Self::config_path().filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {path:?}"))?;
+        let (migrated, changes) = Self::migrate_config_yaml(&contents)?;
+
+        if !changes.is_empty() && !dry_run {
+            Self::write_config_contents(&path, &migrated)?;
+        }
+        Ok(Some((path, changes)))
+    }
+
+    /// A config file's YAML in the current canonical shape, with a line per
+    /// change: `secret_key: keyring` becomes `keychain: true`, empty values
+    /// are dropped, and hosts are filled in and normalized. Profiles are
+    /// written in name order and unknown keys are kept. Migrating the result
+    /// again reports no changes.
+    pub fn migrate_config_yaml(contents: &str) -> Result<(String, Vec<String>)> {
+        let raw: serde_yaml::Value =
+            serde_yaml::from_str(contents).with_context(|| "Failed to parse config file")?;
+        let mut config_file: ConfigFile = if raw.is_null() {
+            ConfigFile::default()
+        } else {
+            serde_yaml::from_value(raw.clone()).with_context(|| "Failed to parse config file")?
+        };
+
+        let mut names: Vec<String> = config_file.profiles.keys().cloned().collect();
+        names.sort();
+
+        let mut changes = Vec::new();
+        let mut profiles = serde_yaml::Mapping::new();
+        for name in &names {
+            let profile = config_file.profiles.get_mut(name).expect("listed profile");
+            changes.extend(
+                Self::migrate_profile(profile)
+                    .into_iter()
+                    .map(|change| format!("profile '{name}': {change}")),
+            );
+
+            let mut value = serde_yaml::to_value(&*profile)?;
+            if let (Some(map), Some(old)) = (
+                value.as_mapping_mut(),
+                raw.get("profiles")
+                    .and_then(|p| p.get(name.as_str()))
+                    .and_then(serde_yaml::Value::as_mapping),
+            ) {
+                keep_unknown_keys(map, old, PROFILE_KEYS);
+            }
+            profiles.insert(name.as_str().into(), value);
+        }
+
+        let mut migrated = serde_yaml::Mapping::new();
+        if let Some(default_profile) = &config_file.default_profile {
+            migrated.insert("default_profile".into(), default_profile.as_str().into());
+        }
+        migrated.insert("profiles".into(), profiles.into());
+        if let Some(old) = raw.as_mapping() {
+            keep_unknown_keys(&mut migrated, old, CONFIG_FILE_KEYS);
+        }
+
+        let yaml =
+            serde_yaml::to_string(&migrated).with_context(|| "Failed to serialize config")?;
+        Ok((yaml, changes))
+    }
+
+    /// Bring one profile up to date, describing each change made
+    fn migrate_profile(profile: &mut Profile) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        for (field, value) in [
+            ("public_key", &mut profile.public_key),
+            ("secret_key", &mut profile.secret_key),
+            ("host", &mut profile.host),
+            ("output", &mut profile.output),
+        ] {
+            if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+                *value = None;
+                changes.push(format!("removed empty {field}"));
+            }
+        }
+
+        if profile.secret_key.as_deref() == Some(KEYRING_SENTINEL) {
+            profile.secret_key = None;
+            profile.keychain = true;
+            changes.push(format!(
+                "replaced 'secret_key: {KEYRING_SENTINEL}' with 'keychain: true'"
+            ));
+        }
+
+        match profile.host.as_deref() {
+            None => {
+                profile.host = Some(DEFAULT_HOST.to_string());
+                changes.push(format!("set host to the default {DEFAULT_HOST}"));
+            }
+            Some(host) => {
+                if let Ok(normalized) = Self::normalize_host(host) {
+                    if normalized != host {
+                        changes.push(format!("normalized host '{host}' to '{normalized}'"));
+                        profile.host = Some(normalized);
+                    }
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Load configuration with priority: CLI options > env vars > config file > defaults.
+    /// Format, limit and output fall back to LANGFUSE_FORMAT, LANGFUSE_LIMIT and
+    /// LANGFUSE_OUTPUT, then the profile's defaults.
     #[allow(clippy::too_many_arguments)]
     pub fn load(
         profile: Option<&str>,
@@ -131,13 +397,14 @@ impl Config {
         verbose: bool,
         no_color: bool,
     ) -> Result<Self> {
-        let profile_name = profile
-            .map(|s| s.to_string())
-            .or_else(|| std::env::var("LANGFUSE_PROFILE").ok())
-            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
-
         // Load config file
         let config_file = Self::load_config_file().unwrap_or_default();
+
+        let profile_name = Self::resolve_profile_name(
+            profile,
+            std::env::var("LANGFUSE_PROFILE").ok(),
+            &config_file,
+        );
         let file_profile = config_file.profiles.get(&profile_name);
 
         // Resolve public key: CLI > env > config file
@@ -146,11 +413,17 @@ impl Config {
             .or_else(|| std::env::var("LANGFUSE_PUBLIC_KEY").ok())
             .or_else(|| file_profile.and_then(|p| p.public_key.clone()));
 
-        // Resolve secret key: CLI > env > config file
-        let resolved_secret_key = secret_key
+        // Resolve secret key: CLI > env > config file (or keychain)
+        let resolved_secret_key = match secret_key
             .map(|s| s.to_string())
             .or_else(|| std::env::var("LANGFUSE_SECRET_KEY").ok())
-            .or_else(|| file_profile.and_then(|p| p.secret_key.clone()));
+        {
+            Some(secret_key) => Some(secret_key),
+            None => match file_profile {
+                Some(p) => Self::profile_secret_key(&profile_name, p)?,
+                None => None,
+            },
+        };
 
         // Resolve host: CLI > env > config file > default
         let resolved_host = host
@@ -158,53 +431,288 @@ impl Config {
             .or_else(|| std::env::var("LANGFUSE_HOST").ok())
             .or_else(|| file_profile.and_then(|p| p.host.clone()))
             .unwrap_or_else(|| DEFAULT_HOST.to_string());
+        let resolved_host = Self::normalize_host(&resolved_host)?;
+
+        // Resolve output defaults: CLI > env > config file > built-in default
+        let defaults = Self::resolve_output_defaults(
+            format,
+            limit,
+            output,
+            |name| std::env::var(name).ok(),
+            file_profile,
+        )?;
 
         Ok(Self {
             public_key: resolved_public_key,
             secret_key: resolved_secret_key,
             host: resolved_host,
             profile: profile_name,
-            format: format.unwrap_or(OutputFormat::Table),
-            limit: limit.unwrap_or(DEFAULT_LIMIT),
+            format: defaults.format.unwrap_or(OutputFormat::Table),
+            format_set: defaults.format.is_some(),
+            limit: defaults.limit.unwrap_or(DEFAULT_LIMIT),
             page: page.unwrap_or(1),
-            output: output.map(|s| s.to_string()),
+            output: defaults.output,
             verbose,
             no_color,
+            page_timeout: None,
+            concurrency: DEFAULT_CONCURRENCY,
+            page_size: MAX_PAGE_SIZE,
+            quiet: false,
+            progress: false,
+            dry_run: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            proxy: None,
+            user_agent: None,
         })
     }
 
+    /// Normalize a host: add `https://` when no scheme is given and drop
+    /// trailing slashes. An empty host is left for `is_valid` to report.
+    pub fn normalize_host(host: &str) -> Result<String> {
+        let host = host.trim();
+        if host.is_empty() {
+            return Ok(String::new());
+        }
+
+        let host = if host.contains("://") {
+            host.to_string()
+        } else {
+            format!("https://{host}")
+        };
+        let host = host.trim_end_matches('/').to_string();
+
+        let invalid =
+            || anyhow::anyhow!("Invalid host '{host}'. Expected e.g. https://cloud.langfuse.com");
+        let url = reqwest::Url::parse(&host).map_err(|_| invalid())?;
+        if !matches!(url.scheme(), "http" | "https")
+            || url.host_str().is_none_or(str::is_empty)
+            || host.contains(char::is_whitespace)
+        {
+            return Err(invalid());
+        }
+
+        Ok(host)
+    }
+
+    /// Resolve format, limit and output: the flag wins, then LANGFUSE_FORMAT,
+    /// LANGFUSE_LIMIT and LANGFUSE_OUTPUT, then the profile. None falls back to
+    /// the built-in default.
+    pub fn resolve_output_defaults(
+        format: Option<OutputFormat>,
+        limit: Option<u32>,
+        output: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+        profile: Option<&Profile>,
+    ) -> Result<OutputDefaults> {
+        let env_format = env("LANGFUSE_FORMAT")
+            .map(|f| {
+                OutputFormat::from_str(&f, true)
+                    .map_err(|_| anyhow::anyhow!("Invalid LANGFUSE_FORMAT '{f}'"))
+            })
+            .transpose()?;
+        let env_limit = env("LANGFUSE_LIMIT")
+            .map(|l| {
+                l.parse::<u32>()
+                    .map_err(|_| anyhow::anyhow!("Invalid LANGFUSE_LIMIT '{l}'"))
+            })
+            .transpose()?;
+
+        Ok(OutputDefaults {
+            format: format
+                .or(env_format)
+                .or_else(|| profile.and_then(|p| p.format)),
+            limit: limit
+                .or(env_limit)
+                .or_else(|| profile.and_then(|p| p.limit)),
+            output: output
+                .map(str::to_string)
+                .or_else(|| env("LANGFUSE_OUTPUT"))
+                .or_else(|| profile.and_then(|p| p.output.clone())),
+        })
+    }
+
+    /// Resolve the proxy URL: the flag wins, then HTTPS_PROXY, then ALL_PROXY
+    /// (either case). Empty values are ignored.
+    pub fn resolve_proxy(
+        flag: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Option<String> {
+        flag.map(str::to_string)
+            .into_iter()
+            .chain(
+                ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+                    .into_iter()
+                    .filter_map(&env),
+            )
+            .find(|url| !url.trim().is_empty())
+    }
+
+    /// Profile to use: CLI > LANGFUSE_PROFILE > `config use` default > "default"
+    pub fn resolve_profile_name(
+        profile: Option<&str>,
+        env_profile: Option<String>,
+        config_file: &ConfigFile,
+    ) -> String {
+        profile
+            .map(|s| s.to_string())
+            .or(env_profile)
+            .or_else(|| config_file.default_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Make an existing profile the default. Returns whether the profile exists.
+    pub fn set_default_profile(profile_name: &str) -> Result<bool> {
+        let mut config_file = Self::load_config_file()?;
+
+        if !config_file.profiles.contains_key(profile_name) {
+            return Ok(false);
+        }
+
+        config_file.default_profile = Some(profile_name.to_string());
+        Self::save_config_file(&config_file)?;
+        Ok(true)
+    }
+
+    /// Output format for a command whose own default is `command_default`
+    pub fn format_or(&self, command_default: OutputFormat) -> OutputFormat {
+        if self.format_set {
+            self.format
+        } else {
+            command_default
+        }
+    }
+
     /// Check if configuration has required credentials
     pub fn is_valid(&self) -> bool {
         self.public_key.is_some() && self.secret_key.is_some() && !self.host.is_empty()
     }
 
-    /// Set a profile in the config file
+    /// Set a profile in the config file. With `use_keychain`, the secret key is
+    /// stored in the OS keychain instead, falling back to the file if unavailable.
     pub fn set_profile(
         profile_name: &str,
         public_key: &str,
         secret_key: &str,
         host: Option<&str>,
+        use_keychain: bool,
     ) -> Result<()> {
         let mut config_file = Self::load_config_file().unwrap_or_default();
 
+        let previously_in_keychain = config_file
+            .profiles
+            .get(profile_name)
+            .is_some_and(Profile::secret_in_keychain);
+
+        let in_keychain = Self::store_secret(
+            &OsKeychain,
+            profile_name,
+            secret_key,
+            use_keychain,
+            previously_in_keychain,
+        );
+
+        // Keep any output defaults already set on the profile
+        let previous = config_file
+            .profiles
+            .remove(profile_name)
+            .unwrap_or_default();
+
         config_file.profiles.insert(
             profile_name.to_string(),
             Profile {
                 public_key: Some(public_key.to_string()),
-                secret_key: Some(secret_key.to_string()),
+                secret_key: (!in_keychain).then(|| secret_key.to_string()),
                 host: host.map(|s| s.to_string()),
+                keychain: in_keychain,
+                ..previous
             },
         );
 
         Self::save_config_file(&config_file)
     }
 
+    /// Decide where a profile's secret key goes. With `use_keychain` it is put
+    /// in `store`, falling back to the file (with a warning) if that fails.
+    /// Returns whether the secret ended up in the store.
+    pub fn store_secret(
+        store: &dyn SecretStore,
+        profile_name: &str,
+        secret_key: &str,
+        use_keychain: bool,
+        previously_in_keychain: bool,
+    ) -> bool {
+        let in_keychain = use_keychain
+            && match store.set(profile_name, secret_key) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!(
+                        "Warning: OS keychain unavailable ({e}); storing secret key in config file"
+                    );
+                    false
+                }
+            };
+
+        // Don't leave a stale secret behind when moving back to file storage
+        if previously_in_keychain && !in_keychain {
+            let _ = store.delete(profile_name);
+        }
+
+        in_keychain
+    }
+
+    /// A stored profile's secret key, read from the OS keychain if it lives there
+    pub fn profile_secret_key(profile_name: &str, profile: &Profile) -> Result<Option<String>> {
+        Self::profile_secret_key_from(&OsKeychain, profile_name, profile)
+    }
+
+    /// A stored profile's secret key, read from `store` if it lives there.
+    /// A keychain that can't be read is an error rather than a missing key.
+    pub fn profile_secret_key_from(
+        store: &dyn SecretStore,
+        profile_name: &str,
+        profile: &Profile,
+    ) -> Result<Option<String>> {
+        if !profile.secret_in_keychain() {
+            return Ok(profile.secret_key.clone());
+        }
+
+        store.get(profile_name).map(Some).with_context(|| {
+            format!(
+                "Failed to read the secret key for profile '{profile_name}' from the OS keychain. \
+                 Set LANGFUSE_SECRET_KEY, or run 'lf config set --profile {profile_name}' \
+                 without --use-keychain to store it in the config file"
+            )
+        })
+    }
+
     /// Get a profile from the config file
     pub fn get_profile(profile_name: &str) -> Result<Option<Profile>> {
         let config_file = Self::load_config_file()?;
         Ok(config_file.profiles.get(profile_name).cloned())
     }
 
+    /// Remove a profile from the config file (and its keychain secret, if any).
+    /// Returns whether the profile existed.
+    pub fn delete_profile(profile_name: &str) -> Result<bool> {
+        let mut config_file = Self::load_config_file()?;
+
+        let Some(profile) = config_file.profiles.remove(profile_name) else {
+            return Ok(false);
+        };
+
+        if config_file.default_profile.as_deref() == Some(profile_name) {
+            config_file.default_profile = None;
+        }
+
+        if profile.secret_in_keychain() {
+            let _ = OsKeychain.delete(profile_name);
+        }
+
+        Self::save_config_file(&config_file)?;
+        Ok(true)
+    }
+
     /// List all profiles
     pub fn list_profiles() -> Result<Vec<String>> {
         let config_file = Self::load_config_file()?;
@@ -226,6 +734,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
     use std::env;
     use tempfile::TempDir;
 
@@ -297,6 +806,51 @@ mod tests {
         assert!(!config.is_valid());
     }
 
+    #[test]
+    fn test_normalize_host_adds_https_to_bare_hosts() {
+        assert_eq!(
+            Config::normalize_host("cloud.langfuse.com").unwrap(),
+            "https://cloud.langfuse.com"
+        );
+        assert_eq!(
+            Config::normalize_host("localhost:3000").unwrap(),
+            "https://localhost:3000"
+        );
+    }
+
+    #[test]
+    fn test_normalize_host_strips_trailing_slashes() {
+        assert_eq!(
+            Config::normalize_host("https://langfuse.example.com/").unwrap(),
+            "https://langfuse.example.com"
+        );
+        assert_eq!(
+            Config::normalize_host("https://example.com/langfuse//").unwrap(),
+            "https://example.com/langfuse"
+        );
+    }
+
+    #[test]
+    fn test_normalize_host_preserves_http() {
+        assert_eq!(
+            Config::normalize_host("http://localhost:3000").unwrap(),
+            "http://localhost:3000"
+        );
+    }
+
+    #[test]
+    fn test_normalize_host_rejects_invalid_hosts() {
+        for host in [
+            "ftp://example.com",
+            "https://",
+            "not a host",
+            "https://exa mple.com",
+        ] {
+            let err = Config::normalize_host(host).unwrap_err();
+            assert!(err.to_string().contains("Invalid host"), "{host}");
+        }
+    }
+
     // ========== Key Masking Tests ==========
 
     #[test]
@@ -338,6 +892,132 @@ mod tests {
         assert!(profile.public_key.is_none());
         assert!(profile.secret_key.is_none());
         assert!(profile.host.is_none());
+        assert!(!profile.keychain);
+    }
+
+    #[test]
+    fn test_profile_deserialize_keychain_reference() {
+        let yaml = r#"
+public_key: pk-test
+keychain: true
+"#;
+
+        let profile: Profile = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(profile.public_key, Some("pk-test".to_string()));
+        assert!(profile.secret_key.is_none());
+        assert!(profile.keychain);
+    }
+
+    /// In-memory secret store; `None` simulates an unavailable keychain
+    struct MockStore(Option<RefCell<HashMap<String, String>>>);
+
+    impl MockStore {
+        fn with(profile_name: &str, secret_key: &str) -> Self {
+            let secrets = HashMap::from([(profile_name.to_string(), secret_key.to_string())]);
+            Self(Some(RefCell::new(secrets)))
+        }
+
+        fn unavailable() -> Self {
+            Self(None)
+        }
+
+        fn secrets(&self) -> Result<&RefCell<HashMap<String, String>>> {
+            self.0
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("no keychain service"))
+        }
+    }
+
+    impl SecretStore for MockStore {
+        fn get(&self, profile_name: &str) -> Result<String> {
+            self.secrets()?
+                .borrow()
+                .get(profile_name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("no entry"))
+        }
+
+        fn set(&self, profile_name: &str, secret_key: &str) -> Result<()> {
+            self.secrets()?
+                .borrow_mut()
+                .insert(profile_name.to_string(), secret_key.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, profile_name: &str) -> Result<()> {
+            self.secrets()?.borrow_mut().remove(profile_name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_profile_secret_key_from_keyring_sentinel() {
+        let store = MockStore::with("prod", "sk-from-keyring");
+        let profile: Profile =
+            serde_yaml::from_str("public_key: pk\nsecret_key: keyring\n").unwrap();
+
+        assert!(profile.secret_in_keychain());
+        assert_eq!(
+            Config::profile_secret_key_from(&store, "prod", &profile).unwrap(),
+            Some("sk-from-keyring".to_string())
+        );
+    }
+
+    #[test]
+    fn test_profile_secret_key_from_file_skips_store() {
+        let profile = Profile {
+            secret_key: Some("sk-in-file".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Config::profile_secret_key_from(&MockStore::unavailable(), "dev", &profile).unwrap(),
+            Some("sk-in-file".to_string())
+        );
+    }
+
+    #[test]
+    fn test_profile_secret_key_unavailable_keychain_is_an_error() {
+        let profile = Profile {
+            keychain: true,
+            ..Default::default()
+        };
+
+        let err = Config::profile_secret_key_from(&MockStore::unavailable(), "prod", &profile)
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("profile 'prod' from the OS keychain"));
+        assert!(err.to_string().contains("LANGFUSE_SECRET_KEY"));
+    }
+
+    #[test]
+    fn test_store_secret_uses_keychain_when_requested() {
+        let store = MockStore::with("other", "sk-other");
+
+        assert!(Config::store_secret(&store, "prod", "sk-new", true, false));
+        assert_eq!(store.get("prod").unwrap(), "sk-new");
+    }
+
+    #[test]
+    fn test_store_secret_falls_back_to_file() {
+        assert!(!Config::store_secret(
+            &MockStore::unavailable(),
+            "prod",
+            "sk-new",
+            true,
+            false
+        ));
+    }
+
+    #[test]
+    fn test_store_secret_removes_stale_keychain_entry() {
+        let store = MockStore::with("prod", "sk-old");
+
+        assert!(!Config::store_secret(&store, "prod", "sk-new", false, true));
+        assert!(store.get("prod").is_err());
     }
 
     #[test]
@@ -346,6 +1026,8 @@ mod tests {
             public_key: Some("pk-123".to_string()),
             secret_key: Some("sk-456".to_string()),
             host: Some("https://custom.com".to_string()),
+            keychain: false,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&profile).unwrap();
@@ -353,6 +1035,7 @@ mod tests {
         assert!(yaml.contains("public_key: pk-123"));
         assert!(yaml.contains("secret_key: sk-456"));
         assert!(yaml.contains("host: https://custom.com"));
+        assert!(!yaml.contains("keychain"));
     }
 
     #[test]
@@ -372,6 +1055,56 @@ host: https://test.langfuse.com
 
     // ========== ConfigFile Tests ==========
 
+    #[test]
+    fn test_migrate_old_config_keeps_credentials() {
+        let old = r#"
+profiles:
+  work:
+    public_key: pk-work
+    secret_key: keyring
+    host: langfuse.example.com/
+    team: ml-platform
+  default:
+    public_key: pk-lf-old
+    secret_key: sk-lf-old
+    output: ""
+"#;
+
+        let (migrated, changes) = Config::migrate_config_yaml(old).unwrap();
+
+        assert_eq!(
+            changes,
+            [
+                "profile 'default': removed empty output",
+                "profile 'default': set host to the default https://cloud.langfuse.com",
+                "profile 'work': replaced 'secret_key: keyring' with 'keychain: true'",
+                "profile 'work': normalized host 'langfuse.example.com/' to 'https://langfuse.example.com'",
+            ]
+        );
+
+        let config_file: ConfigFile = serde_yaml::from_str(&migrated).unwrap();
+        let default = &config_file.profiles["default"];
+        assert_eq!(default.public_key.as_deref(), Some("pk-lf-old"));
+        assert_eq!(default.secret_key.as_deref(), Some("sk-lf-old"));
+        assert_eq!(default.output, None);
+        let work = &config_file.profiles["work"];
+        assert_eq!(work.public_key.as_deref(), Some("pk-work"));
+        assert!(work.secret_in_keychain());
+        assert!(migrated.contains("team: ml-platform"));
+
+        let (again, changes) = Config::migrate_config_yaml(&migrated).unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(again, migrated);
+    }
+
+    #[test]
+    fn test_migrate_keeps_unknown_top_level_keys() {
+        let (migrated, _) = Config::migrate_config_yaml("telemetry: false\n").unwrap();
+
+        assert!(migrated.contains("telemetry: false"));
+        assert!(migrated.contains("profiles: {}"));
+    }
+
     #[test]
     fn test_config_file_default() {
         let config_file = ConfigFile::default();
@@ -388,6 +1121,8 @@ host: https://test.langfuse.com
                 public_key: Some("pk-default".to_string()),
                 secret_key: Some("sk-default".to_string()),
                 host: None,
+                keychain: false,
+                ..Default::default()
             },
         );
         config_file.profiles.insert(
@@ -396,6 +1131,8 @@ host: https://test.langfuse.com
                 public_key: Some("pk-prod".to_string()),
                 secret_key: Some("sk-prod".to_string()),
                 host: Some("https://prod.langfuse.com".to_string()),
+                keychain: true,
+                ..Default::default()
             },
         );
 
@@ -405,6 +1142,7 @@ host: https://test.langfuse.com
         assert!(yaml.contains("production:"));
         assert!(yaml.contains("pk-default"));
         assert!(yaml.contains("pk-prod"));
+        assert!(yaml.contains("keychain: true"));
     }
 
     #[test]
@@ -465,6 +1203,121 @@ profiles:
         assert!(config.no_color);
     }
 
+    #[test]
+    fn test_profile_format_used_without_cli_flag() {
+        let profile = Profile {
+            format: Some(OutputFormat::Json),
+            limit: Some(200),
+            output: Some("export.json".to_string()),
+            ..Default::default()
+        };
+
+        let defaults =
+            Config::resolve_output_defaults(None, None, None, |_| None, Some(&profile)).unwrap();
+
+        assert_eq!(
+            defaults,
+            OutputDefaults {
+                format: Some(OutputFormat::Json),
+                limit: Some(200),
+                output: Some("export.json".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_output_defaults_precedence() {
+        let profile = Profile {
+            format: Some(OutputFormat::Json),
+            limit: Some(200),
+            ..Default::default()
+        };
+        let env = |name: &str| match name {
+            "LANGFUSE_FORMAT" => Some("csv".to_string()),
+            "LANGFUSE_LIMIT" => Some("75".to_string()),
+            _ => None,
+        };
+
+        // env beats the profile
+        let defaults =
+            Config::resolve_output_defaults(None, None, None, env, Some(&profile)).unwrap();
+        assert_eq!(defaults.format, Some(OutputFormat::Csv));
+        assert_eq!(defaults.limit, Some(75));
+
+        // CLI beats env
+        let defaults = Config::resolve_output_defaults(
+            Some(OutputFormat::Markdown),
+            Some(10),
+            Some("out.md"),
+            env,
+            Some(&profile),
+        )
+        .unwrap();
+        assert_eq!(defaults.format, Some(OutputFormat::Markdown));
+        assert_eq!(defaults.limit, Some(10));
+        assert_eq!(defaults.output, Some("out.md".to_string()));
+
+        // Nothing set leaves the built-in defaults to the caller
+        let defaults = Config::resolve_output_defaults(None, None, None, |_| None, None).unwrap();
+        assert_eq!(defaults, OutputDefaults::default());
+    }
+
+    #[test]
+    fn test_output_defaults_reject_invalid_env() {
+        let env = |name: &str| (name == "LANGFUSE_LIMIT").then(|| "lots".to_string());
+        let err = Config::resolve_output_defaults(None, None, None, env, None).unwrap_err();
+        assert!(err.to_string().contains("Invalid LANGFUSE_LIMIT"));
+    }
+
+    #[test]
+    fn test_profile_output_defaults_are_optional_in_yaml() {
+        let profile: Profile =
+            serde_yaml::from_str("public_key: pk\nformat: json\nlimit: 200\n").unwrap();
+        assert_eq!(profile.format, Some(OutputFormat::Json));
+        assert_eq!(profile.limit, Some(200));
+        assert_eq!(profile.output, None);
+
+        let yaml = serde_yaml::to_string(&Profile::default()).unwrap();
+        assert!(!yaml.contains("format"));
+    }
+
+    #[test]
+    fn test_resolve_proxy_precedence() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert_eq!(
+            Config::resolve_proxy(
+                Some("http://flag:1"),
+                env(&[("HTTPS_PROXY", "http://https:2")])
+            ),
+            Some("http://flag:1".to_string())
+        );
+        assert_eq!(
+            Config::resolve_proxy(
+                None,
+                env(&[
+                    ("ALL_PROXY", "http://all:3"),
+                    ("https_proxy", "http://https:2")
+                ])
+            ),
+            Some("http://https:2".to_string())
+        );
+        assert_eq!(
+            Config::resolve_proxy(
+                None,
+                env(&[("HTTPS_PROXY", ""), ("all_proxy", "http://all:3")])
+            ),
+            Some("http://all:3".to_string())
+        );
+        assert_eq!(Config::resolve_proxy(None, env(&[])), None);
+    }
+
     #[test]
     fn test_config_load_with_defaults() {
         // Clear environment variables that might interfere
@@ -563,6 +1416,8 @@ profiles:
                 public_key: Some("pk-save-test".to_string()),
                 secret_key: Some("sk-save-test".to_string()),
                 host: Some("https://save-test.com".to_string()),
+                keychain: false,
+                ..Default::default()
             },
         );
 
@@ -585,6 +1440,73 @@ profiles:
 
     // ========== Config Path Tests ==========
 
+    #[test]
+    fn test_config_path_env_override() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+
+        assert_eq!(
+            Config::config_path_from(env(&[
+                ("LF_CONFIG", "/tmp/lf.yml"),
+                ("LANGFUSE_CONFIG", "/tmp/langfuse.yml")
+            ])),
+            Some(PathBuf::from("/tmp/lf.yml"))
+        );
+        assert_eq!(
+            Config::config_path_from(env(&[
+                ("LF_CONFIG", ""),
+                ("LANGFUSE_CONFIG", "/tmp/langfuse.yml")
+            ])),
+            Some(PathBuf::from("/tmp/langfuse.yml"))
+        );
+        assert_ne!(
+            Config::config_path_from(env(&[])),
+            Some(PathBuf::from("/tmp/lf.yml"))
+        );
+    }
+
+    #[test]
+    fn test_lf_config_reads_and_writes_profiles_there() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("lf.yml");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "profiles:\n  existing:\n    public_key: pk-existing\n",
+        )
+        .unwrap();
+        let lf_config = path.to_string_lossy().to_string();
+        let resolved =
+            Config::config_path_from(|name| (name == "LF_CONFIG").then(|| lf_config.clone()))
+                .unwrap();
+        assert_eq!(resolved, path);
+
+        let mut config_file = Config::load_config_file_from(&resolved).unwrap();
+        let existing = config_file.profiles.get("existing").cloned();
+        config_file.profiles.insert(
+            "lf-config-test".to_string(),
+            Profile {
+                public_key: Some("pk-new".to_string()),
+                ..Default::default()
+            },
+        );
+        Config::save_config_file_to(&resolved, &config_file).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+
+        assert_eq!(
+            existing.unwrap().public_key,
+            Some("pk-existing".to_string())
+        );
+        assert!(written.contains("lf-config-test"));
+        assert!(written.contains("pk-new"));
+        assert!(written.contains("pk-existing"));
+    }
+
     #[test]
     fn test_config_path_returns_some() {
         // This should always return Some on systems with home directories
@@ -638,6 +1560,42 @@ profiles:
         assert!(config_file.profiles.is_empty());
     }
 
+    #[test]
+    fn test_config_file_default_profile_round_trip() {
+        let yaml = "default_profile: staging\nprofiles: {}";
+        let config_file: ConfigFile = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config_file.default_profile.as_deref(), Some("staging"));
+
+        let unset = serde_yaml::to_string(&ConfigFile::default()).unwrap();
+        assert!(!unset.contains("default_profile"));
+    }
+
+    #[test]
+    fn test_resolve_profile_name_precedence() {
+        let with_default = ConfigFile {
+            default_profile: Some("staging".to_string()),
+            ..Default::default()
+        };
+        let env = || Some("from-env".to_string());
+
+        assert_eq!(
+            Config::resolve_profile_name(Some("cli"), env(), &with_default),
+            "cli"
+        );
+        assert_eq!(
+            Config::resolve_profile_name(None, env(), &with_default),
+            "from-env"
+        );
+        assert_eq!(
+            Config::resolve_profile_name(None, None, &with_default),
+            "staging"
+        );
+        assert_eq!(
+            Config::resolve_profile_name(None, None, &ConfigFile::default()),
+            DEFAULT_PROFILE
+        );
+    }
+
     #[test]
     fn test_config_file_missing_profiles_key() {
         let yaml = "other_key: value";
@@ -0,0 +1,211 @@
+// ABOUTME: Parses dataset items for bulk import from JSONL or CSV files
+// ABOUTME: Each row keeps its line number so failures can be reported individually
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// File formats accepted by `datasets item-import`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportFormat {
+    #[value(alias = "ndjson")]
+    Jsonl,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "jsonl" | "ndjson" => Some(ImportFormat::Jsonl),
+            "csv" => Some(ImportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A dataset item to create
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItem {
+    pub input: Value,
+    #[serde(default, alias = "expected_output")]
+    pub expected_output: Option<Value>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+/// One row of the import file: the item, or why it couldn't be parsed
+#[derive(Debug)]
+pub struct ImportRow {
+    pub line: usize,
+    pub item: Result<ImportItem, String>,
+}
+
+/// Parse file content into rows in the given format
+pub fn parse_items(content: &str, format: ImportFormat) -> Result<Vec<ImportRow>> {
+    match format {
+        ImportFormat::Jsonl => Ok(parse_jsonl(content)),
+        ImportFormat::Csv => parse_csv(content),
+    }
+}
+
+/// One JSON object per line; blank lines are skipped
+fn parse_jsonl(content: &str) -> Vec<ImportRow> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| ImportRow {
+            line: i + 1,
+            item: serde_json::from_str(line).map_err(|e| e.to_string()),
+        })
+        .collect()
+}
+
+/// A header row naming `input`, `expectedOutput` and `metadata` columns.
+/// Cells holding valid JSON are used as JSON, anything else as a string.
+fn parse_csv(content: &str) -> Result<Vec<ImportRow>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.trim()));
+    let input_col =
+        column(&["input"]).ok_or_else(|| anyhow::anyhow!("CSV file has no 'input' column"))?;
+    let expected_col = column(&["expectedOutput", "expected_output"]);
+    let metadata_col = column(&["metadata"]);
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let (line, item) = match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line() as usize);
+                let cell = |col: Option<usize>| {
+                    col.and_then(|c| record.get(c))
+                        .filter(|v| !v.is_empty())
+                        .map(cell_value)
+                };
+                let item = match cell(Some(input_col)) {
+                    Some(input) => Ok(ImportItem {
+                        input,
+                        expected_output: cell(expected_col),
+                        metadata: cell(metadata_col),
+                    }),
+                    None => Err("missing input".to_string()),
+                };
+                (line, item)
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line() as usize);
+                (line, Err(e.to_string()))
+            }
+        };
+        rows.push(ImportRow { line, item });
+    }
+
+    Ok(rows)
+}
+
+fn cell_value(cell: &str) -> Value {
+    serde_json::from_str(cell).unwrap_or_else(|_| Value::String(cell.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_jsonl_skips_malformed_rows() {
+        let content = r#"{"input": {"q": "2+2"}, "expectedOutput": "4"}
+
+{"input": "broken"
+{"input": "hi", "metadata": {"lang": "en"}}
+{"expectedOutput": "no input"}
+"#;
+
+        let rows = parse_items(content, ImportFormat::Jsonl).unwrap();
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].line, 1);
+        assert_eq!(
+            rows[0].item,
+            Ok(ImportItem {
+                input: json!({"q": "2+2"}),
+                expected_output: Some(json!("4")),
+                metadata: None,
+            })
+        );
+        assert_eq!(rows[1].line, 3);
+        assert!(rows[1].item.is_err());
+        assert_eq!(rows[2].line, 4);
+        assert_eq!(
+            rows[2].item.as_ref().unwrap().metadata,
+            Some(json!({"lang": "en"}))
+        );
+        assert_eq!(rows[3].line, 5);
+        assert!(rows[3].item.as_ref().unwrap_err().contains("input"));
+    }
+
+    #[test]
+    fn test_parse_csv_json_and_plain_cells() {
+        let content = "input,expectedOutput,metadata\n\
+                       \"{\"\"q\"\": \"\"2+2\"\"}\",4,\n\
+                       What is the capital of France?,Paris,\"{\"\"lang\"\": \"\"en\"\"}\"\n";
+
+        let rows = parse_items(content, ImportFormat::Csv).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].item,
+            Ok(ImportItem {
+                input: json!({"q": "2+2"}),
+                expected_output: Some(json!(4)),
+                metadata: None,
+            })
+        );
+        assert_eq!(
+            rows[1].item,
+            Ok(ImportItem {
+                input: json!("What is the capital of France?"),
+                expected_output: Some(json!("Paris")),
+                metadata: Some(json!({"lang": "en"})),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_skips_malformed_rows() {
+        let content = "input,expected_output\nhello,world\ntoo,many,fields\n,no input\nbye,\n";
+
+        let rows = parse_items(content, ImportFormat::Csv).unwrap();
+
+        let lines: Vec<usize> = rows.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5]);
+        assert!(rows[0].item.is_ok());
+        assert!(rows[1].item.is_err());
+        assert_eq!(rows[2].item, Err("missing input".to_string()));
+        assert_eq!(rows[3].item.as_ref().unwrap().expected_output, None);
+    }
+
+    #[test]
+    fn test_parse_csv_requires_input_column() {
+        let err = parse_items("question,answer\na,b\n", ImportFormat::Csv).unwrap_err();
+        assert!(err.to_string().contains("no 'input' column"));
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            ImportFormat::from_path(Path::new("items.jsonl")),
+            Some(ImportFormat::Jsonl)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("items.CSV")),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("items.txt")), None);
+    }
+}
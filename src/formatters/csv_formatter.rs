@@ -1,44 +1,48 @@
 use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::BTreeSet;
+
+use super::FormatOptions;
 
 pub struct CsvFormatter;
 
 impl CsvFormatter {
-    pub fn format<T: Serialize>(data: &T) -> Result<String> {
+    pub fn format<T: Serialize>(data: &T, options: &FormatOptions) -> Result<String> {
+        Self::format_delimited(data, options, b',')
+    }
+
+    /// Format with a custom field delimiter (shared with the TSV formatter)
+    pub(super) fn format_delimited<T: Serialize>(
+        data: &T,
+        options: &FormatOptions,
+        delimiter: u8,
+    ) -> Result<String> {
         let value = serde_json::to_value(data)?;
 
         match &value {
             Value::Array(arr) if arr.is_empty() => Ok("No data to display".to_string()),
             Value::Null => Ok("No data to display".to_string()),
-            Value::Array(arr) => Self::format_array(arr),
-            Value::Object(_) => Self::format_array(&[value]),
+            Value::Array(arr) => Self::format_array(arr, options, delimiter),
+            Value::Object(_) => Self::format_array(&[value], options, delimiter),
             _ => Ok(value.to_string()),
         }
     }
 
-    fn format_array(arr: &[Value]) -> Result<String> {
+    fn format_array(arr: &[Value], options: &FormatOptions, delimiter: u8) -> Result<String> {
         if arr.is_empty() {
             return Ok("No data to display".to_string());
         }
 
-        // Collect all unique keys across all objects
-        let mut headers: BTreeSet<String> = BTreeSet::new();
-        for item in arr {
-            if let Value::Object(obj) = item {
-                for key in obj.keys() {
-                    headers.insert(key.clone());
-                }
-            }
-        }
-
-        let headers_vec: Vec<String> = headers.into_iter().collect();
+        let headers_vec = options.headers(arr);
 
-        let mut wtr = csv::Writer::from_writer(vec![]);
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(vec![]);
 
-        // Write header row
-        wtr.write_record(&headers_vec)?;
+        // Write header row, unless --no-header asked for data rows only
+        if !options.no_header {
+            wtr.write_record(&headers_vec)?;
+        }
 
         // Write data rows
         for item in arr {
@@ -46,7 +50,7 @@ impl CsvFormatter {
                 .iter()
                 .map(|key| {
                     if let Value::Object(obj) = item {
-                        Self::format_value(obj.get(key))
+                        Self::format_value(obj.get(key), options)
                     } else {
                         String::new()
                     }
@@ -60,11 +64,11 @@ impl CsvFormatter {
         Ok(String::from_utf8(data)?)
     }
 
-    fn format_value(value: Option<&Value>) -> String {
+    fn format_value(value: Option<&Value>, options: &FormatOptions) -> String {
         match value {
             None | Some(Value::Null) => String::new(),
             Some(Value::String(s)) => s.clone(),
-            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Number(n)) => options.number_format.format(n),
             Some(Value::Bool(b)) => b.to_string(),
             Some(Value::Array(_)) | Some(Value::Object(_)) => {
                 serde_json::to_string(value.unwrap()).unwrap_or_default()
@@ -83,14 +87,14 @@ mod tests {
     #[test]
     fn test_format_empty_array() {
         let data: Vec<serde_json::Value> = vec![];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
     #[test]
     fn test_format_null() {
         let data: Option<String> = None;
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
@@ -100,7 +104,7 @@ mod tests {
             "id": "123",
             "name": "test"
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should have header row and data row
         let lines: Vec<&str> = result.lines().collect();
@@ -117,7 +121,7 @@ mod tests {
             json!({"id": "1", "status": "active"}),
             json!({"id": "2", "status": "inactive"}),
         ];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         assert_eq!(lines.len(), 3); // header + 2 data rows
@@ -130,7 +134,7 @@ mod tests {
     #[test]
     fn test_format_primitive_value() {
         let data = "simple string";
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("simple string"));
     }
 
@@ -138,54 +142,60 @@ mod tests {
 
     #[test]
     fn test_format_value_none() {
-        let result = CsvFormatter::format_value(None);
+        let result = CsvFormatter::format_value(None, &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_null() {
-        let result = CsvFormatter::format_value(Some(&Value::Null));
+        let result = CsvFormatter::format_value(Some(&Value::Null), &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_string() {
         let value = json!("hello");
-        let result = CsvFormatter::format_value(Some(&value));
+        let result = CsvFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "hello");
     }
 
     #[test]
     fn test_format_value_number() {
         let value = json!(123);
-        let result = CsvFormatter::format_value(Some(&value));
+        let result = CsvFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "123");
 
         let float_value = json!(45.67);
-        let result = CsvFormatter::format_value(Some(&float_value));
+        let result = CsvFormatter::format_value(Some(&float_value), &FormatOptions::default());
         assert_eq!(result, "45.67");
     }
 
     #[test]
     fn test_format_value_boolean() {
         let true_val = json!(true);
-        assert_eq!(CsvFormatter::format_value(Some(&true_val)), "true");
+        assert_eq!(
+            CsvFormatter::format_value(Some(&true_val), &FormatOptions::default()),
+            "true"
+        );
 
         let false_val = json!(false);
-        assert_eq!(CsvFormatter::format_value(Some(&false_val)), "false");
+        assert_eq!(
+            CsvFormatter::format_value(Some(&false_val), &FormatOptions::default()),
+            "false"
+        );
     }
 
     #[test]
     fn test_format_value_array() {
         let value = json!([1, 2, 3]);
-        let result = CsvFormatter::format_value(Some(&value));
+        let result = CsvFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "[1,2,3]");
     }
 
     #[test]
     fn test_format_value_object() {
         let value = json!({"a": 1});
-        let result = CsvFormatter::format_value(Some(&value));
+        let result = CsvFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "{\"a\":1}");
     }
 
@@ -196,7 +206,7 @@ mod tests {
         let data = json!({
             "message": "hello, world"
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Value with comma should be quoted in CSV
         assert!(result.contains("\"hello, world\""));
@@ -207,7 +217,7 @@ mod tests {
         let data = json!({
             "message": "say \"hello\""
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Double quotes should be escaped
         assert!(result.contains("\"\""));
@@ -218,7 +228,7 @@ mod tests {
         let data = json!({
             "message": "line1\nline2"
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Newlines in values should be preserved but quoted
         assert!(result.contains("line1\nline2") || result.contains("\"line1"));
@@ -227,7 +237,7 @@ mod tests {
     #[test]
     fn test_csv_header_order() {
         let data = vec![json!({"zebra": "z", "alpha": "a", "middle": "m"})];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         let headers: Vec<&str> = lines[0].split(',').collect();
@@ -236,13 +246,30 @@ mod tests {
         assert_eq!(headers, vec!["alpha", "middle", "zebra"]);
     }
 
+    #[test]
+    fn test_csv_no_header() {
+        let data = vec![
+            json!({"zebra": "z1", "alpha": "a1"}),
+            json!({"zebra": "z2", "alpha": "a2"}),
+        ];
+        let options = FormatOptions {
+            no_header: true,
+            ..Default::default()
+        };
+
+        let result = CsvFormatter::format(&data, &options).unwrap();
+
+        // Data rows keep the alphabetical column order, with no header line
+        assert_eq!(result, "a1,z1\na2,z2\n");
+    }
+
     #[test]
     fn test_csv_objects_with_different_keys() {
         let data = vec![
             json!({"id": "1", "name": "Alice"}),
             json!({"id": "2", "email": "bob@test.com"}),
         ];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
 
@@ -262,7 +289,7 @@ mod tests {
             "id": "1",
             "name": null
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         // Should have two columns, one with empty value
@@ -270,13 +297,14 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_csv_numeric_values() {
         let data = json!({
             "int": 42,
             "float": 3.14,
             "negative": -100
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("42"));
         assert!(result.contains("3.14"));
@@ -289,7 +317,7 @@ mod tests {
             "greeting": "你好",
             "emoji": "🎉"
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("你好"));
         assert!(result.contains("🎉"));
@@ -303,7 +331,7 @@ mod tests {
             json!({"only_column": "value1"}),
             json!({"only_column": "value2"}),
         ];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         assert_eq!(lines.len(), 3);
@@ -316,7 +344,7 @@ mod tests {
             "id": "",
             "name": ""
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         // Data row should just have a comma between empty values
@@ -326,7 +354,7 @@ mod tests {
     #[test]
     fn test_csv_array_with_non_objects() {
         let data = vec![json!("string1"), json!("string2")];
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
         // Should handle gracefully without error
         assert!(!result.is_empty());
     }
@@ -337,7 +365,7 @@ mod tests {
             "id": "1",
             "metadata": {"key": "value"}
         });
-        let result = CsvFormatter::format(&data).unwrap();
+        let result = CsvFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Nested object should be serialized as JSON string
         assert!(
@@ -0,0 +1,79 @@
+use serde_json::{Map, Value};
+
+/// Flatten nested objects into dotted keys (`usage.input`), row by row.
+/// Arrays are left as values so the formatters render them as JSON.
+pub fn flatten(value: Value) -> Value {
+    match value {
+        Value::Array(arr) => Value::Array(arr.into_iter().map(flatten_row).collect()),
+        other => flatten_row(other),
+    }
+}
+
+fn flatten_row(value: Value) -> Value {
+    match value {
+        Value::Object(obj) => {
+            let mut flat = Map::new();
+            flatten_into(&mut flat, None, obj);
+            Value::Object(flat)
+        }
+        other => other,
+    }
+}
+
+fn flatten_into(flat: &mut Map<String, Value>, prefix: Option<&str>, obj: Map<String, Value>) {
+    for (key, value) in obj {
+        let key = match prefix {
+            Some(prefix) => format!("{prefix}.{key}"),
+            None => key,
+        };
+        match value {
+            // Empty objects stay as a single `{}` cell rather than vanishing
+            Value::Object(inner) if !inner.is_empty() => flatten_into(flat, Some(&key), inner),
+            other => {
+                flat.insert(key, other);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_nested_objects() {
+        let data = json!({
+            "id": "obs-1",
+            "usage": {"input": 10, "output": 20},
+            "metadata": {"env": "prod", "build": {"sha": "abc"}}
+        });
+
+        assert_eq!(
+            flatten(data),
+            json!({
+                "id": "obs-1",
+                "usage.input": 10,
+                "usage.output": 20,
+                "metadata.env": "prod",
+                "metadata.build.sha": "abc"
+            })
+        );
+    }
+
+    #[test]
+    fn test_flatten_keeps_arrays_and_empty_objects() {
+        let data = json!([
+            {"tags": ["a", "b"], "metadata": {}},
+            "not an object"
+        ]);
+
+        assert_eq!(
+            flatten(data),
+            json!([
+                {"tags": ["a", "b"], "metadata": {}},
+                "not an object"
+            ])
+        );
+    }
+}
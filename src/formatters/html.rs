@@ -0,0 +1,125 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::FormatOptions;
+
+/// An HTML `<table>` snippet for embedding in reports, with every cell escaped
+pub struct HtmlFormatter;
+
+impl HtmlFormatter {
+    pub fn format<T: Serialize>(data: &T, options: &FormatOptions) -> Result<String> {
+        let value = serde_json::to_value(data)?;
+
+        match &value {
+            Value::Array(arr) if arr.is_empty() => Ok("No data to display".to_string()),
+            Value::Null => Ok("No data to display".to_string()),
+            Value::Array(arr) => Self::format_array(arr, options),
+            Value::Object(_) => Self::format_array(&[value], options),
+            _ => Ok(Self::escape(&value.to_string())),
+        }
+    }
+
+    fn format_array(arr: &[Value], options: &FormatOptions) -> Result<String> {
+        let headers_vec = options.headers(arr);
+
+        let mut output = String::from("<table>\n  <thead>\n    <tr>");
+        for header in &headers_vec {
+            output.push_str(&format!("<th>{}</th>", Self::escape(header)));
+        }
+        output.push_str("</tr>\n  </thead>\n  <tbody>\n");
+
+        for item in arr {
+            output.push_str("    <tr>");
+            for key in &headers_vec {
+                let value = if let Value::Object(obj) = item {
+                    Self::format_value(obj.get(key), options)
+                } else {
+                    String::new()
+                };
+                output.push_str(&format!("<td>{}</td>", Self::escape(&value)));
+            }
+            output.push_str("</tr>\n");
+        }
+
+        output.push_str("  </tbody>\n</table>\n");
+        Ok(output)
+    }
+
+    fn format_value(value: Option<&Value>, options: &FormatOptions) -> String {
+        match value {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => options.number_format.format(n),
+            Some(Value::Bool(b)) => b.to_string(),
+            Some(Value::Array(_)) | Some(Value::Object(_)) => {
+                serde_json::to_string(value.unwrap()).unwrap_or_default()
+            }
+        }
+    }
+
+    /// Escape the characters HTML treats specially, in text and in attributes
+    fn escape(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        for c in s.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_table_structure() {
+        let data = json!([
+            {"id": "1", "cost": 0.5},
+            {"id": "2", "name": "second"}
+        ]);
+
+        let result = HtmlFormatter::format(&data, &FormatOptions::default()).unwrap();
+
+        assert_eq!(
+            result,
+            "<table>\n  <thead>\n    <tr><th>cost</th><th>id</th><th>name</th></tr>\n  </thead>\n  <tbody>\n    <tr><td>0.5</td><td>1</td><td></td></tr>\n    <tr><td></td><td>2</td><td>second</td></tr>\n  </tbody>\n</table>\n"
+        );
+    }
+
+    #[test]
+    fn test_format_escapes_script_tags() {
+        let data = json!({"input": "<script>alert(\"x\" & 'y')</script>"});
+
+        let result = HtmlFormatter::format(&data, &FormatOptions::default()).unwrap();
+
+        assert!(!result.contains("<script>"));
+        assert!(result.contains(
+            "<td>&lt;script&gt;alert(&quot;x&quot; &amp; &#39;y&#39;)&lt;/script&gt;</td>"
+        ));
+    }
+
+    #[test]
+    fn test_format_escapes_nested_json() {
+        let data = json!({"metadata": {"tag": "<b>"}});
+
+        let result = HtmlFormatter::format(&data, &FormatOptions::default()).unwrap();
+
+        assert!(result.contains("<td>{&quot;tag&quot;:&quot;&lt;b&gt;&quot;}</td>"));
+    }
+
+    #[test]
+    fn test_format_empty_array() {
+        let data: Vec<Value> = vec![];
+        let result = HtmlFormatter::format(&data, &FormatOptions::default()).unwrap();
+        assert_eq!(result, "No data to display");
+    }
+}
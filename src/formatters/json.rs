@@ -4,8 +4,13 @@ use serde::Serialize;
 pub struct JsonFormatter;
 
 impl JsonFormatter {
-    pub fn format<T: Serialize>(data: &T) -> Result<String> {
-        Ok(serde_json::to_string_pretty(data)?)
+    /// Pretty-printed JSON, or a single line when `compact` is set
+    pub fn format<T: Serialize>(data: &T, compact: bool) -> Result<String> {
+        if compact {
+            Ok(serde_json::to_string(data)?)
+        } else {
+            Ok(serde_json::to_string_pretty(data)?)
+        }
     }
 }
 
@@ -20,7 +25,7 @@ mod tests {
             "id": "123",
             "name": "test"
         });
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("\"id\": \"123\""));
         assert!(result.contains("\"name\": \"test\""));
@@ -29,7 +34,7 @@ mod tests {
     #[test]
     fn test_format_array() {
         let data = vec![json!({"id": "1"}), json!({"id": "2"})];
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("["));
         assert!(result.contains("]"));
@@ -40,35 +45,35 @@ mod tests {
     #[test]
     fn test_format_empty_array() {
         let data: Vec<serde_json::Value> = vec![];
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
         assert_eq!(result, "[]");
     }
 
     #[test]
     fn test_format_null() {
         let data: Option<String> = None;
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
         assert_eq!(result, "null");
     }
 
     #[test]
     fn test_format_string() {
         let data = "hello world";
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
         assert_eq!(result, "\"hello world\"");
     }
 
     #[test]
     fn test_format_number() {
         let data = 42;
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
         assert_eq!(result, "42");
     }
 
     #[test]
     fn test_format_boolean() {
-        assert_eq!(JsonFormatter::format(&true).unwrap(), "true");
-        assert_eq!(JsonFormatter::format(&false).unwrap(), "false");
+        assert_eq!(JsonFormatter::format(&true, false).unwrap(), "true");
+        assert_eq!(JsonFormatter::format(&false, false).unwrap(), "false");
     }
 
     #[test]
@@ -80,7 +85,7 @@ mod tests {
                 }
             }
         });
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("outer"));
         assert!(result.contains("inner"));
@@ -90,19 +95,31 @@ mod tests {
     #[test]
     fn test_format_pretty_print() {
         let data = json!({"a": 1, "b": 2});
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         // Pretty-printed JSON should have newlines and indentation
         assert!(result.contains("\n"));
         assert!(result.contains("  ")); // indentation
     }
 
+    #[test]
+    fn test_format_compact() {
+        let data = json!({"a": 1, "b": 2});
+
+        assert_eq!(
+            JsonFormatter::format(&data, true).unwrap(),
+            r#"{"a":1,"b":2}"#
+        );
+        assert!(!JsonFormatter::format(&data, true).unwrap().contains('\n'));
+        assert!(JsonFormatter::format(&data, false).unwrap().contains('\n'));
+    }
+
     #[test]
     fn test_format_special_characters() {
         let data = json!({
             "message": "Hello\nWorld\t\"Quoted\""
         });
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         // Special characters should be properly escaped
         assert!(result.contains("\\n"));
@@ -116,19 +133,20 @@ mod tests {
             "greeting": "你好世界",
             "emoji": "🎉"
         });
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("你好世界"));
         assert!(result.contains("🎉"));
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_format_large_numbers() {
         let data = json!({
             "big_int": 9007199254740993_i64,
             "float": 3.141592653589793
         });
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("9007199254740993"));
         assert!(result.contains("3.141592653589793"));
@@ -148,7 +166,7 @@ mod tests {
             count: 42,
             active: true,
         };
-        let result = JsonFormatter::format(&data).unwrap();
+        let result = JsonFormatter::format(&data, false).unwrap();
 
         assert!(result.contains("\"name\": \"test\""));
         assert!(result.contains("\"count\": 42"));
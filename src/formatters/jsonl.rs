@@ -0,0 +1,91 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+pub struct JsonlFormatter;
+
+impl JsonlFormatter {
+    /// Emit one compact JSON value per line. Arrays produce a line per element;
+    /// anything else produces a single line.
+    pub fn format<T: Serialize>(data: &T) -> Result<String> {
+        Self::format_records(&serde_json::to_value(data)?, |record| record)
+    }
+
+    /// Like `format`, writing each line as `line(record)`
+    pub fn format_records<'a, S: Serialize>(
+        value: &'a Value,
+        line: impl Fn(&'a Value) -> S,
+    ) -> Result<String> {
+        let lines = match value {
+            Value::Array(arr) => arr
+                .iter()
+                .map(|record| serde_json::to_string(&line(record)))
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => vec![serde_json::to_string(&line(value))?],
+        };
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MetricsResult;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_metrics_rows_one_object_per_line() {
+        let result: MetricsResult = serde_json::from_value(json!({
+            "data": [
+                {"time_dimension": "2024-01-01T00:00:00Z", "count_count": 12},
+                {"time_dimension": "2024-01-02T00:00:00Z", "count_count": 7},
+                {"time_dimension": "2024-01-03T00:00:00Z", "count_count": 0}
+            ]
+        }))
+        .unwrap();
+
+        let output = JsonlFormatter::format(&result.data).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            let row: Value = serde_json::from_str(line).unwrap();
+            assert!(row.is_object());
+            assert!(row.get("time_dimension").is_some());
+        }
+    }
+
+    #[test]
+    fn test_format_array_of_two_objects() {
+        let data = json!([{"id": "1", "name": "a"}, {"id": "2", "name": "b"}]);
+        let result = JsonlFormatter::format(&data).unwrap();
+
+        assert_eq!(
+            result,
+            "{\"id\":\"1\",\"name\":\"a\"}\n{\"id\":\"2\",\"name\":\"b\"}"
+        );
+        assert_eq!(result.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_format_empty_array() {
+        let data: Vec<Value> = vec![];
+        let result = JsonlFormatter::format(&data).unwrap();
+
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_format_single_object() {
+        let data = json!({"id": "1", "nested": {"a": [1, 2]}});
+        let result = JsonlFormatter::format(&data).unwrap();
+
+        assert_eq!(result, r#"{"id":"1","nested":{"a":[1,2]}}"#);
+    }
+
+    #[test]
+    fn test_format_scalar() {
+        let result = JsonlFormatter::format(&"hello").unwrap();
+        assert_eq!(result, "\"hello\"");
+    }
+}
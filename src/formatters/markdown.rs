@@ -1,39 +1,30 @@
 use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::BTreeSet;
+
+use super::FormatOptions;
 
 pub struct MarkdownFormatter;
 
 impl MarkdownFormatter {
-    pub fn format<T: Serialize>(data: &T) -> Result<String> {
+    pub fn format<T: Serialize>(data: &T, options: &FormatOptions) -> Result<String> {
         let value = serde_json::to_value(data)?;
 
         match &value {
             Value::Array(arr) if arr.is_empty() => Ok("No data to display".to_string()),
             Value::Null => Ok("No data to display".to_string()),
-            Value::Array(arr) => Self::format_array(arr),
-            Value::Object(_) => Self::format_array(&[value]),
+            Value::Array(arr) => Self::format_array(arr, options),
+            Value::Object(_) => Self::format_array(&[value], options),
             _ => Ok(value.to_string()),
         }
     }
 
-    fn format_array(arr: &[Value]) -> Result<String> {
+    fn format_array(arr: &[Value], options: &FormatOptions) -> Result<String> {
         if arr.is_empty() {
             return Ok("No data to display".to_string());
         }
 
-        // Collect all unique keys across all objects
-        let mut headers: BTreeSet<String> = BTreeSet::new();
-        for item in arr {
-            if let Value::Object(obj) = item {
-                for key in obj.keys() {
-                    headers.insert(key.clone());
-                }
-            }
-        }
-
-        let headers_vec: Vec<String> = headers.into_iter().collect();
+        let headers_vec = options.headers(arr);
 
         let mut output = String::new();
 
@@ -56,7 +47,7 @@ impl MarkdownFormatter {
             output.push('|');
             for key in &headers_vec {
                 let value = if let Value::Object(obj) = item {
-                    Self::format_value(obj.get(key))
+                    Self::format_value(obj.get(key), options)
                 } else {
                     String::new()
                 };
@@ -68,11 +59,11 @@ impl MarkdownFormatter {
         Ok(output)
     }
 
-    fn format_value(value: Option<&Value>) -> String {
+    fn format_value(value: Option<&Value>, options: &FormatOptions) -> String {
         match value {
             None | Some(Value::Null) => String::new(),
             Some(Value::String(s)) => s.clone(),
-            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Number(n)) => options.number_format.format(n),
             Some(Value::Bool(b)) => b.to_string(),
             Some(Value::Array(_)) | Some(Value::Object(_)) => {
                 serde_json::to_string(value.unwrap()).unwrap_or_default()
@@ -95,14 +86,14 @@ mod tests {
     #[test]
     fn test_format_empty_array() {
         let data: Vec<serde_json::Value> = vec![];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
     #[test]
     fn test_format_null() {
         let data: Option<String> = None;
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
@@ -112,7 +103,7 @@ mod tests {
             "id": "123",
             "name": "test"
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should be valid markdown table
         assert!(result.contains("| id |"));
@@ -128,7 +119,7 @@ mod tests {
             json!({"id": "1", "status": "active"}),
             json!({"id": "2", "status": "inactive"}),
         ];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         assert_eq!(lines.len(), 4); // header + separator + 2 data rows
@@ -144,7 +135,7 @@ mod tests {
     #[test]
     fn test_format_primitive_value() {
         let data = "simple string";
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("simple string"));
     }
 
@@ -152,54 +143,60 @@ mod tests {
 
     #[test]
     fn test_format_value_none() {
-        let result = MarkdownFormatter::format_value(None);
+        let result = MarkdownFormatter::format_value(None, &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_null() {
-        let result = MarkdownFormatter::format_value(Some(&Value::Null));
+        let result = MarkdownFormatter::format_value(Some(&Value::Null), &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_string() {
         let value = json!("hello");
-        let result = MarkdownFormatter::format_value(Some(&value));
+        let result = MarkdownFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "hello");
     }
 
     #[test]
     fn test_format_value_number() {
         let value = json!(123);
-        let result = MarkdownFormatter::format_value(Some(&value));
+        let result = MarkdownFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "123");
 
         let float_value = json!(45.67);
-        let result = MarkdownFormatter::format_value(Some(&float_value));
+        let result = MarkdownFormatter::format_value(Some(&float_value), &FormatOptions::default());
         assert_eq!(result, "45.67");
     }
 
     #[test]
     fn test_format_value_boolean() {
         let true_val = json!(true);
-        assert_eq!(MarkdownFormatter::format_value(Some(&true_val)), "true");
+        assert_eq!(
+            MarkdownFormatter::format_value(Some(&true_val), &FormatOptions::default()),
+            "true"
+        );
 
         let false_val = json!(false);
-        assert_eq!(MarkdownFormatter::format_value(Some(&false_val)), "false");
+        assert_eq!(
+            MarkdownFormatter::format_value(Some(&false_val), &FormatOptions::default()),
+            "false"
+        );
     }
 
     #[test]
     fn test_format_value_array() {
         let value = json!([1, 2, 3]);
-        let result = MarkdownFormatter::format_value(Some(&value));
+        let result = MarkdownFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "[1,2,3]");
     }
 
     #[test]
     fn test_format_value_object() {
         let value = json!({"a": 1});
-        let result = MarkdownFormatter::format_value(Some(&value));
+        let result = MarkdownFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "{\"a\":1}");
     }
 
@@ -238,7 +235,7 @@ mod tests {
     #[test]
     fn test_markdown_table_structure() {
         let data = vec![json!({"col1": "a", "col2": "b"})];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
 
@@ -259,7 +256,7 @@ mod tests {
     #[test]
     fn test_markdown_column_count() {
         let data = vec![json!({"a": "1", "b": "2", "c": "3"})];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
 
@@ -277,7 +274,7 @@ mod tests {
             json!({"id": "1", "name": "Alice"}),
             json!({"id": "2", "email": "bob@test.com"}),
         ];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should have all headers
         assert!(result.contains("id"));
@@ -290,7 +287,7 @@ mod tests {
         let data = json!({
             "expression": "a | b"
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Pipe in value should be escaped
         assert!(result.contains("a \\| b"));
@@ -302,7 +299,7 @@ mod tests {
             "id": "1",
             "name": null
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should still have proper table structure
         let lines: Vec<&str> = result.lines().collect();
@@ -315,7 +312,7 @@ mod tests {
             "greeting": "你好",
             "emoji": "🎉"
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("你好"));
         assert!(result.contains("🎉"));
@@ -324,7 +321,7 @@ mod tests {
     #[test]
     fn test_markdown_header_order() {
         let data = vec![json!({"zebra": "z", "alpha": "a", "middle": "m"})];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         let header = lines[0];
@@ -344,7 +341,7 @@ mod tests {
             "id": "1",
             "metadata": {"key": "value"}
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Nested object should be rendered as JSON
         assert!(result.contains("id"));
@@ -354,7 +351,7 @@ mod tests {
     #[test]
     fn test_markdown_single_column() {
         let data = vec![json!({"only": "one"}), json!({"only": "two"})];
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         let lines: Vec<&str> = result.lines().collect();
         assert_eq!(lines.len(), 4); // header + sep + 2 data
@@ -366,7 +363,7 @@ mod tests {
             "code": "`inline`",
             "bold": "**text**"
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Markdown characters in values should be preserved
         assert!(result.contains("`inline`"));
@@ -379,7 +376,7 @@ mod tests {
             "short": "a",
             "long": "This is a very long value that spans many characters"
         });
-        let result = MarkdownFormatter::format(&data).unwrap();
+        let result = MarkdownFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Long values should be preserved (no truncation in markdown)
         assert!(result.contains("This is a very long value"));
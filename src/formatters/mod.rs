@@ -1,25 +1,181 @@
 mod csv_formatter;
+mod flatten;
+mod html;
 mod json;
+mod jsonl;
 mod markdown;
+mod number;
+mod sort;
 mod table;
+mod tsv;
 
 pub use csv_formatter::CsvFormatter;
+pub use html::HtmlFormatter;
 pub use json::JsonFormatter;
+pub use jsonl::JsonlFormatter;
 pub use markdown::MarkdownFormatter;
+pub use number::NumberFormat;
 pub use table::TableFormatter;
+pub use tsv::TsvFormatter;
 
 use anyhow::Result;
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
 
 use crate::types::OutputFormat;
 
+/// Rendering options for the tabular formatters
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    /// How numeric cells are rendered
+    pub number_format: NumberFormat,
+    /// Columns to show, in order (default: every key, sorted)
+    pub fields: Option<Vec<String>>,
+    /// Columns to leave out, applied after `fields`
+    pub exclude_fields: Vec<String>,
+    /// Plain ASCII output with no ANSI escapes (--no-color / NO_COLOR)
+    pub no_color: bool,
+    /// Flatten nested objects into dotted columns (table, csv, markdown)
+    pub flatten: bool,
+    /// Single-line JSON instead of pretty-printed (json only; jsonl is always compact)
+    pub compact: bool,
+    /// Field to sort records by
+    pub sort: Option<String>,
+    /// Reverse the record order (after sorting, if any)
+    pub reverse: bool,
+    /// Leave out the header row (csv and tsv only)
+    pub no_header: bool,
+    /// Wrap table cells at this many characters instead of cutting nested JSON short
+    pub max_col_width: Option<usize>,
+    /// Total width to fit tables in (the terminal's), wrapping the widest columns first
+    pub table_width: Option<usize>,
+}
+
+impl FormatOptions {
+    /// Column headers for a set of rows: the selected fields in the user's
+    /// order, or every key present across the rows in alphabetical order
+    pub fn headers(&self, rows: &[Value]) -> Vec<String> {
+        if let Some(fields) = &self.fields {
+            return fields
+                .iter()
+                .filter(|f| !self.exclude_fields.contains(f))
+                .cloned()
+                .collect();
+        }
+
+        let mut headers: BTreeSet<String> = BTreeSet::new();
+        for row in rows {
+            if let Value::Object(obj) = row {
+                headers.extend(obj.keys().cloned());
+            }
+        }
+        headers.into_iter().collect()
+    }
+
+    /// Records in the requested order (--sort, --reverse)
+    pub fn ordered(&self, value: Value) -> Value {
+        if self.sort.is_none() && !self.reverse {
+            return value;
+        }
+        sort::sort_records(value, self.sort.as_deref(), self.reverse)
+    }
+
+    /// Objects (or arrays of objects) without the excluded fields
+    fn without_excluded(&self, value: Value) -> Value {
+        if self.exclude_fields.is_empty() {
+            return value;
+        }
+
+        let exclude = |v: Value| match v {
+            Value::Object(mut obj) => {
+                obj.retain(|key, _| !self.exclude_fields.contains(key));
+                Value::Object(obj)
+            }
+            other => other,
+        };
+
+        match value {
+            Value::Array(arr) => Value::Array(arr.into_iter().map(exclude).collect()),
+            other => exclude(other),
+        }
+    }
+
+    /// The data as rows for the tabular formatters, flattened if requested
+    fn tabular<T: Serialize>(&self, data: &T) -> Result<Value> {
+        let value = serde_json::to_value(data)?;
+        Ok(if self.flatten {
+            flatten::flatten(value)
+        } else {
+            value
+        })
+    }
+
+    /// Objects (or arrays of objects) restricted to the selected fields,
+    /// serialized with their keys in the order the fields were given
+    fn select_fields<'a>(&'a self, value: &'a Value) -> SelectedFields<'a> {
+        SelectedFields {
+            value,
+            fields: self.fields.as_deref(),
+        }
+    }
+}
+
+/// A value that serializes with only `fields`, in that order. serde_json
+/// maps sort their keys, so the order has to be kept while writing.
+struct SelectedFields<'a> {
+    value: &'a Value,
+    fields: Option<&'a [String]>,
+}
+
+impl Serialize for SelectedFields<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let Some(fields) = self.fields else {
+            return self.value.serialize(serializer);
+        };
+
+        match self.value {
+            Value::Array(items) => {
+                serializer.collect_seq(items.iter().map(|value| SelectedFields {
+                    value,
+                    fields: Some(fields),
+                }))
+            }
+            Value::Object(obj) => {
+                let mut map = serializer.serialize_map(None)?;
+                for field in fields {
+                    if let Some(value) = obj.get(field) {
+                        map.serialize_entry(field, value)?;
+                    }
+                }
+                map.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
+}
+
 /// Format data according to the specified output format
-pub fn format_output<T: Serialize>(data: &T, format: OutputFormat) -> Result<String> {
+pub fn format_output<T: Serialize>(
+    data: &T,
+    format: OutputFormat,
+    options: &FormatOptions,
+) -> Result<String> {
+    let data = &options.without_excluded(options.ordered(serde_json::to_value(data)?));
+
     match format {
-        OutputFormat::Table => TableFormatter::format(data),
-        OutputFormat::Json => JsonFormatter::format(data),
-        OutputFormat::Csv => CsvFormatter::format(data),
-        OutputFormat::Markdown => MarkdownFormatter::format(data),
+        OutputFormat::Table => TableFormatter::format(&options.tabular(data)?, options),
+        OutputFormat::Json => JsonFormatter::format(&options.select_fields(data), options.compact),
+        OutputFormat::Jsonl if options.fields.is_none() => JsonlFormatter::format(data),
+        OutputFormat::Jsonl => {
+            JsonlFormatter::format_records(data, |record| options.select_fields(record))
+        }
+        OutputFormat::Csv => CsvFormatter::format(&options.tabular(data)?, options),
+        OutputFormat::Tsv => TsvFormatter::format(&options.tabular(data)?, options),
+        OutputFormat::Markdown => MarkdownFormatter::format(&options.tabular(data)?, options),
+        OutputFormat::Html => HtmlFormatter::format(&options.tabular(data)?, options),
     }
 }
 
@@ -31,7 +187,7 @@ mod tests {
     #[test]
     fn test_format_output_table() {
         let data = json!({"id": "1", "name": "test"});
-        let result = format_output(&data, OutputFormat::Table).unwrap();
+        let result = format_output(&data, OutputFormat::Table, &FormatOptions::default()).unwrap();
 
         // Table format should have structured output
         assert!(result.contains("id"));
@@ -43,7 +199,7 @@ mod tests {
     #[test]
     fn test_format_output_json() {
         let data = json!({"id": "1", "name": "test"});
-        let result = format_output(&data, OutputFormat::Json).unwrap();
+        let result = format_output(&data, OutputFormat::Json, &FormatOptions::default()).unwrap();
 
         // JSON format should be valid JSON
         assert!(result.contains("\"id\": \"1\""));
@@ -53,7 +209,7 @@ mod tests {
     #[test]
     fn test_format_output_csv() {
         let data = json!({"id": "1", "name": "test"});
-        let result = format_output(&data, OutputFormat::Csv).unwrap();
+        let result = format_output(&data, OutputFormat::Csv, &FormatOptions::default()).unwrap();
 
         // CSV format should have comma-separated values
         assert!(result.contains("id"));
@@ -65,7 +221,8 @@ mod tests {
     #[test]
     fn test_format_output_markdown() {
         let data = json!({"id": "1", "name": "test"});
-        let result = format_output(&data, OutputFormat::Markdown).unwrap();
+        let result =
+            format_output(&data, OutputFormat::Markdown, &FormatOptions::default()).unwrap();
 
         // Markdown format should have table structure
         assert!(result.contains("|"));
@@ -78,10 +235,11 @@ mod tests {
     fn test_format_output_empty_data() {
         let data: Vec<serde_json::Value> = vec![];
 
-        let table = format_output(&data, OutputFormat::Table).unwrap();
-        let csv = format_output(&data, OutputFormat::Csv).unwrap();
-        let markdown = format_output(&data, OutputFormat::Markdown).unwrap();
-        let json = format_output(&data, OutputFormat::Json).unwrap();
+        let table = format_output(&data, OutputFormat::Table, &FormatOptions::default()).unwrap();
+        let csv = format_output(&data, OutputFormat::Csv, &FormatOptions::default()).unwrap();
+        let markdown =
+            format_output(&data, OutputFormat::Markdown, &FormatOptions::default()).unwrap();
+        let json = format_output(&data, OutputFormat::Json, &FormatOptions::default()).unwrap();
 
         assert_eq!(table, "No data to display");
         assert_eq!(csv, "No data to display");
@@ -93,10 +251,11 @@ mod tests {
     fn test_format_output_array() {
         let data = vec![json!({"id": "1"}), json!({"id": "2"})];
 
-        let table = format_output(&data, OutputFormat::Table).unwrap();
-        let csv = format_output(&data, OutputFormat::Csv).unwrap();
-        let markdown = format_output(&data, OutputFormat::Markdown).unwrap();
-        let json = format_output(&data, OutputFormat::Json).unwrap();
+        let table = format_output(&data, OutputFormat::Table, &FormatOptions::default()).unwrap();
+        let csv = format_output(&data, OutputFormat::Csv, &FormatOptions::default()).unwrap();
+        let markdown =
+            format_output(&data, OutputFormat::Markdown, &FormatOptions::default()).unwrap();
+        let json = format_output(&data, OutputFormat::Json, &FormatOptions::default()).unwrap();
 
         // All formats should include both records
         assert!(table.contains("1") && table.contains("2"));
@@ -105,6 +264,181 @@ mod tests {
         assert!(json.contains("1") && json.contains("2"));
     }
 
+    #[test]
+    fn test_format_output_flatten_csv_columns() {
+        let data = json!([
+            {"id": "1", "usage": {"input": 10, "output": 20}},
+            {"id": "2", "usage": {"input": 5}}
+        ]);
+        let options = FormatOptions {
+            flatten: true,
+            ..Default::default()
+        };
+
+        let csv = format_output(&data, OutputFormat::Csv, &options).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(lines.next(), Some("id,usage.input,usage.output"));
+        assert_eq!(lines.next(), Some("1,10,20"));
+        assert_eq!(lines.next(), Some("2,5,"));
+    }
+
+    #[test]
+    fn test_format_output_flatten_leaves_json_untouched() {
+        let data = json!({"usage": {"input": 10}});
+        let options = FormatOptions {
+            flatten: true,
+            ..Default::default()
+        };
+
+        let json = format_output(&data, OutputFormat::Json, &options).unwrap();
+
+        assert!(json.contains("\"usage\": {"));
+        assert!(!json.contains("usage.input"));
+    }
+
+    #[test]
+    fn test_format_output_number_format() {
+        let data = json!({"cost": 1234.5678, "tokens": 1500000});
+        let options = FormatOptions {
+            number_format: "thousands:2".parse().unwrap(),
+            ..Default::default()
+        };
+
+        let table = format_output(&data, OutputFormat::Table, &options).unwrap();
+        let csv = format_output(&data, OutputFormat::Csv, &options).unwrap();
+        let markdown = format_output(&data, OutputFormat::Markdown, &options).unwrap();
+        let json = format_output(&data, OutputFormat::Json, &options).unwrap();
+
+        assert!(table.contains("1,234.57") && table.contains("1,500,000"));
+        assert!(csv.contains("\"1,234.57\"") && csv.contains("\"1,500,000\""));
+        assert!(markdown.contains("1,234.57") && markdown.contains("1,500,000"));
+        // JSON keeps raw numbers
+        assert!(json.contains("1234.5678"));
+    }
+
+    #[test]
+    fn test_format_output_fields_order_and_missing_keys() {
+        let data = json!([
+            {"id": "1", "name": "first", "timestamp": "2024-01-01", "input": "big"},
+            {"id": "2", "timestamp": "2024-01-02"}
+        ]);
+        let options = FormatOptions {
+            fields: Some(vec![
+                "timestamp".to_string(),
+                "id".to_string(),
+                "name".to_string(),
+            ]),
+            ..Default::default()
+        };
+
+        let csv = format_output(&data, OutputFormat::Csv, &options).unwrap();
+        assert_eq!(
+            csv.lines().collect::<Vec<_>>(),
+            ["timestamp,id,name", "2024-01-01,1,first", "2024-01-02,2,"]
+        );
+
+        let markdown = format_output(&data, OutputFormat::Markdown, &options).unwrap();
+        assert!(markdown.starts_with("| timestamp | id | name |"));
+        assert!(markdown.contains("| 2024-01-02 | 2 |  |"));
+
+        let table = format_output(&data, OutputFormat::Table, &options).unwrap();
+        assert!(!table.contains("input"));
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.find("timestamp").unwrap() < header.find("id").unwrap());
+        assert!(header.find("id").unwrap() < header.find("name").unwrap());
+    }
+
+    #[test]
+    fn test_format_output_fields_keep_their_order_in_json() {
+        let data = json!([{"id": "1", "name": "first", "timestamp": "2024-01-01"}]);
+        let options = FormatOptions {
+            fields: Some(vec![
+                "timestamp".to_string(),
+                "name".to_string(),
+                "id".to_string(),
+            ]),
+            compact: true,
+            ..Default::default()
+        };
+
+        let json = format_output(&data, OutputFormat::Json, &options).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"timestamp":"2024-01-01","name":"first","id":"1"}]"#
+        );
+
+        let jsonl = format_output(&data, OutputFormat::Jsonl, &options).unwrap();
+        assert_eq!(
+            jsonl,
+            r#"{"timestamp":"2024-01-01","name":"first","id":"1"}"#
+        );
+    }
+
+    #[test]
+    fn test_format_output_fields_filters_json() {
+        let data = json!([
+            {"id": "1", "name": "first", "input": "big"},
+            {"id": "2", "input": "big"}
+        ]);
+        let options = FormatOptions {
+            fields: Some(vec!["id".to_string(), "name".to_string()]),
+            ..Default::default()
+        };
+
+        let json = format_output(&data, OutputFormat::Json, &options).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, json!([{"id": "1", "name": "first"}, {"id": "2"}]));
+
+        let jsonl = format_output(&data, OutputFormat::Jsonl, &options).unwrap();
+        assert_eq!(jsonl, "{\"id\":\"1\",\"name\":\"first\"}\n{\"id\":\"2\"}");
+    }
+
+    #[test]
+    fn test_format_output_exclude_fields_drops_only_those_columns() {
+        let data = json!([
+            {"id": "1", "name": "first", "input": "big", "output": "bigger", "latency": 1.5},
+            {"id": "2", "input": "big", "metadata": {"env": "prod"}}
+        ]);
+        let options = FormatOptions {
+            exclude_fields: vec!["input".to_string(), "output".to_string()],
+            ..Default::default()
+        };
+
+        let csv = format_output(&data, OutputFormat::Csv, &options).unwrap();
+        assert_eq!(csv.lines().next().unwrap(), "id,latency,metadata,name");
+
+        let json = format_output(&data, OutputFormat::Json, &options).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            json!([
+                {"id": "1", "name": "first", "latency": 1.5},
+                {"id": "2", "metadata": {"env": "prod"}}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_format_output_exclude_applies_after_fields() {
+        let data = json!([{"id": "1", "name": "first", "input": "big"}]);
+        let options = FormatOptions {
+            fields: Some(vec![
+                "id".to_string(),
+                "input".to_string(),
+                "name".to_string(),
+            ]),
+            exclude_fields: vec!["input".to_string()],
+            ..Default::default()
+        };
+
+        let csv = format_output(&data, OutputFormat::Csv, &options).unwrap();
+        assert_eq!(csv.lines().collect::<Vec<_>>(), ["id,name", "1,first"]);
+
+        let jsonl = format_output(&data, OutputFormat::Jsonl, &options).unwrap();
+        assert_eq!(jsonl, "{\"id\":\"1\",\"name\":\"first\"}");
+    }
+
     #[test]
     fn test_format_output_complex_data() {
         let data = json!({
@@ -117,9 +451,9 @@ mod tests {
         });
 
         // All formats should handle complex data without error
-        assert!(format_output(&data, OutputFormat::Table).is_ok());
-        assert!(format_output(&data, OutputFormat::Json).is_ok());
-        assert!(format_output(&data, OutputFormat::Csv).is_ok());
-        assert!(format_output(&data, OutputFormat::Markdown).is_ok());
+        assert!(format_output(&data, OutputFormat::Table, &FormatOptions::default()).is_ok());
+        assert!(format_output(&data, OutputFormat::Json, &FormatOptions::default()).is_ok());
+        assert!(format_output(&data, OutputFormat::Csv, &FormatOptions::default()).is_ok());
+        assert!(format_output(&data, OutputFormat::Markdown, &FormatOptions::default()).is_ok());
     }
 }
@@ -0,0 +1,190 @@
+use anyhow::{bail, Result};
+use serde_json::Number;
+use std::str::FromStr;
+
+/// How numeric cells are rendered in tabular output
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NumberFormat {
+    /// Fixed number of decimal places for non-integer values
+    pub decimals: Option<usize>,
+    /// Group integer digits with thousands separators
+    pub thousands: bool,
+}
+
+impl FromStr for NumberFormat {
+    type Err = anyhow::Error;
+
+    /// Parse `auto`, `fixed:N`, `thousands` or `thousands:N`
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, decimals) = match s.split_once(':') {
+            Some((kind, n)) => match n.parse::<usize>() {
+                Ok(d) => (kind, Some(d)),
+                Err(_) => bail!("Invalid decimal places '{n}' in number format '{s}'"),
+            },
+            None => (s, None),
+        };
+
+        match kind {
+            "auto" if decimals.is_none() => Ok(Self::default()),
+            "fixed" if decimals.is_some() => Ok(Self {
+                decimals,
+                thousands: false,
+            }),
+            "thousands" => Ok(Self {
+                decimals,
+                thousands: true,
+            }),
+            _ => bail!(
+                "Invalid number format '{s}'. Expected auto, fixed:N, thousands or thousands:N"
+            ),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Render a JSON number according to this format
+    pub fn format(&self, n: &Number) -> String {
+        if *self == Self::default() {
+            return n.to_string();
+        }
+
+        let plain = if n.is_f64() {
+            let f = n.as_f64().unwrap_or_default();
+            match self.decimals {
+                Some(d) => format!("{f:.d$}"),
+                None => f.to_string(),
+            }
+        } else {
+            n.to_string()
+        };
+
+        if self.thousands {
+            Self::group_thousands(&plain)
+        } else {
+            plain
+        }
+    }
+
+    fn group_thousands(s: &str) -> String {
+        let (sign, rest) = match s.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", s),
+        };
+        let (int_part, frac_part) = match rest.split_once('.') {
+            Some((i, f)) => (i, Some(f)),
+            None => (rest, None),
+        };
+
+        let mut grouped = String::new();
+        for (i, c) in int_part.chars().enumerate() {
+            if i > 0 && (int_part.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(c);
+        }
+
+        match frac_part {
+            Some(f) => format!("{sign}{grouped}.{f}"),
+            None => format!("{sign}{grouped}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn num(v: serde_json::Value) -> Number {
+        match v {
+            serde_json::Value::Number(n) => n,
+            _ => panic!("not a number"),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_formats() {
+        assert_eq!(
+            "auto".parse::<NumberFormat>().unwrap(),
+            NumberFormat::default()
+        );
+        assert_eq!(
+            "fixed:2".parse::<NumberFormat>().unwrap(),
+            NumberFormat {
+                decimals: Some(2),
+                thousands: false
+            }
+        );
+        assert_eq!(
+            "thousands".parse::<NumberFormat>().unwrap(),
+            NumberFormat {
+                decimals: None,
+                thousands: true
+            }
+        );
+        assert_eq!(
+            "thousands:4".parse::<NumberFormat>().unwrap(),
+            NumberFormat {
+                decimals: Some(4),
+                thousands: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_number_formats() {
+        assert!("fixed".parse::<NumberFormat>().is_err());
+        assert!("fixed:x".parse::<NumberFormat>().is_err());
+        assert!("auto:2".parse::<NumberFormat>().is_err());
+        assert!("scientific".parse::<NumberFormat>().is_err());
+    }
+
+    #[test]
+    fn test_auto_keeps_default_rendering() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(&num(json!(42))), "42");
+        assert_eq!(format.format(&num(json!(45.67))), "45.67");
+    }
+
+    #[test]
+    fn test_fixed_decimals() {
+        let format = NumberFormat {
+            decimals: Some(2),
+            thousands: false,
+        };
+        assert_eq!(format.format(&num(json!(0.000123456))), "0.00");
+        assert_eq!(format.format(&num(json!(12.345678))), "12.35");
+        // Integers are left as integers
+        assert_eq!(format.format(&num(json!(1500))), "1500");
+    }
+
+    #[test]
+    fn test_thousands_separators() {
+        let format = NumberFormat {
+            decimals: None,
+            thousands: true,
+        };
+        assert_eq!(format.format(&num(json!(1234567))), "1,234,567");
+        assert_eq!(format.format(&num(json!(-1234))), "-1,234");
+        assert_eq!(format.format(&num(json!(999))), "999");
+        assert_eq!(format.format(&num(json!(1234.5))), "1,234.5");
+    }
+
+    #[test]
+    fn test_thousands_with_decimals() {
+        let format = NumberFormat {
+            decimals: Some(2),
+            thousands: true,
+        };
+        assert_eq!(format.format(&num(json!(1234567.891))), "1,234,567.89");
+    }
+
+    #[test]
+    fn test_small_float_avoids_scientific_notation() {
+        let format = NumberFormat {
+            decimals: None,
+            thousands: true,
+        };
+        assert_eq!(format.format(&num(json!(0.0000001))), "0.0000001");
+    }
+}
@@ -0,0 +1,131 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Sort an array of records by a field (dotted paths look inside nested
+/// objects), then optionally reverse it. Records missing the field always
+/// come last. Anything other than an array is returned unchanged.
+pub fn sort_records(value: Value, field: Option<&str>, reverse: bool) -> Value {
+    let Value::Array(mut records) = value else {
+        return value;
+    };
+
+    if let Some(field) = field {
+        records.sort_by(|a, b| match (lookup(a, field), lookup(b, field)) {
+            (Some(a), Some(b)) if reverse => compare(b, a),
+            (Some(a), Some(b)) => compare(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    } else if reverse {
+        records.reverse();
+    }
+
+    Value::Array(records)
+}
+
+fn lookup<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(value, |current, key| current.get(key))
+        .filter(|v| !v.is_null())
+}
+
+/// Numbers compare numerically and strings lexically; values of different
+/// kinds order numbers, then strings, then booleans, then anything else.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        _ => rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a.to_string().cmp(&b.to_string())),
+    }
+}
+
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Number(_) => 0,
+        Value::String(_) => 1,
+        Value::Bool(_) => 2,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(value: &Value) -> Vec<&str> {
+        value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_numbers_numerically() {
+        let data = json!([
+            {"id": "a", "latency": 10},
+            {"id": "b", "latency": 9.5},
+            {"id": "c", "latency": 100}
+        ]);
+
+        let sorted = sort_records(data, Some("latency"), false);
+
+        assert_eq!(ids(&sorted), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_sort_strings_lexically_and_reversed() {
+        let data = json!([
+            {"id": "a", "name": "beta"},
+            {"id": "b", "name": "alpha"},
+            {"id": "c", "name": "gamma"}
+        ]);
+
+        assert_eq!(
+            ids(&sort_records(data.clone(), Some("name"), false)),
+            vec!["b", "a", "c"]
+        );
+        assert_eq!(
+            ids(&sort_records(data, Some("name"), true)),
+            vec!["c", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_sort_missing_keys_last_even_when_reversed() {
+        let data = json!([
+            {"id": "a"},
+            {"id": "b", "usage": {"total": 5}},
+            {"id": "c", "usage": {"total": null}},
+            {"id": "d", "usage": {"total": 50}}
+        ]);
+
+        assert_eq!(
+            ids(&sort_records(data.clone(), Some("usage.total"), false)),
+            vec!["b", "d", "a", "c"]
+        );
+        assert_eq!(
+            ids(&sort_records(data, Some("usage.total"), true)),
+            vec!["d", "b", "a", "c"]
+        );
+    }
+
+    #[test]
+    fn test_reverse_without_sort_and_single_object() {
+        let data = json!([{"id": "a"}, {"id": "b"}]);
+        assert_eq!(ids(&sort_records(data, None, true)), vec!["b", "a"]);
+
+        let single = json!({"id": "a", "name": "x"});
+        assert_eq!(sort_records(single.clone(), Some("name"), true), single);
+    }
+}
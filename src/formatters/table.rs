@@ -1,46 +1,40 @@
 use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
-use std::collections::BTreeSet;
-use tabled::{builder::Builder, settings::Style};
+
+use super::FormatOptions;
+use tabled::builder::Builder;
+use tabled::settings::object::Columns;
+use tabled::settings::peaker::PriorityMax;
+use tabled::settings::{Style, Width};
 
 pub struct TableFormatter;
 
 impl TableFormatter {
-    pub fn format<T: Serialize>(data: &T) -> Result<String> {
+    pub fn format<T: Serialize>(data: &T, options: &FormatOptions) -> Result<String> {
         let value = serde_json::to_value(data)?;
 
         match &value {
             Value::Array(arr) if arr.is_empty() => Ok("No data to display".to_string()),
             Value::Null => Ok("No data to display".to_string()),
-            Value::Array(arr) => Self::format_array(arr),
-            Value::Object(_) => Self::format_array(&[value]),
+            Value::Array(arr) => Self::format_array(arr, options),
+            Value::Object(_) => Self::format_array(&[value], options),
             _ => Ok(value.to_string()),
         }
     }
 
-    fn format_array(arr: &[Value]) -> Result<String> {
+    fn format_array(arr: &[Value], options: &FormatOptions) -> Result<String> {
         if arr.is_empty() {
             return Ok("No data to display".to_string());
         }
 
-        // Collect all unique keys across all objects
-        let mut headers: BTreeSet<String> = BTreeSet::new();
-        for item in arr {
-            if let Value::Object(obj) = item {
-                for key in obj.keys() {
-                    headers.insert(key.clone());
-                }
-            }
-        }
+        let headers_vec = options.headers(arr);
 
         // No object keys found - can't display as table
-        if headers.is_empty() {
+        if headers_vec.is_empty() {
             return Ok("No data to display".to_string());
         }
 
-        let headers_vec: Vec<String> = headers.into_iter().collect();
-
         let mut builder = Builder::default();
 
         // Add header row
@@ -52,7 +46,7 @@ impl TableFormatter {
                 .iter()
                 .map(|key| {
                     if let Value::Object(obj) = item {
-                        Self::format_value(obj.get(key))
+                        Self::format_value(obj.get(key), options)
                     } else {
                         String::new()
                     }
@@ -62,30 +56,46 @@ impl TableFormatter {
         }
 
         let mut table = builder.build();
-        table.with(Style::rounded());
+        if options.no_color {
+            table.with(Style::ascii());
+        } else {
+            table.with(Style::rounded());
+        }
+        if let Some(width) = options.max_col_width {
+            table.modify(Columns::new(..), Width::wrap(width).keep_words(true));
+        }
+        if let Some(width) = options.table_width {
+            table.with(Width::wrap(width).priority(PriorityMax).keep_words(true));
+        }
 
         Ok(table.to_string())
     }
 
-    fn format_value(value: Option<&Value>) -> String {
+    fn format_value(value: Option<&Value>, options: &FormatOptions) -> String {
         match value {
             None | Some(Value::Null) => String::new(),
             Some(Value::String(s)) => s.clone(),
-            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::Number(n)) => options.number_format.format(n),
             Some(Value::Bool(b)) => b.to_string(),
             Some(Value::Array(arr)) => {
-                // Truncate long arrays
-                let s = serde_json::to_string(arr).unwrap_or_default();
-                Self::truncate_string(&s, 50)
+                Self::nested_json(serde_json::to_string(arr).unwrap_or_default(), options)
             }
             Some(Value::Object(obj)) => {
-                // Truncate long objects
-                let s = serde_json::to_string(obj).unwrap_or_default();
-                Self::truncate_string(&s, 50)
+                Self::nested_json(serde_json::to_string(obj).unwrap_or_default(), options)
             }
         }
     }
 
+    /// Long nested JSON is cut to 50 characters, unless --max-col-width is
+    /// set, in which case the whole value is kept and wrapped to that width
+    fn nested_json(s: String, options: &FormatOptions) -> String {
+        if options.max_col_width.is_some() {
+            s
+        } else {
+            Self::truncate_string(&s, 50)
+        }
+    }
+
     fn truncate_string(s: &str, max_len: usize) -> String {
         if s.len() <= max_len {
             s.to_string()
@@ -102,17 +112,35 @@ mod tests {
 
     // ========== Basic Formatting Tests ==========
 
+    #[test]
+    fn test_format_no_color_is_plain_ascii() {
+        let data = json!([
+            {"id": "1", "name": "first"},
+            {"id": "2", "name": "second"}
+        ]);
+        let options = FormatOptions {
+            no_color: true,
+            ..Default::default()
+        };
+
+        let result = TableFormatter::format(&data, &options).unwrap();
+
+        assert!(!result.contains('\x1b'));
+        assert!(result.is_ascii());
+        assert!(result.contains("+----"));
+    }
+
     #[test]
     fn test_format_empty_array() {
         let data: Vec<serde_json::Value> = vec![];
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
     #[test]
     fn test_format_null() {
         let data: Option<String> = None;
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert_eq!(result, "No data to display");
     }
 
@@ -122,7 +150,7 @@ mod tests {
             "id": "123",
             "name": "test"
         });
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should contain table formatting and data
         assert!(result.contains("id"));
@@ -137,7 +165,7 @@ mod tests {
             json!({"id": "1", "status": "active"}),
             json!({"id": "2", "status": "inactive"}),
         ];
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("id"));
         assert!(result.contains("status"));
@@ -150,21 +178,21 @@ mod tests {
     #[test]
     fn test_format_primitive_value() {
         let data = "simple string";
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("simple string"));
     }
 
     #[test]
     fn test_format_number() {
         let data = 42;
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("42"));
     }
 
     #[test]
     fn test_format_boolean() {
         let data = true;
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("true"));
     }
 
@@ -172,47 +200,53 @@ mod tests {
 
     #[test]
     fn test_format_value_none() {
-        let result = TableFormatter::format_value(None);
+        let result = TableFormatter::format_value(None, &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_null() {
-        let result = TableFormatter::format_value(Some(&Value::Null));
+        let result = TableFormatter::format_value(Some(&Value::Null), &FormatOptions::default());
         assert_eq!(result, "");
     }
 
     #[test]
     fn test_format_value_string() {
         let value = json!("hello");
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "hello");
     }
 
     #[test]
     fn test_format_value_number() {
         let value = json!(123);
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "123");
 
         let float_value = json!(45.67);
-        let result = TableFormatter::format_value(Some(&float_value));
+        let result = TableFormatter::format_value(Some(&float_value), &FormatOptions::default());
         assert_eq!(result, "45.67");
     }
 
     #[test]
     fn test_format_value_boolean() {
         let true_val = json!(true);
-        assert_eq!(TableFormatter::format_value(Some(&true_val)), "true");
+        assert_eq!(
+            TableFormatter::format_value(Some(&true_val), &FormatOptions::default()),
+            "true"
+        );
 
         let false_val = json!(false);
-        assert_eq!(TableFormatter::format_value(Some(&false_val)), "false");
+        assert_eq!(
+            TableFormatter::format_value(Some(&false_val), &FormatOptions::default()),
+            "false"
+        );
     }
 
     #[test]
     fn test_format_value_array_short() {
         let value = json!([1, 2, 3]);
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "[1,2,3]");
     }
 
@@ -230,7 +264,7 @@ mod tests {
             "be",
             "truncated"
         ]);
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert!(result.ends_with("..."));
         assert!(result.len() <= 53); // 50 + "..."
     }
@@ -238,7 +272,7 @@ mod tests {
     #[test]
     fn test_format_value_object_short() {
         let value = json!({"a": 1});
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert_eq!(result, "{\"a\":1}");
     }
 
@@ -247,13 +281,48 @@ mod tests {
         let value = json!({
             "long_key_name": "this is a very long value that should be truncated"
         });
-        let result = TableFormatter::format_value(Some(&value));
+        let result = TableFormatter::format_value(Some(&value), &FormatOptions::default());
         assert!(result.ends_with("..."));
         assert!(result.len() <= 53); // 50 + "..."
     }
 
     // ========== Truncation Tests ==========
 
+    #[test]
+    fn test_max_col_width_wraps_long_values() {
+        let data =
+            json!({"input": "word ".repeat(20).trim_end(), "metadata": {"k": "x".repeat(60)}});
+        let options = FormatOptions {
+            max_col_width: Some(20),
+            no_color: true,
+            ..Default::default()
+        };
+
+        let result = TableFormatter::format(&data, &options).unwrap();
+
+        // Two columns of at most 20 characters, plus borders and padding
+        assert!(result
+            .lines()
+            .all(|line| line.chars().count() <= 2 * 20 + 7));
+        assert!(result.lines().count() > 5);
+        assert!(!result.contains("..."));
+        assert_eq!(result.matches('x').count(), 60);
+    }
+
+    #[test]
+    fn test_table_width_fits_the_terminal() {
+        let data = json!({"id": "t-1", "output": "lorem ipsum ".repeat(20)});
+        let options = FormatOptions {
+            table_width: Some(60),
+            ..Default::default()
+        };
+
+        let result = TableFormatter::format(&data, &options).unwrap();
+
+        assert!(result.lines().all(|line| line.chars().count() <= 60));
+        assert!(result.contains("t-1"));
+    }
+
     #[test]
     fn test_truncate_string_short() {
         let result = TableFormatter::truncate_string("short", 50);
@@ -283,7 +352,7 @@ mod tests {
             json!({"id": "1", "name": "Alice"}),
             json!({"id": "2", "email": "bob@test.com"}),
         ];
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         // Should contain all keys from both objects
         assert!(result.contains("id"));
@@ -297,7 +366,7 @@ mod tests {
             "id": "1",
             "metadata": {"key": "value"}
         });
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("id"));
         assert!(result.contains("metadata"));
@@ -310,7 +379,7 @@ mod tests {
             "id": "",
             "name": ""
         });
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
 
         assert!(result.contains("id"));
         assert!(result.contains("name"));
@@ -319,7 +388,7 @@ mod tests {
     #[test]
     fn test_format_array_with_non_objects() {
         let data = vec![json!("string1"), json!("string2")];
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         // Non-objects can't be displayed as a table
         assert_eq!(result, "No data to display");
     }
@@ -329,7 +398,7 @@ mod tests {
         let data = json!({
             "message": "Hello\nWorld\tTab"
         });
-        let result = TableFormatter::format(&data).unwrap();
+        let result = TableFormatter::format(&data, &FormatOptions::default()).unwrap();
         assert!(result.contains("message"));
     }
 }
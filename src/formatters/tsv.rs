@@ -0,0 +1,67 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{CsvFormatter, FormatOptions};
+
+/// Tab-separated output, with the same columns and quoting rules as CSV
+pub struct TsvFormatter;
+
+impl TsvFormatter {
+    pub fn format<T: Serialize>(data: &T, options: &FormatOptions) -> Result<String> {
+        CsvFormatter::format_delimited(data, options, b'\t')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_format_header_is_tab_delimited() {
+        let data = json!([
+            {"id": "1", "name": "first", "cost": 0.5},
+            {"id": "2", "name": "second", "cost": 1.25}
+        ]);
+
+        let result = TsvFormatter::format(&data, &FormatOptions::default()).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+
+        assert_eq!(lines[0], "cost\tid\tname");
+        assert_eq!(lines[1], "0.5\t1\tfirst");
+        assert_eq!(lines[2], "1.25\t2\tsecond");
+    }
+
+    #[test]
+    fn test_format_quotes_tabs_and_newlines() {
+        let data = json!({"note": "has\ttab", "text": "two\nlines"});
+
+        let result = TsvFormatter::format(&data, &FormatOptions::default()).unwrap();
+
+        assert_eq!(result, "note\ttext\n\"has\ttab\"\t\"two\nlines\"\n");
+    }
+
+    #[test]
+    fn test_format_no_header_keeps_data_rows() {
+        let data = json!([
+            {"id": "1", "name": "first"},
+            {"id": "2", "name": "second"}
+        ]);
+        let options = FormatOptions {
+            fields: Some(vec!["name".to_string(), "id".to_string()]),
+            no_header: true,
+            ..Default::default()
+        };
+
+        let result = TsvFormatter::format(&data, &options).unwrap();
+
+        assert_eq!(result, "first\t1\nsecond\t2\n");
+    }
+
+    #[test]
+    fn test_format_empty_array() {
+        let data: Vec<serde_json::Value> = vec![];
+        let result = TsvFormatter::format(&data, &FormatOptions::default()).unwrap();
+        assert_eq!(result, "No data to display");
+    }
+}
@@ -0,0 +1,106 @@
+// ABOUTME: Client-side frequency counts over fetched records (--count-by)
+// ABOUTME: Groups records by a field's value and sorts the counts descending
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Label used for records where the field is missing or null
+pub const NO_VALUE: &str = "(none)";
+
+/// One row of a frequency histogram
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Count records by the value of `field`, most frequent first.
+/// Dotted fields (e.g. `metadata.env`) look inside nested objects.
+pub fn count_by<T: Serialize>(records: &[T], field: &str) -> Result<Vec<ValueCount>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for record in records {
+        let value = serde_json::to_value(record)?;
+        let key = match lookup(&value, field) {
+            None | Some(Value::Null) => NO_VALUE.to_string(),
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        };
+        *counts.entry(key).or_default() += 1;
+    }
+
+    let mut rows: Vec<ValueCount> = counts
+        .into_iter()
+        .map(|(value, count)| ValueCount { value, count })
+        .collect();
+    rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+    Ok(rows)
+}
+
+fn lookup<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(value, |current, key| current.get(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(value: &str, count: usize) -> ValueCount {
+        ValueCount {
+            value: value.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn test_count_by_sorts_by_count_descending() {
+        let records = vec![
+            json!({"model": "gpt-4"}),
+            json!({"model": "claude"}),
+            json!({"model": "gpt-4"}),
+            json!({"model": "gpt-4"}),
+            json!({"model": "claude"}),
+            json!({"model": "mistral"}),
+        ];
+
+        let rows = count_by(&records, "model").unwrap();
+
+        assert_eq!(
+            rows,
+            vec![row("gpt-4", 3), row("claude", 2), row("mistral", 1)]
+        );
+    }
+
+    #[test]
+    fn test_count_by_missing_and_null_values() {
+        let records = vec![json!({"model": null}), json!({}), json!({"model": "gpt-4"})];
+
+        let rows = count_by(&records, "model").unwrap();
+
+        assert_eq!(rows, vec![row(NO_VALUE, 2), row("gpt-4", 1)]);
+    }
+
+    #[test]
+    fn test_count_by_nested_and_non_string_fields() {
+        let records = vec![
+            json!({"metadata": {"env": "prod"}, "value": 1}),
+            json!({"metadata": {"env": "prod"}, "value": 0.5}),
+            json!({"metadata": {"env": "dev"}, "value": 1}),
+        ];
+
+        assert_eq!(
+            count_by(&records, "metadata.env").unwrap(),
+            vec![row("prod", 2), row("dev", 1)]
+        );
+        assert_eq!(
+            count_by(&records, "value").unwrap(),
+            vec![row("1", 2), row("0.5", 1)]
+        );
+    }
+}
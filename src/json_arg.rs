@@ -0,0 +1,118 @@
+// ABOUTME: Reads JSON flag values (--input, --expected-output, --metadata) inline, from a file or stdin
+// ABOUTME: `@path` reads a file and `-` reads stdin; the result is always validated as JSON
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::{self, Read};
+
+/// Parse the JSON given to `flag`: inline JSON, `@path` to read a file, or
+/// `-` to read stdin
+pub fn parse_json_arg(flag: &str, value: &str) -> Result<Value> {
+    parse_json_arg_from(flag, value, &mut io::stdin())
+}
+
+fn parse_json_arg_from(flag: &str, value: &str, stdin: &mut impl Read) -> Result<Value> {
+    let text = if value == "-" {
+        let mut buf = String::new();
+        stdin
+            .read_to_string(&mut buf)
+            .with_context(|| format!("Failed to read {flag} from stdin"))?;
+        buf
+    } else if let Some(path) = value.strip_prefix('@') {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {flag} from {path}"))?
+    } else {
+        value.to_string()
+    };
+
+    serde_json::from_str(&text).with_context(|| format!("{flag} is not valid JSON"))
+}
+
+/// Stdin can only be read once, so at most one of `flags` may be `-`
+pub fn check_single_stdin(flags: &[(&str, Option<&str>)]) -> Result<()> {
+    let from_stdin: Vec<&str> = flags
+        .iter()
+        .filter(|(_, value)| *value == Some("-"))
+        .map(|(flag, _)| *flag)
+        .collect();
+
+    if from_stdin.len() > 1 {
+        anyhow::bail!(
+            "Only one option can read from stdin ('-'), but {} all do",
+            from_stdin.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_inline_json() {
+        let value = parse_json_arg_from("--input", r#"{"q": 1}"#, &mut io::empty()).unwrap();
+        assert_eq!(value, json!({"q": 1}));
+    }
+
+    #[test]
+    fn test_at_file_reads_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("input.json");
+        std::fs::write(
+            &path,
+            r#"{"question": "What's 2+2?", "context": ["a", "b"]}"#,
+        )
+        .unwrap();
+
+        let value =
+            parse_json_arg_from("--input", &format!("@{}", path.display()), &mut io::empty())
+                .unwrap();
+
+        assert_eq!(
+            value,
+            json!({"question": "What's 2+2?", "context": ["a", "b"]})
+        );
+    }
+
+    #[test]
+    fn test_dash_reads_stdin() {
+        let mut stdin = "[1, 2, {\"answer\": \"4\"}]\n".as_bytes();
+
+        let value = parse_json_arg_from("--expected-output", "-", &mut stdin).unwrap();
+
+        assert_eq!(value, json!([1, 2, {"answer": "4"}]));
+    }
+
+    #[test]
+    fn test_invalid_json_names_the_flag() {
+        let mut stdin = "not json".as_bytes();
+
+        let err = parse_json_arg_from("--metadata", "-", &mut stdin).unwrap_err();
+
+        assert_eq!(err.to_string(), "--metadata is not valid JSON");
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let err =
+            parse_json_arg_from("--input", "@/no/such/file.json", &mut io::empty()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Failed to read --input from /no/such/file.json"));
+    }
+
+    #[test]
+    fn test_only_one_flag_may_read_stdin() {
+        assert!(check_single_stdin(&[("--input", Some("-")), ("--metadata", Some("{}"))]).is_ok());
+
+        let err = check_single_stdin(&[
+            ("--input", Some("-")),
+            ("--expected-output", None),
+            ("--metadata", Some("-")),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("--input, --metadata"));
+    }
+}
@@ -1,12 +1,26 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::io::{self, Write};
 
 mod client;
 mod commands;
 mod config;
+mod dataset_import;
 mod formatters;
+mod histogram;
+mod json_arg;
+mod prompt_diff;
+mod prompt_files;
+mod record_filter;
+mod redact;
+mod score_import;
+mod time_arg;
+mod timezone;
+mod tree;
 mod types;
 
+use client::ApiError;
 use commands::config::ConfigCommands;
 use commands::datasets::DatasetsCommands;
 use commands::metrics::MetricsCommands;
@@ -15,6 +29,9 @@ use commands::prompts::PromptsCommands;
 use commands::scores::ScoresCommands;
 use commands::sessions::SessionsCommands;
 use commands::traces::TracesCommands;
+use commands::whoami::WhoamiCommand;
+use commands::GlobalOptions;
+use types::ErrorFormat;
 
 /// Langfuse CLI - Command-line interface for Langfuse observability platform
 #[derive(Parser)]
@@ -26,6 +43,9 @@ use commands::traces::TracesCommands;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    global: GlobalOptions,
 }
 
 #[derive(Subcommand)]
@@ -61,6 +81,22 @@ enum Commands {
     /// Manage datasets for evaluation
     #[command(subcommand)]
     Datasets(DatasetsCommands),
+
+    /// Show which profile, host and project the current keys belong to
+    Whoami(WhoamiCommand),
+
+    /// Generate a shell completion script
+    #[command(hide = true)]
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Write the completion script for `shell` to `out`
+fn write_completions(shell: Shell, out: &mut dyn Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "lf", out);
 }
 
 #[tokio::main]
@@ -68,16 +104,341 @@ async fn main() -> Result<()> {
     // Load .env file if present
     let _ = dotenvy::dotenv();
 
+    // Request logging is off unless RUST_LOG is set (e.g. RUST_LOG=lf=debug)
+    if let Ok(filter) = tracing_subscriber::EnvFilter::try_from_default_env() {
+        tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_writer(io::stderr)
+            .init();
+    }
+
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Config(cmd) => cmd.execute().await,
-        Commands::Traces(cmd) => cmd.execute().await,
-        Commands::Sessions(cmd) => cmd.execute().await,
-        Commands::Observations(cmd) => cmd.execute().await,
-        Commands::Scores(cmd) => cmd.execute().await,
-        Commands::Metrics(cmd) => cmd.execute().await,
-        Commands::Prompts(cmd) => cmd.execute().await,
-        Commands::Datasets(cmd) => cmd.execute().await,
+    let result = match cli.command {
+        Commands::Config(cmd) => cmd.execute(&cli.global).await,
+        Commands::Traces(cmd) => cmd.execute(&cli.global).await,
+        Commands::Sessions(cmd) => cmd.execute(&cli.global).await,
+        Commands::Observations(cmd) => cmd.execute(&cli.global).await,
+        Commands::Scores(cmd) => cmd.execute(&cli.global).await,
+        Commands::Metrics(cmd) => cmd.execute(&cli.global).await,
+        Commands::Prompts(cmd) => cmd.execute(&cli.global).await,
+        Commands::Datasets(cmd) => cmd.execute(&cli.global).await,
+        Commands::Whoami(cmd) => cmd.execute(&cli.global).await,
+        Commands::Completions { shell } => {
+            write_completions(shell, &mut io::stdout());
+            Ok(())
+        }
+    };
+
+    // A dry run stops at the first request it would have sent
+    match result {
+        Err(e) if client::is_dry_run(&e) => Ok(()),
+        Err(e) if cli.global.error_format == ErrorFormat::Json => {
+            eprintln!("{}", error_report(&e));
+            std::process::exit(1);
+        }
+        other => other,
+    }
+}
+
+/// A failure as JSON for --error-format json: the `ApiError` kind (or
+/// "error" for anything else), the full message and any HTTP status
+fn error_report(e: &anyhow::Error) -> serde_json::Value {
+    let api_error = e.chain().find_map(|cause| cause.downcast_ref::<ApiError>());
+
+    let mut report = serde_json::json!({
+        "error": api_error.map_or("error", ApiError::kind),
+        "message": format!("{e:#}"),
+    });
+    if let Some(status) = api_error.and_then(ApiError::status) {
+        report["status"] = status.into();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SortOrder;
+
+    #[test]
+    fn test_error_report_for_authentication_failure() {
+        let err = anyhow::Error::from(ApiError::AuthenticationError(401))
+            .context("Failed to list traces");
+
+        let report = error_report(&err);
+
+        assert_eq!(report["error"], "authentication");
+        assert_eq!(report["status"], 401);
+        assert_eq!(
+            report["message"],
+            "Failed to list traces: Authentication failed. Check your public and secret keys."
+        );
+    }
+
+    #[test]
+    fn test_error_report_for_missing_credentials() {
+        let err = commands::require_credentials(&config::Config::default()).unwrap_err();
+
+        let report = error_report(&err);
+
+        assert_eq!(report["error"], "missing_credentials");
+        assert!(report.get("status").is_none());
+        assert_eq!(
+            report["message"],
+            "Missing credentials. Run 'lf config setup' or set environment variables."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_report_for_missing_profile() {
+        let profile = format!("no-such-profile-{}", uuid::Uuid::new_v4());
+        let cli = Cli::try_parse_from([
+            "lf",
+            "config",
+            "show",
+            "--profile",
+            &profile,
+            "--error-format",
+            "json",
+        ])
+        .unwrap();
+        let Commands::Config(cmd) = cli.command else {
+            panic!("expected config show");
+        };
+
+        let err = cmd.execute(&cli.global).await.unwrap_err();
+
+        assert_eq!(
+            error_report(&err),
+            serde_json::json!({
+                "error": "error",
+                "message": format!("Profile '{profile}' not found")
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_report_for_other_errors() {
+        let report = error_report(&anyhow::anyhow!("Fields file f.txt lists no fields"));
+
+        assert_eq!(report["error"], "error");
+        assert!(report.get("status").is_none());
+    }
+
+    #[test]
+    fn test_metrics_filter_is_separate_from_global_where() {
+        let cli = Cli::try_parse_from([
+            "lf",
+            "metrics",
+            "query",
+            "--view",
+            "traces",
+            "--measure",
+            "count",
+            "--aggregation",
+            "count",
+            "--filter",
+            "name:=:chat",
+            "--where",
+            "name=chat",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.global.filters.len(), 1);
+        let Commands::Metrics(MetricsCommands::Query { filters, .. }) = cli.command else {
+            panic!("expected metrics query");
+        };
+        assert_eq!(filters.len(), 1);
+    }
+
+    #[test]
+    fn test_traces_export_needs_one_target_and_no_sort() {
+        let parse =
+            |args: &[&str]| Cli::try_parse_from([&["lf", "traces", "export"], args].concat());
+
+        assert!(parse(&["--output", "t.jsonl"]).is_ok());
+        let cli = parse(&["--split-size", "100", "--output-dir", "parts"]).unwrap();
+        let Commands::Traces(TracesCommands::Export { output, .. }) = cli.command else {
+            panic!("expected traces export");
+        };
+        assert_eq!(output, None);
+
+        assert!(parse(&[]).is_err());
+        assert!(parse(&[
+            "--output",
+            "t.jsonl",
+            "--split-size",
+            "100",
+            "--output-dir",
+            "parts"
+        ])
+        .is_err());
+        assert!(parse(&["--output", "t.jsonl", "--sort", "timestamp"]).is_err());
+        assert!(parse(&["--output", "t.jsonl", "--reverse"]).is_err());
+    }
+
+    #[test]
+    fn test_observation_type_is_validated_by_the_cli() {
+        let err = Cli::try_parse_from(["lf", "observations", "list", "--type", "trace"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+
+        let cli =
+            Cli::try_parse_from(["lf", "observations", "list", "--type", "GENERATION"]).unwrap();
+        let Commands::Observations(ObservationsCommands::List { r#type, .. }) = cli.command else {
+            panic!("expected observations list");
+        };
+        assert_eq!(r#type.unwrap().to_api_string(), "GENERATION");
+    }
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_relative_from_is_resolved_at_parse_time() {
+        let cli =
+            Cli::try_parse_from(["lf", "traces", "list", "--from", "-7d", "--to", "now"]).unwrap();
+
+        let Commands::Traces(TracesCommands::List { from, to, .. }) = cli.command else {
+            panic!("expected traces list");
+        };
+        assert!(from.unwrap().ends_with('Z'));
+        assert!(to.unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_redact_is_an_alias_for_censor() {
+        let cli = Cli::try_parse_from([
+            "lf",
+            "traces",
+            "get",
+            "trace-1",
+            "--redact",
+            "input",
+            "--censor",
+            "usage.totalCost",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.global.censor, vec!["input", "usage.totalCost"]);
+    }
+
+    #[test]
+    fn test_columns_is_an_alias_for_fields() {
+        let cli = Cli::try_parse_from([
+            "lf",
+            "traces",
+            "list",
+            "--columns",
+            "id,name",
+            "--exclude-fields",
+            "input,output",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.global.fields, vec!["id", "name"]);
+        assert_eq!(cli.global.exclude_fields, vec!["input", "output"]);
+    }
+
+    #[test]
+    fn test_connect_timeout_must_be_positive() {
+        let cli = Cli::try_parse_from(["lf", "traces", "list", "--connect-timeout", "45"]).unwrap();
+        assert_eq!(cli.global.connect_timeout, 45);
+
+        let cli = Cli::try_parse_from(["lf", "traces", "list"]).unwrap();
+        assert_eq!(cli.global.connect_timeout, 10);
+
+        assert!(Cli::try_parse_from(["lf", "traces", "list", "--connect-timeout", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_traces_list_order_is_validated() {
+        let cli = Cli::try_parse_from(["lf", "traces", "list", "--order", "ASC"]).unwrap();
+        let Commands::Traces(TracesCommands::List { order, .. }) = cli.command else {
+            panic!("expected traces list");
+        };
+        assert_eq!(order, Some(SortOrder::Asc));
+
+        assert!(Cli::try_parse_from(["lf", "traces", "list", "--order", "oldest"]).is_err());
+        assert!(
+            Cli::try_parse_from(["lf", "traces", "list", "--order", "asc", "--stable"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_prompts_export_takes_its_own_output_dir() {
+        let cli = Cli::try_parse_from([
+            "lf",
+            "prompts",
+            "export",
+            "my-prompt",
+            "--output-dir",
+            "prompts/",
+        ])
+        .unwrap();
+
+        let Commands::Prompts(PromptsCommands::Export { output_dir, .. }) = cli.command else {
+            panic!("expected prompts export");
+        };
+        assert_eq!(output_dir, std::path::PathBuf::from("prompts/"));
+        assert_eq!(cli.global.split_size, None);
+
+        assert!(Cli::try_parse_from([
+            "lf",
+            "prompts",
+            "export",
+            "my-prompt",
+            "--output-dir",
+            "prompts/",
+            "--split-size",
+            "2",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_split_size_is_at_least_one_and_needs_its_own_output_dir() {
+        let cli = Cli::try_parse_from([
+            "lf",
+            "traces",
+            "list",
+            "--split-size",
+            "2",
+            "--output-dir",
+            "parts/",
+        ])
+        .unwrap();
+        assert_eq!(cli.global.split_size, Some(2));
+        assert_eq!(
+            cli.global.output_dir,
+            Some(std::path::PathBuf::from("parts/"))
+        );
+
+        assert!(Cli::try_parse_from([
+            "lf",
+            "traces",
+            "list",
+            "--split-size",
+            "0",
+            "--output-dir",
+            "parts/",
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from(["lf", "traces", "list", "--split-size", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_bash_completions() {
+        let mut out = Vec::new();
+        write_completions(Shell::Bash, &mut out);
+        let script = String::from_utf8(out).unwrap();
+
+        assert!(!script.is_empty());
+        assert!(script.contains("lf"));
+        assert!(script.contains("traces"));
     }
 }
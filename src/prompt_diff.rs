@@ -0,0 +1,244 @@
+// ABOUTME: Compares two prompt versions (prompts diff)
+// ABOUTME: Unified diff of text content or per-message chat diff, plus label/tag/config changes
+
+use similar::TextDiff;
+use std::collections::BTreeSet;
+
+use crate::types::{ChatMessage, Prompt, PromptContent};
+
+/// Describe what changed between two versions of a prompt
+pub fn diff_prompts(from: &Prompt, to: &Prompt) -> String {
+    let mut out = format!(
+        "--- {} v{}\n+++ {} v{}\n",
+        from.name, from.version, to.name, to.version
+    );
+
+    match (&from.prompt, &to.prompt) {
+        (PromptContent::Text(a), PromptContent::Text(b)) => {
+            if a == b {
+                out.push_str("Content: unchanged\n");
+            } else {
+                out.push_str(&unified(a, b));
+            }
+        }
+        (PromptContent::Chat(a), PromptContent::Chat(b)) => out.push_str(&diff_messages(a, b)),
+        (a, b) => out.push_str(&format!(
+            "Type changed: {} -> {}\n",
+            content_type(a),
+            content_type(b)
+        )),
+    }
+
+    let summary = [
+        set_change("Labels", &from.labels, &to.labels),
+        set_change("Tags", &from.tags, &to.tags),
+        config_change(from, to),
+    ];
+    for line in summary.into_iter().flatten() {
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    out
+}
+
+fn content_type(content: &PromptContent) -> &'static str {
+    match content {
+        PromptContent::Text(_) => "text",
+        PromptContent::Chat(_) => "chat",
+    }
+}
+
+/// Line-based unified diff without file headers
+fn unified(a: &str, b: &str) -> String {
+    let mut a = a.to_string();
+    let mut b = b.to_string();
+    // Compare final lines equally whether or not the text ends in a newline
+    for text in [&mut a, &mut b] {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    TextDiff::from_lines(&a, &b)
+        .unified_diff()
+        .context_radius(3)
+        .to_string()
+}
+
+/// Compare chat messages by position
+fn diff_messages(from: &[ChatMessage], to: &[ChatMessage]) -> String {
+    let mut out = String::new();
+
+    for i in 0..from.len().max(to.len()) {
+        let n = i + 1;
+        match (from.get(i), to.get(i)) {
+            (Some(a), Some(b)) if a == b => {}
+            (Some(a), Some(b)) => {
+                if a.label() == b.label() {
+                    out.push_str(&format!("Message {n} ({}) changed:\n", b.label()));
+                } else {
+                    out.push_str(&format!(
+                        "Message {n} changed role: {} -> {}\n",
+                        a.label(),
+                        b.label()
+                    ));
+                }
+                if a.content != b.content {
+                    out.push_str(&unified(&a.content, &b.content));
+                }
+            }
+            (Some(a), None) => {
+                out.push_str(&format!("Message {n} ({}) removed:\n", a.label()));
+                out.push_str(&prefixed('-', &a.content));
+            }
+            (None, Some(b)) => {
+                out.push_str(&format!("Message {n} ({}) added:\n", b.label()));
+                out.push_str(&prefixed('+', &b.content));
+            }
+            (None, None) => unreachable!("index is within the longer list"),
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("Content: unchanged\n");
+    }
+    out
+}
+
+fn prefixed(marker: char, text: &str) -> String {
+    text.lines()
+        .map(|line| format!("{marker}{line}\n"))
+        .collect()
+}
+
+/// `Labels: +production -staging`, or None when nothing changed
+fn set_change(what: &str, from: &[String], to: &[String]) -> Option<String> {
+    let from: BTreeSet<&String> = from.iter().collect();
+    let to: BTreeSet<&String> = to.iter().collect();
+
+    let changes: Vec<String> = to
+        .difference(&from)
+        .map(|v| format!("+{v}"))
+        .chain(from.difference(&to).map(|v| format!("-{v}")))
+        .collect();
+
+    (!changes.is_empty()).then(|| format!("{what}: {}", changes.join(" ")))
+}
+
+fn config_change(from: &Prompt, to: &Prompt) -> Option<String> {
+    let show = |config: &Option<serde_json::Value>| match config {
+        Some(value) => value.to_string(),
+        None => "(none)".to_string(),
+    };
+
+    (from.config != to.config)
+        .then(|| format!("Config: {} -> {}", show(&from.config), show(&to.config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prompt(version: i32, content: PromptContent, labels: &[&str]) -> Prompt {
+        Prompt {
+            name: "greeting".to_string(),
+            version,
+            prompt_type: "text".to_string(),
+            prompt: content,
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            tags: vec![],
+            config: None,
+            commit_message: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_text_prompts() {
+        let from = prompt(
+            1,
+            PromptContent::Text("Hello {{name}}\nBe brief.".to_string()),
+            &["production"],
+        );
+        let mut to = prompt(
+            2,
+            PromptContent::Text("Hello {{name}}\nBe concise.".to_string()),
+            &["staging"],
+        );
+        to.config = Some(json!({"temperature": 0.2}));
+
+        assert_eq!(
+            diff_prompts(&from, &to),
+            "\
+--- greeting v1
++++ greeting v2
+@@ -1,2 +1,2 @@
+ Hello {{name}}
+-Be brief.
++Be concise.
+Labels: +staging -production
+Config: (none) -> {\"temperature\":0.2}
+"
+        );
+    }
+
+    #[test]
+    fn test_diff_chat_prompts_by_message() {
+        let from = prompt(
+            3,
+            PromptContent::Chat(vec![
+                message("system", "You are helpful."),
+                message("user", "Hi"),
+            ]),
+            &[],
+        );
+        let to = prompt(
+            4,
+            PromptContent::Chat(vec![
+                message("system", "You are terse."),
+                message("user", "Hi"),
+                message("assistant", "Hello!"),
+            ]),
+            &[],
+        );
+
+        let diff = diff_prompts(&from, &to);
+
+        assert!(diff.contains("Message 1 (system) changed:\n"));
+        assert!(diff.contains("-You are helpful.\n+You are terse.\n"));
+        assert!(!diff.contains("Message 2"));
+        assert!(diff.contains("Message 3 (assistant) added:\n+Hello!\n"));
+    }
+
+    #[test]
+    fn test_diff_reports_type_change() {
+        let from = prompt(1, PromptContent::Text("Hi".to_string()), &[]);
+        let to = prompt(2, PromptContent::Chat(vec![message("user", "Hi")]), &[]);
+
+        assert_eq!(
+            diff_prompts(&from, &to),
+            "--- greeting v1\n+++ greeting v2\nType changed: text -> chat\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_identical_prompts() {
+        let from = prompt(1, PromptContent::Text("Hi".to_string()), &["a"]);
+        let to = prompt(2, PromptContent::Text("Hi".to_string()), &["a"]);
+
+        assert_eq!(
+            diff_prompts(&from, &to),
+            "--- greeting v1\n+++ greeting v2\nContent: unchanged\n"
+        );
+    }
+}
@@ -0,0 +1,403 @@
+// ABOUTME: Reads and writes prompt versions as files for version control (prompts export/import)
+// ABOUTME: Text prompts are a .txt plus .meta.json sidecar; chat prompts a single .json
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::types::{ChatMessage, Prompt, PromptContent};
+
+/// Everything about a text prompt version except its text, stored next to the `.txt`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFileMeta {
+    pub name: String,
+    pub version: i32,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_message: Option<String>,
+}
+
+impl From<&Prompt> for PromptFileMeta {
+    fn from(prompt: &Prompt) -> Self {
+        Self {
+            name: prompt.name.clone(),
+            version: prompt.version,
+            labels: prompt.labels.clone(),
+            tags: prompt.tags.clone(),
+            config: prompt.config.clone(),
+            commit_message: prompt.commit_message.clone(),
+        }
+    }
+}
+
+/// A chat prompt version: the metadata plus its messages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatPromptFile {
+    #[serde(flatten)]
+    pub meta: PromptFileMeta,
+    pub prompt: Vec<ChatMessage>,
+}
+
+impl From<&Prompt> for ChatPromptFile {
+    fn from(prompt: &Prompt) -> Self {
+        let messages = match &prompt.prompt {
+            PromptContent::Chat(messages) => messages.clone(),
+            PromptContent::Text(_) => vec![],
+        };
+        Self {
+            meta: PromptFileMeta::from(prompt),
+            prompt: messages,
+        }
+    }
+}
+
+/// File name stem for a prompt version, e.g. `support__greeting.v3` for
+/// `support/greeting` version 3. The real name is kept inside the files.
+pub fn file_stem(name: &str, version: i32) -> String {
+    let name = name.replace(['/', '\\'], "__");
+    format!("{name}.v{version}")
+}
+
+/// Write one prompt version into `dir`, returning the paths written
+pub fn write_prompt(dir: &Path, prompt: &Prompt) -> Result<Vec<PathBuf>> {
+    let stem = file_stem(&prompt.name, prompt.version);
+
+    let files = match &prompt.prompt {
+        PromptContent::Text(text) => {
+            let meta = serde_json::to_string_pretty(&PromptFileMeta::from(prompt))?;
+            vec![
+                (dir.join(format!("{stem}.txt")), text.clone()),
+                (dir.join(format!("{stem}.meta.json")), meta + "\n"),
+            ]
+        }
+        PromptContent::Chat(_) => {
+            let json = serde_json::to_string_pretty(&ChatPromptFile::from(prompt))?;
+            vec![(dir.join(format!("{stem}.json")), json + "\n")]
+        }
+    };
+
+    for (path, content) in &files {
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// A prompt version read back from exported files
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptFile {
+    Text { meta: PromptFileMeta, text: String },
+    Chat(ChatPromptFile),
+}
+
+impl PromptFile {
+    pub fn meta(&self) -> &PromptFileMeta {
+        match self {
+            PromptFile::Text { meta, .. } => meta,
+            PromptFile::Chat(chat) => &chat.meta,
+        }
+    }
+}
+
+/// Read the prompt stored at `path`: a `.txt` with its `.meta.json` sidecar,
+/// or a chat `.json`. Returns None for other files, including the sidecars.
+pub fn read_prompt(path: &Path) -> Result<Option<PromptFile>> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+
+    if let Some(stem) = file_name.strip_suffix(".txt") {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let meta_path = path.with_file_name(format!("{stem}.meta.json"));
+        let meta = std::fs::read_to_string(&meta_path)
+            .with_context(|| format!("Failed to read {}", meta_path.display()))?;
+        let meta = serde_json::from_str(&meta)
+            .with_context(|| format!("Invalid prompt metadata in {}", meta_path.display()))?;
+        return Ok(Some(PromptFile::Text { meta, text }));
+    }
+
+    if file_name.ends_with(".json") && !file_name.ends_with(".meta.json") {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let chat = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid chat prompt file {}", path.display()))?;
+        return Ok(Some(PromptFile::Chat(chat)));
+    }
+
+    Ok(None)
+}
+
+/// Every prompt file in `dir`, ordered by prompt name then version so
+/// versions can be recreated in their original order. Files that aren't
+/// prompt files are passed to `on_skip` with the reason; a file that looks
+/// like a prompt but can't be read is an error.
+pub fn read_dir(
+    dir: &Path,
+    mut on_skip: impl FnMut(&Path, &str),
+) -> Result<Vec<(PathBuf, PromptFile)>> {
+    let mut prompts = Vec::new();
+    for entry in
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        // Sidecars are read along with their .txt
+        if file_name.ends_with(".meta.json") {
+            continue;
+        }
+        if let Some(reason) = unrecognised(&path, file_name)? {
+            on_skip(&path, &reason);
+            continue;
+        }
+        if let Some(prompt) = read_prompt(&path)? {
+            prompts.push((path, prompt));
+        }
+    }
+
+    prompts.sort_by(|(_, a), (_, b)| {
+        (&a.meta().name, a.meta().version).cmp(&(&b.meta().name, b.meta().version))
+    });
+    Ok(prompts)
+}
+
+/// Why a file in an import directory isn't a prompt file, or None if it
+/// should be read as one. Invalid JSON counts as a malformed chat prompt.
+fn unrecognised(path: &Path, file_name: &str) -> Result<Option<String>> {
+    if let Some(stem) = file_name.strip_suffix(".txt") {
+        let sidecar = format!("{stem}.meta.json");
+        if path.with_file_name(&sidecar).exists() {
+            return Ok(None);
+        }
+        return Ok(Some(format!("no {sidecar} next to it")));
+    }
+
+    if file_name.ends_with(".json") {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let is_prompt = serde_json::from_str::<serde_json::Value>(&content)
+            .map(|value| value.get("prompt").is_some())
+            .unwrap_or(true);
+        return Ok((!is_prompt).then(|| "not a chat prompt file".to_string()));
+    }
+
+    Ok(Some("not a prompt file".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn prompt(name: &str, version: i32, content: PromptContent) -> Prompt {
+        Prompt {
+            name: name.to_string(),
+            version,
+            prompt_type: "text".to_string(),
+            prompt: content,
+            labels: vec!["production".to_string()],
+            tags: vec!["support".to_string()],
+            config: Some(json!({"temperature": 0.2})),
+            commit_message: Some("Be friendlier".to_string()),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_file_stem_sanitizes_slashes() {
+        assert_eq!(file_stem("greeting", 1), "greeting.v1");
+        assert_eq!(file_stem("support/greeting", 12), "support__greeting.v12");
+        assert_eq!(file_stem("a\\b", 2), "a__b.v2");
+    }
+
+    #[test]
+    fn test_write_text_prompt() {
+        let dir = TempDir::new().unwrap();
+        let text = prompt(
+            "support/greeting",
+            3,
+            PromptContent::Text("Hello {{name}}".to_string()),
+        );
+
+        let paths = write_prompt(dir.path(), &text).unwrap();
+
+        assert_eq!(
+            paths,
+            vec![
+                dir.path().join("support__greeting.v3.txt"),
+                dir.path().join("support__greeting.v3.meta.json"),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&paths[0]).unwrap(),
+            "Hello {{name}}"
+        );
+        let meta: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&paths[1]).unwrap()).unwrap();
+        assert_eq!(
+            meta,
+            json!({
+                "name": "support/greeting",
+                "version": 3,
+                "labels": ["production"],
+                "tags": ["support"],
+                "config": {"temperature": 0.2},
+                "commitMessage": "Be friendlier"
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_chat_prompt() {
+        let dir = TempDir::new().unwrap();
+        let chat = prompt(
+            "assistant",
+            1,
+            PromptContent::Chat(vec![ChatMessage {
+                role: "system".to_string(),
+                content: "You are helpful.".to_string(),
+                ..Default::default()
+            }]),
+        );
+
+        let paths = write_prompt(dir.path(), &chat).unwrap();
+
+        assert_eq!(paths, vec![dir.path().join("assistant.v1.json")]);
+        let file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&paths[0]).unwrap()).unwrap();
+        assert_eq!(file["name"], "assistant");
+        assert_eq!(file["labels"], json!(["production"]));
+        assert_eq!(
+            file["prompt"],
+            json!([{"role": "system", "content": "You are helpful."}])
+        );
+    }
+
+    #[test]
+    fn test_read_prompts_back_in_version_order() {
+        let dir = TempDir::new().unwrap();
+        let chat = prompt(
+            "assistant",
+            1,
+            PromptContent::Chat(vec![ChatMessage {
+                role: "user".to_string(),
+                content: "Hi".to_string(),
+                ..Default::default()
+            }]),
+        );
+        for version in [2, 10, 1] {
+            let text = prompt(
+                "support/greeting",
+                version,
+                PromptContent::Text(format!("Hello v{version}")),
+            );
+            write_prompt(dir.path(), &text).unwrap();
+        }
+        write_prompt(dir.path(), &chat).unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a prompt").unwrap();
+
+        let mut skipped = vec![];
+        let prompts = read_dir(dir.path(), |path, _| skipped.push(path.to_path_buf())).unwrap();
+
+        let order: Vec<(&str, i32)> = prompts
+            .iter()
+            .map(|(_, p)| (p.meta().name.as_str(), p.meta().version))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("assistant", 1),
+                ("support/greeting", 1),
+                ("support/greeting", 2),
+                ("support/greeting", 10)
+            ]
+        );
+        assert_eq!(prompts[0].1, PromptFile::Chat(ChatPromptFile::from(&chat)));
+        match &prompts[1].1 {
+            PromptFile::Text { meta, text } => {
+                assert_eq!(text, "Hello v1");
+                assert_eq!(meta.commit_message.as_deref(), Some("Be friendlier"));
+            }
+            other => panic!("expected a text prompt, got {other:?}"),
+        }
+        assert_eq!(skipped, vec![dir.path().join("README.md")]);
+    }
+
+    #[test]
+    fn test_read_dir_skips_stray_files() {
+        let dir = TempDir::new().unwrap();
+        write_prompt(
+            dir.path(),
+            &prompt("greeting", 1, PromptContent::Text("Hello".to_string())),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("orphan.v1.txt"), "Hello").unwrap();
+        std::fs::write(dir.path().join("package.json"), r#"{"name": "app"}"#).unwrap();
+
+        let mut skipped = vec![];
+        let prompts = read_dir(dir.path(), |path, reason| {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            skipped.push((name, reason.to_string()));
+        })
+        .unwrap();
+        skipped.sort();
+
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].1.meta().name, "greeting");
+        assert_eq!(
+            skipped,
+            vec![
+                (
+                    "orphan.v1.txt".to_string(),
+                    "no orphan.v1.meta.json next to it".to_string()
+                ),
+                (
+                    "package.json".to_string(),
+                    "not a chat prompt file".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_dir_fails_on_malformed_prompt_file() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("broken.v1.json"),
+            r#"{"name": "broken", "prompt": "not a list of messages"}"#,
+        )
+        .unwrap();
+
+        let err = read_dir(dir.path(), |_, _| {}).unwrap_err();
+
+        assert!(err.to_string().contains("Invalid chat prompt file"));
+    }
+
+    #[test]
+    fn test_read_text_prompt_requires_sidecar() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("orphan.v1.txt");
+        std::fs::write(&path, "Hello").unwrap();
+
+        let err = read_prompt(&path).unwrap_err();
+
+        assert!(err.to_string().contains("orphan.v1.meta.json"));
+    }
+}
@@ -0,0 +1,171 @@
+// ABOUTME: Client-side record filtering for list commands (--where FIELD=VALUE)
+// ABOUTME: Supports equality, inequality (!=) and substring (~=) tests on dotted field paths
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+/// How a condition compares a field with its value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    /// `field=value`
+    Equals,
+    /// `field!=value` (records without the field also match)
+    NotEquals,
+    /// `field~=value`, a substring match
+    Contains,
+}
+
+/// One `--where` condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Field path split on dots (e.g. `metadata.env`)
+    path: Vec<String>,
+    comparison: Comparison,
+    value: String,
+}
+
+impl Condition {
+    /// Whether a serialized record satisfies the condition
+    pub fn matches(&self, record: &Value) -> bool {
+        let field = self
+            .path
+            .iter()
+            .try_fold(record, |current, key| current.get(key))
+            .filter(|v| !v.is_null())
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+
+        match (self.comparison, field) {
+            (Comparison::Equals, Some(field)) => field == self.value,
+            (Comparison::NotEquals, Some(field)) => field != self.value,
+            (Comparison::Contains, Some(field)) => field.contains(&self.value),
+            (Comparison::NotEquals, None) => true,
+            (_, None) => false,
+        }
+    }
+}
+
+/// Parse `field=value`, `field!=value` or `field~=value` (clap value parser)
+pub fn parse_condition(s: &str) -> Result<Condition, String> {
+    let Some(eq) = s.find('=') else {
+        return Err(format!(
+            "Invalid condition '{s}'. Expected FIELD=VALUE, FIELD!=VALUE or FIELD~=VALUE"
+        ));
+    };
+
+    let (field, comparison) = match s[..eq].strip_suffix('!') {
+        Some(field) => (field, Comparison::NotEquals),
+        None => match s[..eq].strip_suffix('~') {
+            Some(field) => (field, Comparison::Contains),
+            None => (&s[..eq], Comparison::Equals),
+        },
+    };
+
+    let field = field.trim();
+    if field.is_empty() {
+        return Err(format!("Invalid condition '{s}': missing field name"));
+    }
+
+    Ok(Condition {
+        path: field.split('.').map(str::to_string).collect(),
+        comparison,
+        value: s[eq + 1..].to_string(),
+    })
+}
+
+/// Keep the records that satisfy every condition, in their original order
+pub fn keep_matching<T: Serialize>(records: Vec<T>, conditions: &[Condition]) -> Result<Vec<T>> {
+    if conditions.is_empty() {
+        return Ok(records);
+    }
+
+    let mut kept = Vec::with_capacity(records.len());
+    for record in records {
+        let value = serde_json::to_value(&record)?;
+        if conditions.iter().all(|c| c.matches(&value)) {
+            kept.push(record);
+        }
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ids(records: &[Value]) -> Vec<&str> {
+        records.iter().map(|r| r["id"].as_str().unwrap()).collect()
+    }
+
+    fn traces() -> Vec<Value> {
+        vec![
+            json!({"id": "t-1", "release": "v1.2.0", "metadata": {"env": "production"}}),
+            json!({"id": "t-2", "release": "v1.3.0", "metadata": {"env": "preprod"}}),
+            json!({"id": "t-3", "release": "v1.2.0", "metadata": {"env": "staging"}}),
+            json!({"id": "t-4", "metadata": null}),
+        ]
+    }
+
+    #[test]
+    fn test_where_release_equals() {
+        let conditions = [parse_condition("release=v1.2.0").unwrap()];
+
+        let kept = keep_matching(traces(), &conditions).unwrap();
+
+        assert_eq!(ids(&kept), ["t-1", "t-3"]);
+    }
+
+    #[test]
+    fn test_where_nested_substring() {
+        let conditions = [parse_condition("metadata.env~=prod").unwrap()];
+
+        let kept = keep_matching(traces(), &conditions).unwrap();
+
+        assert_eq!(ids(&kept), ["t-1", "t-2"]);
+    }
+
+    #[test]
+    fn test_where_not_equals_keeps_missing_and_combines() {
+        let conditions = [
+            parse_condition("release!=v1.3.0").unwrap(),
+            parse_condition("metadata.env!=staging").unwrap(),
+        ];
+
+        let kept = keep_matching(traces(), &conditions).unwrap();
+
+        assert_eq!(ids(&kept), ["t-1", "t-4"]);
+    }
+
+    #[test]
+    fn test_where_compares_numbers_by_text() {
+        let records = vec![
+            json!({"id": "a", "value": 1}),
+            json!({"id": "b", "value": 0.5}),
+        ];
+        let conditions = [parse_condition("value=0.5").unwrap()];
+
+        assert_eq!(ids(&keep_matching(records, &conditions).unwrap()), ["b"]);
+    }
+
+    #[test]
+    fn test_parse_condition_errors() {
+        assert!(parse_condition("release")
+            .unwrap_err()
+            .contains("FIELD=VALUE"));
+        assert!(parse_condition("=v1")
+            .unwrap_err()
+            .contains("missing field"));
+        assert!(parse_condition("!=v1").is_err());
+    }
+
+    #[test]
+    fn test_parse_condition_value_may_contain_equals() {
+        let condition = parse_condition("input~=a=b").unwrap();
+        assert_eq!(condition.comparison, Comparison::Contains);
+        assert_eq!(condition.value, "a=b");
+    }
+}
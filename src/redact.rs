@@ -0,0 +1,196 @@
+// ABOUTME: Redaction of sensitive values in serialized records before formatting
+// ABOUTME: Supports censoring whole fields by name or dotted path, and substrings matching a regex
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::Value;
+
+/// Placeholder written in place of redacted content
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Redaction rules applied to output records
+#[derive(Debug, Default)]
+pub struct Redactor {
+    /// Field paths split on dots; a plain name is a one-segment path
+    fields: Vec<Vec<String>>,
+    pattern: Option<Regex>,
+}
+
+impl Redactor {
+    /// Build a redactor from field names and an optional regex pattern
+    pub fn new(fields: &[String], pattern: Option<&str>) -> Result<Self> {
+        let pattern = pattern
+            .map(|p| Regex::new(p).with_context(|| format!("Invalid censor pattern: {p}")))
+            .transpose()?;
+
+        Ok(Self {
+            fields: fields
+                .iter()
+                .map(|f| f.split('.').map(str::to_string).collect())
+                .collect(),
+            pattern,
+        })
+    }
+
+    /// Whether any redaction rule is configured
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.pattern.is_none()
+    }
+
+    /// Redact a value in place, recursing into nested objects and arrays.
+    /// Paths match starting at any depth, like plain field names.
+    pub fn apply(&self, value: &mut Value) {
+        self.apply_at(value, &[]);
+    }
+
+    /// `continuing` holds the remaining segments of paths matched so far
+    fn apply_at(&self, value: &mut Value, continuing: &[&[String]]) {
+        match value {
+            Value::Object(obj) => {
+                for (key, v) in obj.iter_mut() {
+                    let mut redact = false;
+                    let mut next: Vec<&[String]> = Vec::new();
+                    let paths = self
+                        .fields
+                        .iter()
+                        .map(Vec::as_slice)
+                        .chain(continuing.iter().copied());
+                    for path in paths {
+                        if let Some((first, rest)) = path.split_first() {
+                            if first == key {
+                                if rest.is_empty() {
+                                    redact = true;
+                                } else {
+                                    next.push(rest);
+                                }
+                            }
+                        }
+                    }
+
+                    if redact {
+                        *v = Value::String(REDACTED.to_string());
+                    } else {
+                        self.apply_at(v, &next);
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                for v in arr.iter_mut() {
+                    self.apply_at(v, continuing);
+                }
+            }
+            Value::String(s) => {
+                if let Some(re) = &self.pattern {
+                    if re.is_match(s) {
+                        *s = re.replace_all(s, REDACTED).into_owned();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_censor_fields_replaces_values() {
+        let redactor = Redactor::new(&["input".to_string(), "output".to_string()], None).unwrap();
+        let mut value = json!({
+            "id": "trace-1",
+            "input": {"messages": [{"role": "user", "content": "secret"}]},
+            "output": "also secret"
+        });
+
+        redactor.apply(&mut value);
+
+        assert_eq!(value["id"], "trace-1");
+        assert_eq!(value["input"], REDACTED);
+        assert_eq!(value["output"], REDACTED);
+    }
+
+    #[test]
+    fn test_censor_fields_in_nested_records() {
+        let redactor = Redactor::new(&["input".to_string()], None).unwrap();
+        let mut value = json!([
+            {"id": "1", "observations": [{"id": "obs-1", "input": "hidden"}]},
+            {"id": "2", "input": null}
+        ]);
+
+        redactor.apply(&mut value);
+
+        assert_eq!(value[0]["observations"][0]["input"], REDACTED);
+        assert_eq!(value[0]["observations"][0]["id"], "obs-1");
+        assert_eq!(value[1]["input"], REDACTED);
+    }
+
+    #[test]
+    fn test_censor_dotted_paths() {
+        let redactor = Redactor::new(
+            &[
+                "usage.totalCost".to_string(),
+                "observations.metadata.apiKey".to_string(),
+            ],
+            None,
+        )
+        .unwrap();
+        let mut value = json!({
+            "id": "trace-1",
+            "totalCost": 1.5,
+            "usage": {"input": 10, "totalCost": 0.02},
+            "observations": [
+                {"id": "obs-1", "metadata": {"apiKey": "sk-123", "env": "prod"}},
+                {"id": "obs-2", "usage": {"totalCost": 0.01}}
+            ]
+        });
+
+        redactor.apply(&mut value);
+
+        assert_eq!(value["id"], "trace-1");
+        assert_eq!(value["totalCost"], 1.5);
+        assert_eq!(value["usage"]["input"], 10);
+        assert_eq!(value["usage"]["totalCost"], REDACTED);
+        assert_eq!(value["observations"][0]["metadata"]["apiKey"], REDACTED);
+        assert_eq!(value["observations"][0]["metadata"]["env"], "prod");
+        assert_eq!(value["observations"][1]["usage"]["totalCost"], REDACTED);
+    }
+
+    #[test]
+    fn test_censor_pattern_replaces_substrings() {
+        let redactor = Redactor::new(&[], Some(r"[\w.]+@[\w.]+")).unwrap();
+        let mut value = json!({
+            "userId": "alice@example.com",
+            "metadata": {"note": "contact bob@example.com today"},
+            "count": 3
+        });
+
+        redactor.apply(&mut value);
+
+        assert_eq!(value["userId"], REDACTED);
+        assert_eq!(value["metadata"]["note"], "contact [REDACTED] today");
+        assert_eq!(value["count"], 3);
+    }
+
+    #[test]
+    fn test_invalid_censor_pattern() {
+        let result = Redactor::new(&[], Some("("));
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid censor pattern"));
+    }
+
+    #[test]
+    fn test_empty_redactor() {
+        let redactor = Redactor::new(&[], None).unwrap();
+        assert!(redactor.is_empty());
+
+        let mut value = json!({"input": "kept"});
+        redactor.apply(&mut value);
+        assert_eq!(value["input"], "kept");
+    }
+}
@@ -0,0 +1,170 @@
+// ABOUTME: Parses scores for bulk import from CSV (scores import)
+// ABOUTME: Each row keeps its line number so failures can be reported individually
+
+use anyhow::Result;
+
+use crate::types::{ScoreDataType, ScoreValue};
+
+/// A score to create, attached to a trace, observation and/or session
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportScore {
+    pub name: String,
+    pub value: ScoreValue,
+    pub trace_id: Option<String>,
+    pub observation_id: Option<String>,
+    pub session_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// One row of the import file: the score, or why it couldn't be parsed
+#[derive(Debug)]
+pub struct ScoreRow {
+    pub line: usize,
+    pub score: Result<ImportScore, String>,
+}
+
+/// Parse a CSV with a header row naming `name`, `value`, at least one of
+/// `traceId`, `observationId` or `sessionId`, and optionally `comment`.
+/// Values are parsed according to `data_type` (numeric when unspecified).
+pub fn parse_scores(content: &str, data_type: Option<ScoreDataType>) -> Result<Vec<ScoreRow>> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let column = |names: &[&str]| headers.iter().position(|h| names.contains(&h.trim()));
+    let columns = Columns {
+        name: column(&["name"]).ok_or_else(|| anyhow::anyhow!("CSV file has no 'name' column"))?,
+        value: column(&["value"])
+            .ok_or_else(|| anyhow::anyhow!("CSV file has no 'value' column"))?,
+        trace_id: column(&["traceId", "trace_id"]),
+        observation_id: column(&["observationId", "observation_id"]),
+        session_id: column(&["sessionId", "session_id"]),
+        comment: column(&["comment"]),
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let (line, score) = match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line() as usize);
+                (line, columns.score(&record, data_type))
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line() as usize);
+                (line, Err(e.to_string()))
+            }
+        };
+        rows.push(ScoreRow { line, score });
+    }
+
+    Ok(rows)
+}
+
+/// Column positions found in the header row
+struct Columns {
+    name: usize,
+    value: usize,
+    trace_id: Option<usize>,
+    observation_id: Option<usize>,
+    session_id: Option<usize>,
+    comment: Option<usize>,
+}
+
+impl Columns {
+    fn score(
+        &self,
+        record: &csv::StringRecord,
+        data_type: Option<ScoreDataType>,
+    ) -> Result<ImportScore, String> {
+        let cell = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        let trace_id = cell(self.trace_id);
+        let observation_id = cell(self.observation_id);
+        let session_id = cell(self.session_id);
+        if trace_id.is_none() && observation_id.is_none() && session_id.is_none() {
+            return Err("no traceId, observationId or sessionId".to_string());
+        }
+
+        let name = cell(Some(self.name)).ok_or("missing name")?;
+        let value = cell(Some(self.value)).ok_or("missing value")?;
+        let value = ScoreValue::parse(&value, data_type).map_err(|e| e.to_string())?;
+
+        Ok(ImportScore {
+            name,
+            value,
+            trace_id,
+            observation_id,
+            session_id,
+            comment: cell(self.comment),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scores_csv() {
+        let content = "traceId,observationId,name,value,comment\n\
+                       trace-1,,accuracy,0.9,looks right\n\
+                       ,obs-2,accuracy,0.4,\n\
+                       ,,accuracy,1,\n\
+                       trace-3,,accuracy,high,\n";
+
+        let rows = parse_scores(content, None).unwrap();
+
+        let lines: Vec<usize> = rows.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![2, 3, 4, 5]);
+        assert_eq!(
+            rows[0].score,
+            Ok(ImportScore {
+                name: "accuracy".to_string(),
+                value: ScoreValue::Numeric(0.9),
+                trace_id: Some("trace-1".to_string()),
+                observation_id: None,
+                session_id: None,
+                comment: Some("looks right".to_string()),
+            })
+        );
+        assert_eq!(
+            rows[1].score.as_ref().unwrap().observation_id,
+            Some("obs-2".to_string())
+        );
+        assert_eq!(
+            rows[2].score,
+            Err("no traceId, observationId or sessionId".to_string())
+        );
+        assert!(rows[3]
+            .score
+            .as_ref()
+            .unwrap_err()
+            .contains("Invalid numeric score value 'high'"));
+    }
+
+    #[test]
+    fn test_parse_scores_uses_data_type() {
+        let content = "sessionId,name,value\nsess-1,helpful,true\nsess-2,helpful,maybe\n";
+
+        let rows = parse_scores(content, Some(ScoreDataType::Boolean)).unwrap();
+
+        assert_eq!(
+            rows[0].score.as_ref().unwrap().value,
+            ScoreValue::Boolean(true)
+        );
+        assert!(rows[1].score.is_err());
+    }
+
+    #[test]
+    fn test_parse_scores_requires_name_and_value_columns() {
+        let err = parse_scores("traceId,value\nt,1\n", None).unwrap_err();
+        assert!(err.to_string().contains("no 'name' column"));
+
+        let err = parse_scores("traceId,name\nt,x\n", None).unwrap_err();
+        assert!(err.to_string().contains("no 'value' column"));
+    }
+}
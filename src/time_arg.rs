@@ -0,0 +1,149 @@
+// ABOUTME: Parses --from/--to values: relative expressions (-7d, now, today) or ISO 8601
+// ABOUTME: Relative values are resolved against the current UTC time and emitted as RFC 3339
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+
+/// Resolve a `--from`/`--to` value to a timestamp for the API. Accepts
+/// `now`, `today` (midnight UTC) and offsets into the past like `-30s`,
+/// `-15m`, `-12h`, `-7d` and `-2w`; anything else is passed through as an
+/// ISO 8601 timestamp.
+pub fn parse_time_arg(value: &str) -> Result<String> {
+    parse_time_arg_at(value, Utc::now())
+}
+
+fn parse_time_arg_at(value: &str, now: DateTime<Utc>) -> Result<String> {
+    let value = value.trim();
+    let instant = match value.to_lowercase().as_str() {
+        "now" => now,
+        "today" => now
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc(),
+        relative if relative.starts_with('-') => {
+            let offset = parse_offset(&relative[1..]).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid relative time '{value}'. Use a number and unit, e.g. -30m, -12h, -7d or -2w"
+                )
+            })?;
+            now.checked_sub_signed(offset)
+                .ok_or_else(|| anyhow::anyhow!("Relative time '{value}' is out of range"))?
+        }
+        _ => return Ok(value.to_string()),
+    };
+
+    Ok(instant.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Reject a window whose `--from` is later than its `--to`, which the API
+/// would otherwise answer with an empty result. Values that aren't RFC 3339
+/// timestamps are left for the API to judge.
+pub fn check_time_window(from: Option<&str>, to: Option<&str>) -> Result<()> {
+    let (Some(from), Some(to)) = (from, to) else {
+        return Ok(());
+    };
+    if let (Ok(start), Ok(end)) = (
+        DateTime::parse_from_rfc3339(from),
+        DateTime::parse_from_rfc3339(to),
+    ) {
+        if start > end {
+            anyhow::bail!("--from ({from}) is after --to ({to})");
+        }
+    }
+    Ok(())
+}
+
+/// `7d` -> 7 days; None for anything that isn't a count and a unit
+fn parse_offset(offset: &str) -> Option<Duration> {
+    let unit_at = offset.find(|c: char| !c.is_ascii_digit())?;
+    let (count, unit) = offset.split_at(unit_at);
+    let count: i64 = count.parse().ok()?;
+
+    match unit {
+        "s" => Duration::try_seconds(count),
+        "m" => Duration::try_minutes(count),
+        "h" => Duration::try_hours(count),
+        "d" => Duration::try_days(count),
+        "w" => Duration::try_weeks(count),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 10, 15, 30, 45).unwrap()
+    }
+
+    #[test]
+    fn test_relative_days_and_hours() {
+        assert_eq!(
+            parse_time_arg_at("-7d", now()).unwrap(),
+            "2024-03-03T15:30:45Z"
+        );
+        assert_eq!(
+            parse_time_arg_at("-2h", now()).unwrap(),
+            "2024-03-10T13:30:45Z"
+        );
+        assert_eq!(
+            parse_time_arg_at("-30m", now()).unwrap(),
+            "2024-03-10T15:00:45Z"
+        );
+    }
+
+    #[test]
+    fn test_now_and_today() {
+        assert_eq!(
+            parse_time_arg_at("now", now()).unwrap(),
+            "2024-03-10T15:30:45Z"
+        );
+        assert_eq!(
+            parse_time_arg_at("today", now()).unwrap(),
+            "2024-03-10T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_iso_timestamp_passes_through() {
+        assert_eq!(
+            parse_time_arg_at("2024-01-01T00:00:00Z", now()).unwrap(),
+            "2024-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_invalid_relative_time() {
+        for value in ["-7", "-d", "-7y", "-1.5h"] {
+            let err = parse_time_arg_at(value, now()).unwrap_err();
+            assert!(err.to_string().contains("Invalid relative time"), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_relative_time_out_of_range() {
+        for value in ["-100000000d", "-10000000000000s"] {
+            let err = parse_time_arg_at(value, now()).unwrap_err();
+            assert!(err.to_string().contains("out of range"), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_check_time_window() {
+        assert!(
+            check_time_window(Some("2024-03-01T00:00:00Z"), Some("2024-03-02T00:00:00Z")).is_ok()
+        );
+        assert!(check_time_window(Some("2024-03-01T00:00:00Z"), None).is_ok());
+        assert!(check_time_window(Some("yesterday"), Some("2024-03-02T00:00:00Z")).is_ok());
+
+        let err = check_time_window(
+            Some("2024-03-02T00:00:00Z"),
+            Some("2024-03-01T00:00:00+02:00"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("is after --to"));
+    }
+}
@@ -0,0 +1,108 @@
+// ABOUTME: Display of timestamps in a chosen IANA time zone (--timezone)
+// ABOUTME: Rewrites known timestamp fields in serialized records before formatting
+
+use chrono::{DateTime, SecondsFormat};
+use chrono_tz::Tz;
+use serde_json::Value;
+
+/// Fields holding RFC 3339 timestamps that are converted for display
+const TIMESTAMP_FIELDS: &[&str] = &[
+    "timestamp",
+    "createdAt",
+    "startTime",
+    "endTime",
+    "updatedAt",
+];
+
+/// Parse an IANA zone name such as `America/New_York` (clap value parser)
+pub fn parse_timezone(s: &str) -> Result<Tz, String> {
+    s.parse::<Tz>().map_err(|_| {
+        format!("Unknown time zone '{s}'. Expected an IANA name like America/New_York or UTC")
+    })
+}
+
+/// Rewrite timestamp fields into `tz`, recursing into nested objects and
+/// arrays. Values that aren't RFC 3339 timestamps are left as they are.
+pub fn convert_timestamps(value: &mut Value, tz: Tz) {
+    match value {
+        Value::Object(obj) => {
+            for (key, v) in obj.iter_mut() {
+                match v {
+                    Value::String(s) if TIMESTAMP_FIELDS.contains(&key.as_str()) => {
+                        if let Some(converted) = in_zone(s, tz) {
+                            *s = converted;
+                        }
+                    }
+                    _ => convert_timestamps(v, tz),
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr.iter_mut() {
+                convert_timestamps(v, tz);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An RFC 3339 timestamp expressed in `tz`, keeping its UTC offset explicit
+fn in_zone(timestamp: &str, tz: Tz) -> Option<String> {
+    let parsed = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some(
+        parsed
+            .with_timezone(&tz)
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_convert_timestamp_to_new_york() {
+        let tz = parse_timezone("America/New_York").unwrap();
+        let mut value = json!({"id": "t-1", "timestamp": "2024-01-15T15:30:00Z"});
+
+        convert_timestamps(&mut value, tz);
+
+        assert_eq!(value["timestamp"], "2024-01-15T10:30:00-05:00");
+        assert_eq!(value["id"], "t-1");
+    }
+
+    #[test]
+    fn test_convert_keeps_fractional_seconds_and_dst_offset() {
+        let tz = parse_timezone("Europe/London").unwrap();
+        let mut value = json!([{"startTime": "2024-07-01T12:00:00.250Z"}]);
+
+        convert_timestamps(&mut value, tz);
+
+        assert_eq!(value[0]["startTime"], "2024-07-01T13:00:00.250+01:00");
+    }
+
+    #[test]
+    fn test_convert_recurses_and_skips_other_fields() {
+        let tz = parse_timezone("Asia/Kolkata").unwrap();
+        let mut value = json!({
+            "name": "2024-01-15T00:00:00Z",
+            "observations": [{"endTime": "2024-01-15T00:00:00Z", "createdAt": "not a time"}]
+        });
+
+        convert_timestamps(&mut value, tz);
+
+        assert_eq!(value["name"], "2024-01-15T00:00:00Z");
+        assert_eq!(
+            value["observations"][0]["endTime"],
+            "2024-01-15T05:30:00+05:30"
+        );
+        assert_eq!(value["observations"][0]["createdAt"], "not a time");
+    }
+
+    #[test]
+    fn test_parse_timezone_rejects_unknown_zone() {
+        let err = parse_timezone("Mars/Olympus").unwrap_err();
+        assert!(err.contains("Unknown time zone 'Mars/Olympus'"));
+    }
+}
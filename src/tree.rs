@@ -0,0 +1,208 @@
+// ABOUTME: Renders a trace's observations as an indented parent/child tree (--tree)
+// ABOUTME: Orphans are shown at the root and cycles are broken so every node prints once
+
+use chrono::DateTime;
+use std::collections::{HashMap, HashSet};
+
+use crate::types::{Observation, Trace};
+
+/// Render the trace header followed by its observation tree
+pub fn render_trace_tree(trace: &Trace, observations: &[Observation]) -> String {
+    let mut out = match &trace.name {
+        Some(name) => format!("{} ({})\n", name, trace.id),
+        None => format!("{}\n", trace.id),
+    };
+    out.push_str(&render_tree(observations));
+    out.trim_end().to_string()
+}
+
+/// Render observations as an indented tree, siblings ordered by start time.
+/// Observations whose parent isn't in the set are attached at the root.
+pub fn render_tree(observations: &[Observation]) -> String {
+    let ids: HashSet<&str> = observations.iter().map(|o| o.id.as_str()).collect();
+
+    let mut roots: Vec<usize> = Vec::new();
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, obs) in observations.iter().enumerate() {
+        match obs.parent_observation_id.as_deref() {
+            Some(parent) if parent != obs.id && ids.contains(parent) => {
+                children.entry(parent).or_default().push(i)
+            }
+            _ => roots.push(i),
+        }
+    }
+
+    let by_start = |a: &usize, b: &usize| {
+        observations[*a]
+            .start_time
+            .cmp(&observations[*b].start_time)
+    };
+    roots.sort_by(by_start);
+    for siblings in children.values_mut() {
+        siblings.sort_by(by_start);
+    }
+
+    let mut out = String::new();
+    let mut visited: HashSet<usize> = HashSet::new();
+    let tree = Tree {
+        observations,
+        children: &children,
+    };
+    tree.render_level(&roots, "", &mut visited, &mut out);
+
+    // Anything left unvisited sits on a parent cycle; break it at the earliest node
+    let mut remaining: Vec<usize> = (0..observations.len())
+        .filter(|i| !visited.contains(i))
+        .collect();
+    remaining.sort_by(by_start);
+    for i in remaining {
+        if !visited.contains(&i) {
+            tree.render_level(&[i], "", &mut visited, &mut out);
+        }
+    }
+
+    out
+}
+
+struct Tree<'a> {
+    observations: &'a [Observation],
+    children: &'a HashMap<&'a str, Vec<usize>>,
+}
+
+impl Tree<'_> {
+    fn render_level(
+        &self,
+        nodes: &[usize],
+        prefix: &str,
+        visited: &mut HashSet<usize>,
+        out: &mut String,
+    ) {
+        let nodes: Vec<usize> = nodes
+            .iter()
+            .copied()
+            .filter(|i| !visited.contains(i))
+            .collect();
+
+        for (n, &i) in nodes.iter().enumerate() {
+            if !visited.insert(i) {
+                continue;
+            }
+            let last = n + 1 == nodes.len();
+            let obs = &self.observations[i];
+
+            out.push_str(prefix);
+            out.push_str(if last { "└─ " } else { "├─ " });
+            out.push_str(&describe(obs));
+            out.push('\n');
+
+            if let Some(kids) = self.children.get(obs.id.as_str()) {
+                let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+                self.render_level(kids, &child_prefix, visited, out);
+            }
+        }
+    }
+}
+
+/// `name [TYPE] latency`, with placeholders for missing values
+fn describe(obs: &Observation) -> String {
+    format!(
+        "{} [{}] {}",
+        obs.name.as_deref().unwrap_or("(unnamed)"),
+        obs.r#type.as_deref().unwrap_or("?"),
+        latency(obs).unwrap_or_else(|| "-".to_string())
+    )
+}
+
+fn latency(obs: &Observation) -> Option<String> {
+    let start = DateTime::parse_from_rfc3339(obs.start_time.as_deref()?).ok()?;
+    let end = DateTime::parse_from_rfc3339(obs.end_time.as_deref()?).ok()?;
+    let ms = (end - start).num_milliseconds();
+    Some(if ms < 1000 {
+        format!("{ms}ms")
+    } else {
+        format!("{:.2}s", ms as f64 / 1000.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn obs(id: &str, parent: Option<&str>, start: &str, end: Option<&str>) -> Observation {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": id,
+            "type": "SPAN",
+            "parentObservationId": parent,
+            "startTime": start,
+            "endTime": end,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_tree_nests_children_in_start_order() {
+        let observations = vec![
+            obs(
+                "llm",
+                Some("root"),
+                "2024-01-01T00:00:01Z",
+                Some("2024-01-01T00:00:03.5Z"),
+            ),
+            obs(
+                "root",
+                None,
+                "2024-01-01T00:00:00Z",
+                Some("2024-01-01T00:00:04Z"),
+            ),
+            obs(
+                "retrieve",
+                Some("root"),
+                "2024-01-01T00:00:00.100Z",
+                Some("2024-01-01T00:00:00.350Z"),
+            ),
+            obs("embed", Some("retrieve"), "2024-01-01T00:00:00.100Z", None),
+        ];
+
+        assert_eq!(
+            render_tree(&observations),
+            "\
+└─ root [SPAN] 4.00s
+   ├─ retrieve [SPAN] 250ms
+   │  └─ embed [SPAN] -
+   └─ llm [SPAN] 2.50s
+"
+        );
+    }
+
+    #[test]
+    fn test_render_tree_orphans_at_root() {
+        let observations = vec![
+            obs("a", None, "2024-01-01T00:00:00Z", None),
+            obs("orphan", Some("missing"), "2024-01-01T00:00:01Z", None),
+        ];
+
+        assert_eq!(
+            render_tree(&observations),
+            "├─ a [SPAN] -\n└─ orphan [SPAN] -\n"
+        );
+    }
+
+    #[test]
+    fn test_render_tree_breaks_cycles() {
+        let observations = vec![
+            obs("x", Some("y"), "2024-01-01T00:00:00Z", None),
+            obs("y", Some("x"), "2024-01-01T00:00:01Z", None),
+            obs("self", Some("self"), "2024-01-01T00:00:02Z", None),
+        ];
+
+        let rendered = render_tree(&observations);
+
+        assert_eq!(rendered.lines().count(), 3);
+        assert_eq!(
+            rendered,
+            "└─ self [SPAN] -\n└─ x [SPAN] -\n   └─ y [SPAN] -\n"
+        );
+    }
+}
@@ -9,8 +9,60 @@ pub enum OutputFormat {
     #[default]
     Table,
     Json,
+    /// One compact JSON object per line
+    #[value(alias = "ndjson")]
+    #[serde(alias = "ndjson")]
+    Jsonl,
     Csv,
+    /// Tab-separated values
+    Tsv,
     Markdown,
+    /// An HTML <table> snippet
+    Html,
+}
+
+impl OutputFormat {
+    /// File extension used when writing this format to a directory
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Table => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+            OutputFormat::Markdown => "md",
+            OutputFormat::Html => "html",
+        }
+    }
+}
+
+/// How failures are reported on stderr
+#[derive(Debug, Clone, Copy, Default, ValueEnum, PartialEq)]
+pub enum ErrorFormat {
+    /// A readable message
+    #[default]
+    Text,
+    /// A JSON object with the error kind, message and HTTP status
+    Json,
+}
+
+/// Direction for ordering listed records by timestamp
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum SortOrder {
+    /// Oldest first
+    Asc,
+    /// Newest first (the API's default)
+    Desc,
+}
+
+impl SortOrder {
+    /// The API's `orderBy` value for ordering by timestamp
+    pub fn to_order_by(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "timestamp.asc",
+            SortOrder::Desc => "timestamp.desc",
+        }
+    }
 }
 
 /// Metrics view options
@@ -21,6 +73,54 @@ pub enum MetricsView {
     Observations,
 }
 
+impl MetricsView {
+    pub fn to_api_string(&self) -> &str {
+        match self {
+            MetricsView::Traces => "traces",
+            MetricsView::Observations => "observations",
+        }
+    }
+
+    /// Fields the view can be grouped by with `--dimensions`
+    pub fn dimensions(&self) -> &'static [&'static str] {
+        match self {
+            MetricsView::Traces => &[
+                "id",
+                "name",
+                "tags",
+                "userId",
+                "sessionId",
+                "release",
+                "version",
+                "environment",
+                "timestampMonth",
+            ],
+            MetricsView::Observations => &[
+                "id",
+                "traceId",
+                "traceName",
+                "environment",
+                "parentObservationId",
+                "type",
+                "name",
+                "level",
+                "version",
+                "tags",
+                "release",
+                "traceRelease",
+                "traceVersion",
+                "model",
+                "providedModelName",
+                "promptName",
+                "promptVersion",
+                "userId",
+                "sessionId",
+                "startTimeMonth",
+            ],
+        }
+    }
+}
+
 /// Metrics measure options
 #[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -102,6 +202,80 @@ impl TimeGranularity {
     }
 }
 
+/// Observation level options
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ObservationLevel {
+    Debug,
+    Default,
+    Warning,
+    Error,
+}
+
+impl ObservationLevel {
+    pub fn to_api_string(self) -> &'static str {
+        match self {
+            ObservationLevel::Debug => "DEBUG",
+            ObservationLevel::Default => "DEFAULT",
+            ObservationLevel::Warning => "WARNING",
+            ObservationLevel::Error => "ERROR",
+        }
+    }
+}
+
+/// A filter on a metrics query, parsed from `--filter field:operator:value`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsFilter {
+    pub column: String,
+    pub operator: String,
+    pub value: serde_json::Value,
+    #[serde(rename = "type")]
+    pub filter_type: String,
+}
+
+impl MetricsFilter {
+    /// Parse `field:operator:value`. Operators are `=`, `!=`, `in` and `contains`;
+    /// `in` takes a comma-separated list. The value may itself contain colons.
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let mut parts = s.splitn(3, ':');
+        let (Some(column), Some(operator), Some(value)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("Invalid filter '{s}'. Expected FIELD:OPERATOR:VALUE, e.g. name:=:chat");
+        };
+        if column.is_empty() {
+            anyhow::bail!("Invalid filter '{s}'. The field name is empty");
+        }
+
+        let string = |operator: &str| (operator.to_string(), serde_json::json!(value), "string");
+        let options = |operator: &str, values: Vec<&str>| {
+            (
+                operator.to_string(),
+                serde_json::json!(values),
+                "stringOptions",
+            )
+        };
+
+        let (operator, value, filter_type) = match operator {
+            "=" => string("="),
+            "contains" => string("contains"),
+            // The API has no string inequality, so "!=" is "none of" a single value
+            "!=" => options("none of", vec![value]),
+            "in" => options("any of", value.split(',').collect()),
+            other => anyhow::bail!(
+                "Invalid filter operator '{other}' in '{s}'. Expected one of: =, !=, in, contains"
+            ),
+        };
+
+        Ok(MetricsFilter {
+            column: column.to_string(),
+            operator,
+            value,
+            filter_type: filter_type.to_string(),
+        })
+    }
+}
+
 /// Observation type options
 #[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -121,6 +295,63 @@ impl ObservationType {
     }
 }
 
+/// Score data type options
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ScoreDataType {
+    Numeric,
+    Categorical,
+    Boolean,
+}
+
+impl ScoreDataType {
+    pub fn to_api_string(self) -> &'static str {
+        match self {
+            ScoreDataType::Numeric => "NUMERIC",
+            ScoreDataType::Categorical => "CATEGORICAL",
+            ScoreDataType::Boolean => "BOOLEAN",
+        }
+    }
+}
+
+/// A score value to record, typed according to the score's data type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreValue {
+    Numeric(f64),
+    Categorical(String),
+    Boolean(bool),
+}
+
+impl ScoreValue {
+    /// Parse a CLI value for the given data type (numeric when unspecified)
+    pub fn parse(value: &str, data_type: Option<ScoreDataType>) -> anyhow::Result<Self> {
+        match data_type.unwrap_or(ScoreDataType::Numeric) {
+            ScoreDataType::Numeric => value.parse().map(ScoreValue::Numeric).map_err(|_| {
+                anyhow::anyhow!(
+                    "Invalid numeric score value '{value}'. Use --data-type CATEGORICAL for text values"
+                )
+            }),
+            ScoreDataType::Categorical => Ok(ScoreValue::Categorical(value.to_string())),
+            ScoreDataType::Boolean => match value.to_lowercase().as_str() {
+                "true" | "1" => Ok(ScoreValue::Boolean(true)),
+                "false" | "0" => Ok(ScoreValue::Boolean(false)),
+                _ => anyhow::bail!(
+                    "Invalid boolean score value '{value}'. Expected true, false, 1 or 0"
+                ),
+            },
+        }
+    }
+
+    /// The value as sent in the request body. The API takes booleans as 1 or 0.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            ScoreValue::Numeric(n) => serde_json::json!(n),
+            ScoreValue::Categorical(s) => serde_json::json!(s),
+            ScoreValue::Boolean(b) => serde_json::json!(u8::from(*b)),
+        }
+    }
+}
+
 /// A trace from Langfuse
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -131,6 +362,7 @@ pub struct Trace {
     pub session_id: Option<String>,
     pub release: Option<String>,
     pub version: Option<String>,
+    pub environment: Option<String>,
     pub metadata: Option<serde_json::Value>,
     pub tags: Option<Vec<String>>,
     pub input: Option<serde_json::Value>,
@@ -152,6 +384,27 @@ pub struct Session {
     pub traces: Vec<Trace>,
 }
 
+/// A session with the number of traces it contains, as emitted by `sessions export`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExport {
+    pub id: String,
+    pub created_at: Option<String>,
+    pub project_id: Option<String>,
+    pub trace_count: Option<i32>,
+}
+
+/// Token, cost and count totals for one session, as emitted by `sessions metrics`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMetrics {
+    pub session_id: String,
+    pub trace_count: usize,
+    pub observation_count: usize,
+    pub total_tokens: i64,
+    pub total_cost: f64,
+}
+
 /// An observation from Langfuse
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -172,6 +425,7 @@ pub struct Observation {
     pub status_message: Option<String>,
     pub parent_observation_id: Option<String>,
     pub completion_start_time: Option<String>,
+    pub environment: Option<String>,
 }
 
 /// Usage information for an observation
@@ -248,15 +502,55 @@ pub struct PaginationMeta {
     pub total_pages: Option<i32>,
 }
 
-/// A chat message for chat prompts
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A chat message for chat prompts. A placeholder message (`type: placeholder`
+/// with a `name`) marks where a list of messages is inserted at runtime and
+/// has no role or content of its own.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
 pub struct ChatMessage {
+    #[serde(default)]
     pub role: String,
+    #[serde(default)]
     pub content: String,
+    #[serde(rename = "type", default)]
+    pub message_type: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn is_placeholder(&self) -> bool {
+        self.message_type.as_deref() == Some("placeholder")
+    }
+
+    /// The role, or `placeholder:NAME` for placeholders, for display
+    pub fn label(&self) -> String {
+        if self.is_placeholder() {
+            format!("placeholder:{}", self.name.as_deref().unwrap_or_default())
+        } else {
+            self.role.clone()
+        }
+    }
+}
+
+/// Normal messages are just `role` and `content`; placeholders just `type` and `name`
+impl Serialize for ChatMessage {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        if self.is_placeholder() {
+            map.serialize_entry("type", "placeholder")?;
+            map.serialize_entry("name", &self.name)?;
+        } else {
+            map.serialize_entry("role", &self.role)?;
+            map.serialize_entry("content", &self.content)?;
+        }
+        map.end()
+    }
 }
 
 /// Prompt content - either text or chat messages
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum PromptContent {
     Text(String),
@@ -277,10 +571,34 @@ pub struct Prompt {
     #[serde(default)]
     pub tags: Vec<String>,
     pub config: Option<serde_json::Value>,
+    pub commit_message: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// Summary of one prompt version, for choosing which to promote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptVersion {
+    pub version: i32,
+    pub labels: Vec<String>,
+    pub commit_message: Option<String>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
 
+impl From<Prompt> for PromptVersion {
+    fn from(prompt: Prompt) -> Self {
+        Self {
+            version: prompt.version,
+            labels: prompt.labels,
+            commit_message: prompt.commit_message,
+            created_at: prompt.created_at,
+            updated_at: prompt.updated_at,
+        }
+    }
+}
+
 /// Prompt metadata from list endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -295,6 +613,12 @@ pub struct PromptMeta {
     pub last_updated_at: Option<String>,
 }
 
+/// Just the pagination metadata of any list response, for counting
+#[derive(Debug, Clone, Deserialize)]
+pub struct CountResponse {
+    pub meta: Option<PaginationMeta>,
+}
+
 /// API response wrapper for prompts list
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromptsResponse {
@@ -302,6 +626,13 @@ pub struct PromptsResponse {
     pub meta: Option<PaginationMeta>,
 }
 
+/// API response wrapper for prompt versions list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVersionsResponse {
+    pub data: Vec<Prompt>,
+    pub meta: Option<PaginationMeta>,
+}
+
 /// Response from creating a score
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateScoreResponse {
@@ -374,6 +705,20 @@ pub struct DatasetItemsResponse {
     pub meta: Option<PaginationMeta>,
 }
 
+/// A dataset run item, linking a dataset item to the trace that processed it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetRunItem {
+    pub id: String,
+    pub dataset_run_id: Option<String>,
+    pub dataset_run_name: Option<String>,
+    pub dataset_item_id: Option<String>,
+    pub trace_id: Option<String>,
+    pub observation_id: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
 /// API response wrapper for dataset runs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatasetRunsResponse {
@@ -381,11 +726,67 @@ pub struct DatasetRunsResponse {
     pub meta: Option<PaginationMeta>,
 }
 
+/// A Langfuse project the API keys belong to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+}
+
+/// API response wrapper for projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectsResponse {
+    pub data: Vec<Project>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    // ========== MetricsFilter Tests ==========
+
+    #[test]
+    fn test_metrics_filter_equals() {
+        let filter = MetricsFilter::parse("name:=:chat").unwrap();
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({"column": "name", "operator": "=", "value": "chat", "type": "string"})
+        );
+    }
+
+    #[test]
+    fn test_metrics_filter_contains_keeps_colons_in_value() {
+        let filter = MetricsFilter::parse("name:contains:chat:v2").unwrap();
+        assert_eq!(filter.operator, "contains");
+        assert_eq!(filter.value, json!("chat:v2"));
+    }
+
+    #[test]
+    fn test_metrics_filter_in_and_not_equals_use_options() {
+        let filter = MetricsFilter::parse("userId:in:a,b").unwrap();
+        assert_eq!(
+            serde_json::to_value(&filter).unwrap(),
+            json!({"column": "userId", "operator": "any of", "value": ["a", "b"], "type": "stringOptions"})
+        );
+
+        let filter = MetricsFilter::parse("environment:!=:dev").unwrap();
+        assert_eq!(filter.operator, "none of");
+        assert_eq!(filter.value, json!(["dev"]));
+    }
+
+    #[test]
+    fn test_metrics_filter_rejects_malformed() {
+        let err = MetricsFilter::parse("name=chat").unwrap_err();
+        assert!(err.to_string().contains("Expected FIELD:OPERATOR:VALUE"));
+
+        let err = MetricsFilter::parse(":=:chat").unwrap_err();
+        assert!(err.to_string().contains("field name is empty"));
+
+        let err = MetricsFilter::parse("name:like:chat").unwrap_err();
+        assert!(err.to_string().contains("Invalid filter operator 'like'"));
+    }
+
     // ========== OutputFormat Tests ==========
 
     #[test]
@@ -404,6 +805,10 @@ mod tests {
             serde_json::to_string(&OutputFormat::Json).unwrap(),
             "\"json\""
         );
+        assert_eq!(
+            serde_json::to_string(&OutputFormat::Jsonl).unwrap(),
+            "\"jsonl\""
+        );
         assert_eq!(
             serde_json::to_string(&OutputFormat::Csv).unwrap(),
             "\"csv\""
@@ -432,6 +837,65 @@ mod tests {
             serde_json::from_str::<OutputFormat>("\"markdown\"").unwrap(),
             OutputFormat::Markdown
         );
+        assert_eq!(
+            serde_json::from_str::<OutputFormat>("\"ndjson\"").unwrap(),
+            OutputFormat::Jsonl
+        );
+    }
+
+    #[test]
+    fn test_output_format_ndjson_alias() {
+        assert_eq!(
+            OutputFormat::from_str("ndjson", true).unwrap(),
+            OutputFormat::Jsonl
+        );
+        assert_eq!(
+            OutputFormat::from_str("jsonl", true).unwrap(),
+            OutputFormat::Jsonl
+        );
+    }
+
+    // ========== ScoreValue Tests ==========
+
+    #[test]
+    fn test_score_value_numeric() {
+        let value = ScoreValue::parse("0.95", Some(ScoreDataType::Numeric)).unwrap();
+        assert_eq!(value, ScoreValue::Numeric(0.95));
+        assert_eq!(value.to_json(), serde_json::json!(0.95));
+
+        // Numeric is the default when no data type is given
+        assert_eq!(
+            ScoreValue::parse("2", None).unwrap(),
+            ScoreValue::Numeric(2.0)
+        );
+    }
+
+    #[test]
+    fn test_score_value_categorical() {
+        let value = ScoreValue::parse("good", Some(ScoreDataType::Categorical)).unwrap();
+        assert_eq!(value, ScoreValue::Categorical("good".to_string()));
+        assert_eq!(value.to_json(), serde_json::json!("good"));
+    }
+
+    #[test]
+    fn test_score_value_boolean() {
+        let yes = ScoreValue::parse("true", Some(ScoreDataType::Boolean)).unwrap();
+        let no = ScoreValue::parse("0", Some(ScoreDataType::Boolean)).unwrap();
+        assert_eq!(yes, ScoreValue::Boolean(true));
+        assert_eq!(no, ScoreValue::Boolean(false));
+        assert_eq!(yes.to_json(), serde_json::json!(1));
+        assert_eq!(no.to_json(), serde_json::json!(0));
+    }
+
+    #[test]
+    fn test_score_value_invalid() {
+        let err = ScoreValue::parse("good", None).unwrap_err().to_string();
+        assert!(err.contains("Invalid numeric score value 'good'"));
+
+        let err = ScoreValue::parse("maybe", Some(ScoreDataType::Boolean))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid boolean score value 'maybe'"));
     }
 
     // ========== Measure Tests ==========
@@ -587,6 +1051,7 @@ mod tests {
             session_id: None,
             release: None,
             version: None,
+            environment: None,
             metadata: None,
             tags: Some(vec!["tag1".to_string()]),
             input: None,
@@ -966,6 +1431,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chat_prompt_with_placeholder_deserialize() {
+        let json = json!({
+            "name": "assistant",
+            "version": 2,
+            "type": "chat",
+            "prompt": [
+                {"role": "system", "content": "You are helpful.", "type": "chatmessage"},
+                {"type": "placeholder", "name": "history"}
+            ]
+        });
+
+        let prompt: Prompt = serde_json::from_value(json).unwrap();
+
+        let PromptContent::Chat(msgs) = &prompt.prompt else {
+            panic!("Expected chat prompt");
+        };
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].role, "system");
+        assert!(!msgs[0].is_placeholder());
+        assert!(msgs[1].is_placeholder());
+        assert_eq!(msgs[1].name.as_deref(), Some("history"));
+        assert_eq!(msgs[1].label(), "placeholder:history");
+
+        // Round-trips with each kind of message keeping only its own fields
+        assert_eq!(
+            serde_json::to_value(&prompt.prompt).unwrap(),
+            json!([
+                {"role": "system", "content": "You are helpful."},
+                {"type": "placeholder", "name": "history"}
+            ])
+        );
+    }
+
     #[test]
     fn test_prompt_meta_deserialize() {
         let json = json!({
@@ -0,0 +1,55 @@
+// ABOUTME: Runs the lf binary with NO_COLOR set on the child process, so the
+// ABOUTME: environment of the test process itself is never modified
+
+use std::process::Command;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// `traces list` as a table, with NO_COLOR set to `no_color` (or unset)
+fn traces_table(host: &str, no_color: Option<&str>) -> String {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_lf"));
+    cmd.args([
+        "traces",
+        "list",
+        "--format",
+        "table",
+        "--host",
+        host,
+        "--public-key",
+        "pk-test",
+        "--secret-key",
+        "sk-test",
+    ]);
+    cmd.env_remove("NO_COLOR");
+    if let Some(value) = no_color {
+        cmd.env("NO_COLOR", value);
+    }
+
+    let output = cmd.output().unwrap();
+    assert!(
+        output.status.success(),
+        "lf failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[tokio::test]
+async fn test_no_color_env_accepts_any_value() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/public/traces"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{"id": "trace-1", "name": "chat"}],
+            "meta": {"page": 1, "limit": 50, "totalItems": 1, "totalPages": 1}
+        })))
+        .mount(&mock_server)
+        .await;
+    let host = mock_server.uri();
+
+    // Plain ASCII borders with colour off, rounded ones otherwise
+    assert!(traces_table(&host, Some("1")).starts_with('+'));
+    assert!(traces_table(&host, Some("true")).starts_with('+'));
+    assert!(traces_table(&host, Some("")).starts_with('╭'));
+    assert!(traces_table(&host, None).starts_with('╭'));
+}